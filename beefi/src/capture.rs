@@ -1,6 +1,7 @@
 use beefi_lib::{
-    create_live_capture, extract_from_pcap, to_bfm, BfiFile, BfmData, FileContentType, HoneySink,
-    NectarSink, PollenSink, StreamBee, Writer,
+    create_live_capture, extract_cqi_from_pcap, extract_from_pcap, to_bfm_batch, to_bfm_batch_as,
+    BfiFile, BfmData, FileContentType, HoneySink, NectarSink, PollenSink, StreamBee, WaxSink,
+    Writer,
 };
 
 use std::path::PathBuf;
@@ -16,9 +17,14 @@ pub fn run_online_capture(args: OnlineCaptureArgs) {
         interface,
         pcap_out,
         bfa_out,
+        bfa_radians,
         bfm_out,
+        bfm_single,
+        bfm_workers,
+        cqi_out,
         format,
         print,
+        fcs_policy,
         pcap_snaplen,
         pcap_buffered,
         pcap_bufsize,
@@ -43,12 +49,13 @@ pub fn run_online_capture(args: OnlineCaptureArgs) {
         pcap_snaplen,
         pcap_bufsize,
     );
+    bee.set_fcs_policy(fcs_policy);
 
     if let Some(bfa_out_path) = bfa_out {
         let processed_sink = NectarSink::File(BfiFile {
             file_path: bfa_out_path,
             file_type: format,
-            file_content_type: FileContentType::Bfa,
+            file_content_type: bfa_content_type(bfa_radians),
         });
         bee.subscribe_for_nectar(processed_sink);
     }
@@ -57,11 +64,21 @@ pub fn run_online_capture(args: OnlineCaptureArgs) {
         let processed_sink = HoneySink::File(BfiFile {
             file_path: bfm_out_path,
             file_type: format,
-            file_content_type: FileContentType::Bfm,
+            file_content_type: bfm_content_type(bfm_single),
         });
+        bee.set_honey_workers(bfm_workers);
         bee.subscribe_for_honey(processed_sink);
     }
 
+    if let Some(cqi_out_path) = cqi_out {
+        let processed_sink = WaxSink::File(BfiFile {
+            file_path: cqi_out_path,
+            file_type: format,
+            file_content_type: FileContentType::Cqi,
+        });
+        bee.subscribe_for_wax(processed_sink);
+    }
+
     // Start capturing
     bee.start_harvesting(print);
 
@@ -76,7 +93,7 @@ pub fn run_online_capture(args: OnlineCaptureArgs) {
 }
 
 pub fn run_offline_capture(args: OfflineCaptureArgs) {
-    let data = extract_from_pcap(args.pcap_in);
+    let data = extract_from_pcap(args.pcap_in.clone(), args.fcs_policy);
 
     if args.print {
         println!("Data read: {:?}", data);
@@ -86,7 +103,7 @@ pub fn run_offline_capture(args: OfflineCaptureArgs) {
         let file = BfiFile {
             file_path: file,
             file_type: args.format,
-            file_content_type: FileContentType::Bfa,
+            file_content_type: bfa_content_type(args.bfa_radians),
         };
         let mut writer = Writer::new(file).unwrap();
         writer.add_bfa_batch(&data).unwrap();
@@ -97,19 +114,56 @@ pub fn run_offline_capture(args: OfflineCaptureArgs) {
         let file = BfiFile {
             file_path: file,
             file_type: args.format,
-            file_content_type: FileContentType::Bfm,
+            file_content_type: bfm_content_type(args.bfm_single),
         };
         let mut writer = Writer::new(file).unwrap();
-        let bfm: Vec<BfmData> = data
-            .iter()
-            .map(|bfa| to_bfm(bfa).expect("conversion to BFM failed"))
-            .collect();
+        if args.bfm_single {
+            let bfm: Vec<BfmData<f32>> = to_bfm_batch_as(&data)
+                .into_iter()
+                .map(|bfm| bfm.expect("conversion to BFM failed"))
+                .collect();
+            writer.add_bfm_batch(&bfm).unwrap();
+        } else {
+            let bfm: Vec<BfmData> = to_bfm_batch(&data)
+                .into_iter()
+                .map(|bfm| bfm.expect("conversion to BFM failed"))
+                .collect();
+            writer.add_bfm_batch(&bfm).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
 
-        writer.add_bfm_batch(&bfm).unwrap();
+    if let Some(file) = args.cqi_out {
+        let file = BfiFile {
+            file_path: file,
+            file_type: args.format,
+            file_content_type: FileContentType::Cqi,
+        };
+        let mut writer = Writer::new(file).unwrap();
+        let cqi = extract_cqi_from_pcap(args.pcap_in, args.fcs_policy);
+        writer.add_cqi_batch(&cqi).unwrap();
         writer.finalize().unwrap();
     }
 }
 
+/// Content type of the angle output (quantized values or radians)
+fn bfa_content_type(radians: bool) -> FileContentType {
+    if radians {
+        FileContentType::BfaRadians
+    } else {
+        FileContentType::Bfa
+    }
+}
+
+/// Content type of the matrix output (double or single precision)
+fn bfm_content_type(single: bool) -> FileContentType {
+    if single {
+        FileContentType::BfmSingle
+    } else {
+        FileContentType::Bfm
+    }
+}
+
 /// Creates a `CaptureBee` object based on the specified interface or input file.
 /// If `pcap_out` is provided, sets the capture to write raw packets to the given file.
 fn create_bee(
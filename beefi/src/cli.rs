@@ -1,4 +1,4 @@
-use beefi_lib::FileType;
+use beefi_lib::{FcsPolicy, FileType};
 use clap::{ArgGroup, Parser, Subcommand};
 use simplelog::LevelFilter;
 use std::path::PathBuf;
@@ -27,7 +27,7 @@ pub enum Commands {
 }
 
 #[derive(Parser)]
-#[command(group = ArgGroup::new("output").required(true).multiple(true).args(&["pcap_out", "bfa_out", "bfm_out", "print"]))]
+#[command(group = ArgGroup::new("output").required(true).multiple(true).args(&["pcap_out", "bfa_out", "bfm_out", "cqi_out", "print"]))]
 pub struct OnlineCaptureArgs {
     /// Network interface to capture from
     #[arg(long)]
@@ -41,10 +41,26 @@ pub struct OnlineCaptureArgs {
     #[arg(short, long)]
     pub bfa_out: Option<PathBuf>,
 
+    /// Write the angles of `bfa_out` dequantized to radians
+    #[arg(long, default_value = "false", requires = "bfa_out")]
+    pub bfa_radians: bool,
+
     /// Output file for converted beamforming matrices
     #[arg(long)]
     pub bfm_out: Option<PathBuf>,
 
+    /// Write the matrices of `bfm_out` in single precision
+    #[arg(long, default_value = "false", requires = "bfm_out")]
+    pub bfm_single: bool,
+
+    /// Number of threads converting angles to matrices (0: capture thread)
+    #[arg(long, default_value = "0", requires = "bfm_out")]
+    pub bfm_workers: usize,
+
+    /// Output file for extracted channel quality (CQI) reports
+    #[arg(long)]
+    pub cqi_out: Option<PathBuf>,
+
     /// Specify output format, e.g., 'parquet'
     #[arg(long, default_value = "parquet")]
     pub format: FileType,
@@ -53,6 +69,10 @@ pub struct OnlineCaptureArgs {
     #[arg(long, default_value = "false")]
     pub print: bool,
 
+    /// How to treat the FCS of frames (ignore, flag, drop)
+    #[arg(long, default_value = "ignore")]
+    pub fcs_policy: FcsPolicy,
+
     /// PCap snapshot size for internal buffer
     #[arg(long, default_value = "4096")]
     pub pcap_snaplen: i32,
@@ -66,7 +86,7 @@ pub struct OnlineCaptureArgs {
 }
 
 #[derive(Parser)]
-#[command(group = ArgGroup::new("output").required(true).multiple(true).args(&["bfa_out", "bfm_out", "print"]))]
+#[command(group = ArgGroup::new("output").required(true).multiple(true).args(&["bfa_out", "bfm_out", "cqi_out", "print"]))]
 pub struct OfflineCaptureArgs {
     /// Read data from existing pcap file
     #[arg(long)]
@@ -76,10 +96,22 @@ pub struct OfflineCaptureArgs {
     #[arg(short, long)]
     pub bfa_out: Option<PathBuf>,
 
+    /// Write the angles of `bfa_out` dequantized to radians
+    #[arg(long, default_value = "false", requires = "bfa_out")]
+    pub bfa_radians: bool,
+
     /// Output file for extracted feedback matrices
     #[arg(long)]
     pub bfm_out: Option<PathBuf>,
 
+    /// Write the matrices of `bfm_out` in single precision
+    #[arg(long, default_value = "false", requires = "bfm_out")]
+    pub bfm_single: bool,
+
+    /// Output file for extracted channel quality (CQI) reports
+    #[arg(long)]
+    pub cqi_out: Option<PathBuf>,
+
     /// Specify output format, e.g., 'parquet'
     #[arg(long, default_value = "parquet")]
     pub format: FileType,
@@ -87,6 +119,10 @@ pub struct OfflineCaptureArgs {
     /// Whether to print processed data
     #[arg(long, default_value = "false")]
     pub print: bool,
+
+    /// How to treat the FCS of frames (ignore, flag, drop)
+    #[arg(long, default_value = "ignore")]
+    pub fcs_policy: FcsPolicy,
 }
 
 #[derive(Parser)]
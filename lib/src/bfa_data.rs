@@ -1,15 +1,61 @@
 //! BFI/BFA data structs used throughout the library.
 
+use crate::eht_mimo_ctrl::EhtMimoControl;
 use crate::he_mimo_ctrl::HeMimoControl;
+use crate::ht_mimo_ctrl::HtMimoControl;
+use crate::vht_mimo_ctrl::VhtMimoControl;
+use crate::MacHeader;
+use bilge::prelude::*;
+use ndarray::Array2;
+
+#[cfg(feature = "ndpa")]
+use crate::Ndpa;
+#[cfg(feature = "radio_metadata")]
+use crate::RadioMetadata;
+
+/// WiFi standard (PHY generation) of a beamforming feedback report
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum WifiStandard {
+    /// 802.11n
+    Ht,
+    /// 802.11ac
+    Vht,
+    /// 802.11ax
+    He,
+    /// 802.11be
+    Eht,
+}
+
+impl TryFrom<u8> for WifiStandard {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(WifiStandard::Ht),
+            1 => Ok(WifiStandard::Vht),
+            2 => Ok(WifiStandard::He),
+            3 => Ok(WifiStandard::Eht),
+            _ => Err(format!("Invalid WiFi standard: {}", value)),
+        }
+    }
+}
 
 /// Metadata extracted from a single WiFi packet.
 #[derive(Debug, Clone)]
 pub struct BfiMetadata {
+    pub standard: WifiStandard,
     pub bandwidth: u16,
     pub nr_index: u8,
     pub nc_index: u8,
     pub codebook_info: u8,
     pub feedback_type: u8,
+    /// EHT partial bandwidth info (resolution bit and subchannel bitmap)
+    pub partial_bw_info: Option<u16>,
+    /// HE partial bandwidth feedback: first RU26 of the reported span
+    pub ru_start_index: Option<u8>,
+    /// HE partial bandwidth feedback: last RU26 of the reported span
+    pub ru_end_index: Option<u8>,
 }
 
 impl BfiMetadata {
@@ -20,11 +66,73 @@ impl BfiMetadata {
     /// * `header` - The he mimo control header
     pub fn from_mimo_ctrl_header(header: &HeMimoControl) -> Self {
         Self {
+            standard: WifiStandard::He,
             bandwidth: header.bandwidth().to_mhz(),
             nr_index: header.nr_index().into(),
             nc_index: header.nc_index().into(),
             codebook_info: header.codebook_info().into(),
             feedback_type: header.feedback_type().into(),
+            partial_bw_info: None,
+            ru_start_index: Some(header.ru_start_index().into()),
+            ru_end_index: Some(header.ru_end_index().into()),
+        }
+    }
+
+    /// Extract metadata from a VHT Mimo Control packet header
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The vht mimo control header
+    pub fn from_vht_mimo_ctrl_header(header: &VhtMimoControl) -> Self {
+        Self {
+            standard: WifiStandard::Vht,
+            bandwidth: header.bandwidth().to_mhz(),
+            nr_index: header.nr_index().into(),
+            nc_index: header.nc_index().into(),
+            codebook_info: header.codebook_info().into(),
+            feedback_type: header.feedback_type().into(),
+            partial_bw_info: None,
+            ru_start_index: None,
+            ru_end_index: None,
+        }
+    }
+
+    /// Extract metadata from an EHT Mimo Control packet header
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The eht mimo control header
+    pub fn from_eht_mimo_ctrl_header(header: &EhtMimoControl) -> Self {
+        Self {
+            standard: WifiStandard::Eht,
+            bandwidth: header.bandwidth().to_mhz(),
+            nr_index: header.nr_index().into(),
+            nc_index: header.nc_index().into(),
+            codebook_info: header.codebook_info().into(),
+            feedback_type: header.feedback_type().into(),
+            partial_bw_info: Some(UInt::<u16, 9>::from(header.partial_bw_info()).value()),
+            ru_start_index: None,
+            ru_end_index: None,
+        }
+    }
+
+    /// Extract metadata from a HT Mimo Control packet header
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The ht mimo control header
+    pub fn from_ht_mimo_ctrl_header(header: &HtMimoControl) -> Self {
+        Self {
+            standard: WifiStandard::Ht,
+            bandwidth: header.bandwidth_mhz(),
+            nr_index: header.nr_index().into(),
+            nc_index: header.nc_index().into(),
+            codebook_info: header.codebook_info().into(),
+            // NOTE: HT only knows single user feedback
+            feedback_type: 0,
+            partial_bw_info: None,
+            ru_start_index: None,
+            ru_end_index: None,
         }
     }
 }
@@ -34,9 +142,55 @@ impl BfiMetadata {
 pub struct BfaData {
     #[cfg(feature = "bfi_metadata")]
     pub metadata: BfiMetadata,
+    /// Reception information from the link layer header
+    #[cfg(feature = "radio_metadata")]
+    pub radio: RadioMetadata,
     pub timestamp: f64,
+    /// Addressing and sequence information of the feedback frame
+    pub mac_header: MacHeader,
+    /// Result of the FCS verification (`None` if not verified)
+    pub fcs_valid: Option<bool>,
+    /// NDP Announcement that solicited the report (if captured)
+    #[cfg(feature = "ndpa")]
+    pub ndpa: Option<Ndpa>,
     pub token_number: u8,
-    pub bfa_angles: Vec<Vec<u16>>,
+    /// Average SNR (in dB) of each space-time stream
+    pub snr: Vec<f32>,
+    /// Delta SNR (in dB) per stream and subcarrier from the MU Exclusive
+    /// Beamforming Report (only present for MU feedback)
+    pub delta_snr: Option<Array2<i8>>,
+    /// Tone index of every reported subcarrier (if known for the standard)
+    pub subcarrier_indices: Option<Vec<i16>>,
+    /// Quantized angles of shape (num_subcarrier, num_angles)
+    pub bfa_angles: Array2<u16>,
+}
+
+impl BfaData {
+    /// Create BFA data carrying nothing but the angles of a report
+    ///
+    /// This allows converting angles obtained from another source.
+    ///
+    /// # Parameters
+    /// * `metadata` - Configuration of the report
+    /// * `bfa_angles` - Quantized angles of shape (num_subcarrier, num_angles)
+    #[cfg(feature = "bfi_metadata")]
+    pub fn from_angles(metadata: BfiMetadata, bfa_angles: Array2<u16>) -> Self {
+        BfaData {
+            metadata,
+            #[cfg(feature = "radio_metadata")]
+            radio: RadioMetadata::default(),
+            timestamp: 0.0,
+            mac_header: MacHeader::default(),
+            fcs_valid: None,
+            #[cfg(feature = "ndpa")]
+            ndpa: None,
+            token_number: 0,
+            snr: Vec::new(),
+            delta_snr: None,
+            subcarrier_indices: None,
+            bfa_angles,
+        }
+    }
 }
 
 /// Batch type for the above data.
@@ -47,9 +201,18 @@ pub struct BfaData {
 pub struct BfaDataBatch {
     #[cfg(feature = "bfi_metadata")]
     pub metadata: Vec<BfiMetadata>,
+    #[cfg(feature = "radio_metadata")]
+    pub radio: Vec<RadioMetadata>,
     pub timestamps: Vec<f64>,
+    pub mac_header: Vec<MacHeader>,
+    pub fcs_valid: Vec<Option<bool>>,
+    #[cfg(feature = "ndpa")]
+    pub ndpa: Vec<Option<Ndpa>>,
     pub token_numbers: Vec<u8>,
-    pub bfa_angles: Vec<Vec<Vec<u16>>>,
+    pub snr: Vec<Vec<f32>>,
+    pub delta_snr: Vec<Option<Array2<i8>>>,
+    pub subcarrier_indices: Vec<Option<Vec<i16>>>,
+    pub bfa_angles: Vec<Array2<u16>>,
 }
 
 /// Split a vector of BFI data into the BFI batch type
@@ -57,16 +220,34 @@ pub fn split_bfi_data(input: Vec<BfaData>) -> BfaDataBatch {
     // Initialize vectors for each field
     #[cfg(feature = "bfi_metadata")]
     let mut metadata = Vec::with_capacity(input.len());
+    #[cfg(feature = "radio_metadata")]
+    let mut radio = Vec::with_capacity(input.len());
     let mut timestamps = Vec::with_capacity(input.len());
+    let mut mac_header = Vec::with_capacity(input.len());
+    let mut fcs_valid = Vec::with_capacity(input.len());
+    #[cfg(feature = "ndpa")]
+    let mut ndpa = Vec::with_capacity(input.len());
     let mut token_numbers = Vec::with_capacity(input.len());
+    let mut snr = Vec::with_capacity(input.len());
+    let mut delta_snr = Vec::with_capacity(input.len());
+    let mut subcarrier_indices = Vec::with_capacity(input.len());
     let mut bfa_angles = Vec::with_capacity(input.len());
 
     // Populate each vector by iterating over the input data
     for data in input {
         #[cfg(feature = "bfi_metadata")]
         metadata.push(data.metadata);
+        #[cfg(feature = "radio_metadata")]
+        radio.push(data.radio);
         timestamps.push(data.timestamp);
+        mac_header.push(data.mac_header);
+        fcs_valid.push(data.fcs_valid);
+        #[cfg(feature = "ndpa")]
+        ndpa.push(data.ndpa);
         token_numbers.push(data.token_number);
+        snr.push(data.snr);
+        delta_snr.push(data.delta_snr);
+        subcarrier_indices.push(data.subcarrier_indices);
         bfa_angles.push(data.bfa_angles);
     }
 
@@ -74,8 +255,17 @@ pub fn split_bfi_data(input: Vec<BfaData>) -> BfaDataBatch {
     BfaDataBatch {
         #[cfg(feature = "bfi_metadata")]
         metadata,
+        #[cfg(feature = "radio_metadata")]
+        radio,
         timestamps,
+        mac_header,
+        fcs_valid,
+        #[cfg(feature = "ndpa")]
+        ndpa,
         token_numbers,
+        snr,
+        delta_snr,
+        subcarrier_indices,
         bfa_angles,
     }
 }
@@ -1,73 +1,308 @@
 //! Beamforming Feedback Angles (BFA) to Beamforming Feedback Matrix (BFM) conversion.
 //!
-//! TODO: More references to equations in the standard.
-use crate::bfm_data::{BfmData, FeedbackMatrix};
-use crate::extraction::{get_angle_bit_sizes, Angles, ExtractionConfig};
-use crate::{errors::BfmConversionError, BfaData};
+//! The feedback matrix is reconstructed from its Givens decomposition (see
+//! IEEE 802.11-2016 19.3.12.3.6, Equation 19-79), multiplying the diagonal
+//! phase matrices and Givens rotations in the order in which the angles are
+//! reported (see `ExtractionConfig::get_pattern`). The inverse direction
+//! decomposes a matrix into quantized angles again (see `to_bfa`).
+use crate::bfm_data::{BfmData, FeedbackMatrix, Precision};
+use crate::errors::{BfaExtractionError, BfmConversionError};
+use crate::extraction::{angle_bit_sizes, Angles, CompressedAngleBitSizes, ExtractionConfig};
+use crate::BfaData;
 
-use ndarray::Array2;
-use num_complex::Complex64;
+use ndarray::{s, Array2, Array3};
+use num_complex::{Complex, Complex64};
+use std::f64::consts::PI;
+use std::thread;
+
+/// Quantization codebook of the Phi and Psi angles
+///
+/// Quantized angles map to the center of their interval, i.e.
+/// phi = k * PI / 2^(b-1) + PI / 2^b and psi = k * PI / 2^(b+1) + PI / 2^(b+2).
+/// Step and offset of both angle types are computed once per codebook.
+struct AngleCodebook {
+    phi_bit: u8,
+    psi_bit: u8,
+    phi_step_offset: (f64, f64),
+    psi_step_offset: (f64, f64),
+}
+
+impl AngleCodebook {
+    /// Step and offset of the quantization of an angle type
+    fn step_offset(&self, kind: Angles) -> (f64, f64) {
+        match kind {
+            Angles::Phi => self.phi_step_offset,
+            Angles::Psi => self.psi_step_offset,
+        }
+    }
+
+    /// Angle (in radians) of a quantized value
+    fn dequantize(&self, kind: Angles, value: u16) -> f64 {
+        let (step, offset) = self.step_offset(kind);
+        value as f64 * step + offset
+    }
+
+    /// Closest quantized value of an angle (in radians)
+    ///
+    /// Phi wraps around at 2 PI, Psi is clamped to its range [0, PI / 2].
+    fn quantize(&self, kind: Angles, angle: f64) -> u16 {
+        let (step, offset) = self.step_offset(kind);
+        let value = ((angle - offset) / step).round() as i64;
+        match kind {
+            Angles::Phi => value.rem_euclid(1 << self.phi_bit) as u16,
+            Angles::Psi => value.clamp(0, (1 << self.psi_bit) - 1) as u16,
+        }
+    }
+}
+
+impl From<CompressedAngleBitSizes> for AngleCodebook {
+    fn from(bitsizes: CompressedAngleBitSizes) -> Self {
+        let (phi_bit, psi_bit) = (bitsizes.phi_bit, bitsizes.psi_bit);
+        Self {
+            phi_bit,
+            psi_bit,
+            phi_step_offset: (
+                PI / ((1u64 << (phi_bit - 1)) as f64),
+                PI / ((1u64 << phi_bit) as f64),
+            ),
+            psi_step_offset: (
+                PI / ((1u64 << (psi_bit + 1)) as f64),
+                PI / ((1u64 << (psi_bit + 2)) as f64),
+            ),
+        }
+    }
+}
+
+/// Dequantize BFI angles to radians.
+///
+/// Maps every quantized Phi/Psi value to its angle with the codebook that
+/// `to_bfm` uses, without reconstructing the matrices. The result has the
+/// shape of `bfa_angles`, i.e. (num_subcarrier, num_angles).
+pub fn dequantize(bfa: &BfaData) -> Result<Array2<f64>, BfmConversionError> {
+    let pattern = ExtractionConfig::get_pattern(bfa.metadata.nr_index, bfa.metadata.nc_index)?;
+    let codebook = AngleCodebook::from(angle_bit_sizes(&bfa.metadata)?);
+
+    if bfa.bfa_angles.ncols() != pattern.len() {
+        return Err(BfaExtractionError::InvalidAngleCount {
+            expected: pattern.len(),
+            given: bfa.bfa_angles.ncols(),
+        }
+        .into());
+    }
+
+    Ok(Array2::from_shape_fn(bfa.bfa_angles.dim(), |(sub, i)| {
+        codebook.dequantize(pattern[i].0, bfa.bfa_angles[(sub, i)])
+    }))
+}
+
+/// Dequantize the BFI angles of a batch of reports to radians.
+///
+/// See `dequantize`; reports are converted independently.
+pub fn dequantize_batch(data: &[BfaData]) -> Vec<Result<Array2<f64>, BfmConversionError>> {
+    data.iter().map(dequantize).collect()
+}
 
 /// Transform BFI angles to Beamforming Feedback Matrix (BFM).
 ///
 /// Metadata is maintained, this just receovers the matrices from the angles.
 pub fn to_bfm(bfa: &BfaData) -> Result<BfmData, BfmConversionError> {
-    // Retrieve pattern and bit-size parameters.
-    let pattern = ExtractionConfig::get_pattern(bfa.metadata.nr_index, bfa.metadata.nc_index)?;
+    to_bfm_as(bfa)
+}
+
+/// Transform BFI angles to a Beamforming Feedback Matrix (BFM) of the given precision.
+///
+/// The matrices are computed in double precision and stored as `T`, e.g.
+/// `to_bfm_as::<f32>` halves the memory of the result.
+pub fn to_bfm_as<T: Precision>(bfa: &BfaData) -> Result<BfmData<T>, BfmConversionError> {
+    to_bfm_with_scratch(bfa, &mut Array2::zeros((0, 0)))
+}
+
+/// Transform a batch of BFI angles to Beamforming Feedback Matrices (BFM).
+///
+/// The batch is split into contiguous chunks which are converted on all
+/// available cores. Results are returned in the order of the input.
+pub fn to_bfm_batch(data: &[BfaData]) -> Vec<Result<BfmData, BfmConversionError>> {
+    to_bfm_batch_as(data)
+}
 
-    // Compute the bit-size constants once.
-    let bitsizes = get_angle_bit_sizes(bfa.metadata.codebook_info, bfa.metadata.feedback_type)?;
-    let const1_phi = std::f64::consts::PI / ((1u64 << (bitsizes.phi_bit - 1)) as f64);
-    let const2_phi = std::f64::consts::PI / ((1u64 << bitsizes.phi_bit) as f64);
-    let const1_psi = std::f64::consts::PI / ((1u64 << (bitsizes.psi_bit + 1)) as f64);
-    let const2_psi = std::f64::consts::PI / ((1u64 << (bitsizes.psi_bit + 2)) as f64);
+/// Transform a batch of BFI angles to BFM of the given precision.
+///
+/// See `to_bfm_batch` and `to_bfm_as`.
+pub fn to_bfm_batch_as<T: Precision>(
+    data: &[BfaData],
+) -> Vec<Result<BfmData<T>, BfmConversionError>> {
+    let num_threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = data.len().div_ceil(num_threads).max(1);
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = data
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut scratch = Array2::zeros((0, 0));
+                    chunk
+                        .iter()
+                        .map(|bfa| to_bfm_with_scratch(bfa, &mut scratch))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("BFM conversion thread panicked"))
+            .collect()
+    })
+}
+
+/// Transform BFI angles to BFM, accumulating the matrices in `scratch`.
+///
+/// The scratch matrix is resized to the number of receive antennas if
+/// required, such that consecutive conversions reuse its allocation.
+pub(crate) fn to_bfm_with_scratch<T: Precision>(
+    bfa: &BfaData,
+    scratch: &mut Array2<Complex64>,
+) -> Result<BfmData<T>, BfmConversionError> {
+    // Retrieve pattern and codebook parameters.
+    let pattern = ExtractionConfig::get_pattern(bfa.metadata.nr_index, bfa.metadata.nc_index)?;
+    let codebook = AngleCodebook::from(angle_bit_sizes(&bfa.metadata)?);
 
     let num_receive = bfa.metadata.nr_index as usize + 1;
     let num_spatial = bfa.metadata.nc_index as usize + 1;
-    let n_subcarriers = bfa.bfa_angles.len();
+    let n_subcarriers = bfa.bfa_angles.nrows();
+
+    if bfa.bfa_angles.ncols() < pattern.len() {
+        return Err(BfaExtractionError::InvalidAngleCount {
+            expected: pattern.len(),
+            given: bfa.bfa_angles.ncols(),
+        }
+        .into());
+    }
+    if scratch.dim() != (num_receive, num_receive) {
+        *scratch = Array2::zeros((num_receive, num_receive));
+    }
 
     // Preallocate final matrix.
-    let mut final_result = FeedbackMatrix::zeros((num_receive, num_spatial, n_subcarriers));
+    let zero = Complex::new(T::from_f64(0.0), T::from_f64(0.0));
+    let mut final_result: FeedbackMatrix<T> =
+        Array3::from_elem((num_receive, num_spatial, n_subcarriers), zero);
 
     // Process each subcarrier.
-    for (sub_idx, inner_angles) in bfa.bfa_angles.iter().enumerate() {
-        let acc = pattern.iter().enumerate().fold(
-            Array2::<Complex64>::eye(num_receive),
-            |mut acc, (i, &(ref kind, nr, nc))| {
-                // 1. Compute quantized value on the fly.
-                let angle = inner_angles[i] as f64;
-                let quantized = match kind {
-                    Angles::Phi => angle * const1_phi + const2_phi,
-                    Angles::Psi => angle * const1_psi + const2_psi,
-                };
-
-                // 2. Figure out which angle is next in the multiplication
-                let row = nr - 1;
-                let col = nc - 1;
-                match kind {
-                    // 3. Multiply by either (part of) D or givens rotation
-                    Angles::Phi => apply_d_inplace(&mut acc, row, quantized),
-                    Angles::Psi => apply_givens_inplace(&mut acc, row, col, quantized),
-                };
-                acc
-            },
-        );
+    for (sub_idx, inner_angles) in bfa.bfa_angles.rows().into_iter().enumerate() {
+        // Reset the accumulator to the identity
+        scratch.fill(Complex64::ZERO);
+        scratch.diag_mut().fill(Complex64::ONE);
+
+        for (&angle, &(kind, nr, nc)) in inner_angles.iter().zip(pattern) {
+            // 1. Compute quantized value on the fly.
+            let quantized = codebook.dequantize(kind, angle);
+
+            // 2. Figure out which angle is next in the multiplication
+            let row = nr - 1;
+            let col = nc - 1;
+            match kind {
+                // 3. Multiply by either (part of) D or givens rotation
+                Angles::Phi => apply_d_inplace(scratch, row, quantized),
+                Angles::Psi => apply_givens_inplace(scratch, row, col, quantized),
+            };
+        }
 
         // Slice last num_spatial rows (application of non-square identity) and
         // put the matrix into the subcarrier dimension it belongs to.
-        final_result
-            .slice_mut(ndarray::s![.., .., sub_idx])
-            .assign(&acc.slice(ndarray::s![.., 0..num_spatial]));
+        final_result.slice_mut(s![.., .., sub_idx]).zip_mut_with(
+            &scratch.slice(s![.., 0..num_spatial]),
+            |out, value| {
+                *out = Complex::new(T::from_f64(value.re), T::from_f64(value.im));
+            },
+        );
     }
 
     Ok(BfmData {
         metadata: bfa.metadata.clone(),
+        #[cfg(feature = "radio_metadata")]
+        radio: bfa.radio.clone(),
         timestamp: bfa.timestamp,
+        mac_header: bfa.mac_header,
+        fcs_valid: bfa.fcs_valid,
+        #[cfg(feature = "ndpa")]
+        ndpa: bfa.ndpa.clone(),
         token_number: bfa.token_number,
+        snr: bfa.snr.clone(),
+        delta_snr: bfa.delta_snr.clone(),
+        subcarrier_indices: bfa.subcarrier_indices.clone(),
         feedback_matrix: final_result,
     })
 }
 
+/// Transform a Beamforming Feedback Matrix (BFM) back to quantized BFI angles.
+///
+/// This is the inverse of `to_bfm`: the matrix of every subcarrier is
+/// decomposed into Givens rotations and the resulting angles are quantized
+/// with the codebook described by the metadata. The columns of the matrices
+/// need to be orthonormal. Since the feedback only determines each column up
+/// to a phase, the last row is made real and non-negative first.
+pub fn to_bfa(bfm: &BfmData) -> Result<BfaData, BfmConversionError> {
+    let (nr_index, nc_index) = (bfm.metadata.nr_index, bfm.metadata.nc_index);
+    let pattern = ExtractionConfig::get_pattern(nr_index, nc_index)?;
+    let codebook = AngleCodebook::from(angle_bit_sizes(&bfm.metadata)?);
+
+    let (num_receive, num_spatial, n_subcarriers) = bfm.feedback_matrix.dim();
+    if (num_receive, num_spatial) != (nr_index as usize + 1, nc_index as usize + 1) {
+        return Err(BfmConversionError::InvalidMatrixShape {
+            nr_index,
+            nc_index,
+            rows: num_receive,
+            cols: num_spatial,
+        });
+    }
+
+    let mut bfa_angles = Array2::zeros((n_subcarriers, pattern.len()));
+    for (sub_idx, mut angles) in bfa_angles.rows_mut().into_iter().enumerate() {
+        let mut v = bfm.feedback_matrix.slice(s![.., .., sub_idx]).to_owned();
+
+        // Remove the phase of the last row (multiplication with D~^H)
+        for mut column in v.columns_mut() {
+            let scale = Complex64::from_polar(1.0, -column[num_receive - 1].arg());
+            column.mapv_inplace(|x| x * scale);
+        }
+
+        // Undo the multiplications of `to_bfm` from the left, in the order
+        // in which the angles are reported.
+        for (angle, &(kind, nr, nc)) in angles.iter_mut().zip(pattern) {
+            let (row, col) = (nr - 1, nc - 1);
+            let value = match kind {
+                Angles::Phi => {
+                    let phi = v[(row, col)].arg().rem_euclid(2.0 * PI);
+                    remove_d_inplace(&mut v, row, phi);
+                    phi
+                }
+                Angles::Psi => {
+                    let psi = v[(row, col)].norm().atan2(v[(col, col)].norm());
+                    remove_givens_inplace(&mut v, row, col, psi);
+                    psi
+                }
+            };
+            *angle = codebook.quantize(kind, value);
+        }
+    }
+
+    Ok(BfaData {
+        metadata: bfm.metadata.clone(),
+        #[cfg(feature = "radio_metadata")]
+        radio: bfm.radio.clone(),
+        timestamp: bfm.timestamp,
+        mac_header: bfm.mac_header,
+        fcs_valid: bfm.fcs_valid,
+        #[cfg(feature = "ndpa")]
+        ndpa: bfm.ndpa.clone(),
+        token_number: bfm.token_number,
+        snr: bfm.snr.clone(),
+        delta_snr: bfm.delta_snr.clone(),
+        subcarrier_indices: bfm.subcarrier_indices.clone(),
+        bfa_angles,
+    })
+}
+
 /// In-place right-multiplication by the n-dimensional D_i(phi) matrix.
 ///
 /// This function performs the equivalent of multiplying an input matrix `acc` on the right by a
@@ -115,6 +350,31 @@ fn apply_givens_inplace(acc: &mut Array2<Complex64>, row_idx: usize, col_idx: us
     }
 }
 
+/// In-place left-multiplication by the conjugate of the D_i(phi) matrix.
+///
+/// Inverse of `apply_d_inplace` from the other side: removes the phase
+/// exp(i * phase) from the row at position `pos`.
+fn remove_d_inplace(v: &mut Array2<Complex64>, pos: usize, phase: f64) {
+    let scale = Complex64::from_polar(1.0, -phase);
+    v.row_mut(pos).mapv_inplace(|x| x * scale);
+}
+
+/// In-place left-multiplication by the Givens rotation G_{r,c}(psi) matrix.
+///
+/// Inverse of `apply_givens_inplace` from the other side: rotates the rows
+/// `row_idx` and `col_idx` such that the entry at (row_idx, col_idx) vanishes
+/// if `phase` was chosen accordingly.
+fn remove_givens_inplace(v: &mut Array2<Complex64>, row_idx: usize, col_idx: usize, phase: f64) {
+    let cos_val = phase.cos();
+    let sin_val = phase.sin();
+    for c in 0..v.ncols() {
+        let temp_i = v[(row_idx, c)];
+        let temp_j = v[(col_idx, c)];
+        v[(row_idx, c)] = cos_val * temp_i - sin_val * temp_j;
+        v[(col_idx, c)] = sin_val * temp_i + cos_val * temp_j;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -228,21 +488,34 @@ mod tests {
     fn test_to_bfm_frame1() {
         let epsilon = 1e-6;
         let metadata = crate::BfiMetadata {
+            standard: crate::WifiStandard::He,
             bandwidth: 20,
             nr_index: 3, // 4 receive antennas.
             nc_index: 1, // 2 spatial streams.
             codebook_info: 1,
             feedback_type: 0,
+            partial_bw_info: None,
+            ru_start_index: None,
+            ru_end_index: None,
         };
-        let bfa_angles = vec![
-            vec![18, 33, 43, 15, 12, 9, 31, 15, 12, 1],
-            vec![19, 33, 43, 14, 12, 9, 31, 16, 11, 1],
-            vec![26, 34, 43, 15, 12, 9, 25, 16, 12, 1],
+        let bfa_angles = array![
+            [18, 33, 43, 15, 12, 9, 31, 15, 12, 1],
+            [19, 33, 43, 14, 12, 9, 31, 16, 11, 1],
+            [26, 34, 43, 15, 12, 9, 25, 16, 12, 1],
         ];
         let bfi = BfaData {
             metadata,
+            #[cfg(feature = "radio_metadata")]
+            radio: Default::default(),
             timestamp: 0.0,
+            mac_header: Default::default(),
+            fcs_valid: None,
+            #[cfg(feature = "ndpa")]
+            ndpa: None,
             token_number: 0,
+            snr: vec![],
+            delta_snr: None,
+            subcarrier_indices: None,
             bfa_angles,
         };
         let result = to_bfm(&bfi).expect("Conversion failed for frame1");
@@ -319,20 +592,33 @@ mod tests {
     fn test_to_bfm_frame2() {
         let epsilon = 1e-6;
         let metadata = crate::BfiMetadata {
+            standard: crate::WifiStandard::He,
             bandwidth: 20,
             nr_index: 3,
             nc_index: 1,
             codebook_info: 1,
             feedback_type: 0,
+            partial_bw_info: None,
+            ru_start_index: None,
+            ru_end_index: None,
         };
-        let bfa_angles = vec![
-            vec![11, 33, 43, 13, 13, 9, 46, 23, 10, 2],
-            vec![14, 34, 44, 14, 13, 9, 44, 23, 10, 1],
+        let bfa_angles = array![
+            [11, 33, 43, 13, 13, 9, 46, 23, 10, 2],
+            [14, 34, 44, 14, 13, 9, 44, 23, 10, 1],
         ];
         let bfi = BfaData {
             metadata,
+            #[cfg(feature = "radio_metadata")]
+            radio: Default::default(),
             timestamp: 0.0,
+            mac_header: Default::default(),
+            fcs_valid: None,
+            #[cfg(feature = "ndpa")]
+            ndpa: None,
             token_number: 0,
+            snr: vec![],
+            delta_snr: None,
+            subcarrier_indices: None,
             bfa_angles,
         };
         let result = to_bfm(&bfi).expect("Conversion failed for frame2");
@@ -376,4 +662,323 @@ mod tests {
         let expected = stack!(ndarray::Axis(2), expected_sub0, expected_sub1);
         assert_array3_approx_eq(&expected, &result.feedback_matrix, epsilon);
     }
+
+    /// Create 20 MHz BFA data of the given configuration carrying nothing but the angles
+    ///
+    /// # Parameters
+    /// * `standard` - WiFi standard of the codebook
+    /// * `codebook` - Codebook info and feedback type
+    /// * `shape` - Nr and Nc index
+    /// * `bfa_angles` - Quantized angles
+    fn bfa_fixture(
+        standard: crate::WifiStandard,
+        (codebook_info, feedback_type): (u8, u8),
+        (nr_index, nc_index): (u8, u8),
+        bfa_angles: Array2<u16>,
+    ) -> BfaData {
+        let metadata = crate::BfiMetadata {
+            standard,
+            bandwidth: 20,
+            nr_index,
+            nc_index,
+            codebook_info,
+            feedback_type,
+            partial_bw_info: None,
+            ru_start_index: None,
+            ru_end_index: None,
+        };
+        BfaData::from_angles(metadata, bfa_angles)
+    }
+
+    /// Test for the HT codebook in `to_bfm`.
+    ///
+    /// For a 2x1 configuration, the feedback matrix is the single column
+    /// [cos(psi) * exp(i * phi), sin(psi)]. HT codebook 0 quantizes Phi with
+    /// 3 bits and Psi with 1 bit, which differs from the VHT/HE codebooks.
+    #[test]
+    fn test_to_bfm_ht_codebook() {
+        let epsilon = 1e-6;
+        let bfi = bfa_fixture(crate::WifiStandard::Ht, (0, 0), (1, 0), array![[1, 1]]);
+        let result = to_bfm(&bfi).expect("Conversion failed for HT frame");
+
+        // phi = 1 * PI / 4 + PI / 8, psi = 1 * PI / 4 + PI / 8
+        let phi = 3.0 * PI / 8.0;
+        let psi = 3.0 * PI / 8.0;
+        let expected = Array3::from_shape_vec(
+            (2, 1, 1),
+            vec![
+                Complex64::from_polar(psi.cos(), phi),
+                Complex64::new(psi.sin(), 0.0),
+            ],
+        )
+        .unwrap();
+        assert_array3_approx_eq(&expected, &result.feedback_matrix, epsilon);
+    }
+
+    /// Test `to_bfm` for all antenna configurations up to 8x8.
+    ///
+    /// The feedback matrix V is composed of the first columns of a unitary
+    /// matrix, so its columns must be orthonormal (V^H V = I) for every
+    /// subcarrier, independent of the angle values.
+    #[test]
+    fn test_to_bfm_all_shapes() {
+        let epsilon = 1e-9;
+        for nr_index in 1..8u8 {
+            for nc_index in 0..=nr_index {
+                let num_angles = ExtractionConfig::get_pattern(nr_index, nc_index)
+                    .unwrap()
+                    .len();
+                // Arbitrary angles that stay within the 7 bit Psi codebook
+                let bfa_angles = Array2::from_shape_fn((3, num_angles), |(sub, i)| {
+                    ((i * 37 + sub * 11) % 128) as u16
+                });
+                let mut bfi = bfa_fixture(
+                    crate::WifiStandard::He,
+                    (1, 1),
+                    (nr_index, nc_index),
+                    bfa_angles,
+                );
+                bfi.snr = vec![0.0; nc_index as usize + 1];
+                let result = to_bfm(&bfi).expect("Conversion failed");
+
+                let (nr, nc) = (nr_index as usize + 1, nc_index as usize + 1);
+                assert_eq!(result.feedback_matrix.dim(), (nr, nc, 3));
+
+                for sub in 0..3 {
+                    let v = result.feedback_matrix.slice(ndarray::s![.., .., sub]);
+                    for r in 0..nc {
+                        for c in 0..nc {
+                            let val: Complex64 = v
+                                .column(r)
+                                .iter()
+                                .zip(v.column(c).iter())
+                                .map(|(a, b)| a.conj() * b)
+                                .sum();
+                            let expected = if r == c { 1.0 } else { 0.0 };
+                            approx_eq_complex(val, Complex64::new(expected, 0.0), epsilon);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Test that `to_bfa` inverts `to_bfm` for all antenna configurations and
+    /// codebooks, and that the packed angles are extracted unchanged.
+    #[test]
+    fn test_to_bfa_roundtrip() {
+        let codebooks = [
+            (crate::WifiStandard::Ht, (0, 0)),
+            (crate::WifiStandard::Ht, (3, 0)),
+            (crate::WifiStandard::He, (0, 0)),
+            (crate::WifiStandard::He, (1, 1)),
+        ];
+        for (standard, codebook) in codebooks {
+            for nr_index in 1..8u8 {
+                for nc_index in 0..=nr_index {
+                    let pattern = ExtractionConfig::get_pattern(nr_index, nc_index).unwrap();
+                    let mut bfa = bfa_fixture(
+                        standard,
+                        codebook,
+                        (nr_index, nc_index),
+                        Array2::zeros((5, pattern.len())),
+                    );
+                    let bitsizes = angle_bit_sizes(&bfa.metadata).unwrap();
+                    // Arbitrary angles covering the whole codebook
+                    bfa.bfa_angles = Array2::from_shape_fn((5, pattern.len()), |(sub, i)| {
+                        let bits = match pattern[i].0 {
+                            Angles::Phi => bitsizes.phi_bit,
+                            Angles::Psi => bitsizes.psi_bit,
+                        };
+                        ((i * 37 + sub * 11) % (1 << bits)) as u16
+                    });
+
+                    // The phase of each column is not part of the feedback
+                    let mut bfm = to_bfm(&bfa).unwrap();
+                    for (c, mut column) in bfm
+                        .feedback_matrix
+                        .axis_iter_mut(ndarray::Axis(1))
+                        .enumerate()
+                    {
+                        column *= Complex64::from_polar(1.0, 0.3 + c as f64);
+                    }
+                    let result = to_bfa(&bfm).unwrap();
+                    assert_eq!(
+                        result.bfa_angles, bfa.bfa_angles,
+                        "{standard:?}, codebook: {codebook:?}, nr: {nr_index}, nc: {nc_index}"
+                    );
+
+                    let payload = crate::pack_bfa(&result).unwrap();
+                    let config = ExtractionConfig {
+                        bitfield_pattern: pattern
+                            .iter()
+                            .map(|&(kind, _, _)| match kind {
+                                Angles::Phi => bitsizes.phi_bit,
+                                Angles::Psi => bitsizes.psi_bit,
+                            })
+                            .collect(),
+                        num_subcarrier: 5,
+                        subcarrier_indices: None,
+                    };
+                    assert_eq!(payload.len(), config.payload_length());
+                    let extracted = crate::extraction::extract_bfa(&payload, config).unwrap();
+                    assert_eq!(extracted, bfa.bfa_angles);
+                }
+            }
+        }
+    }
+
+    /// Test that `to_bfa` rejects matrices that do not match their metadata.
+    #[test]
+    fn test_to_bfa_invalid_shape() {
+        let bfa = bfa_fixture(
+            crate::WifiStandard::He,
+            (1, 0),
+            (3, 1),
+            Array2::zeros((1, 10)),
+        );
+        let mut bfm = to_bfm(&bfa).unwrap();
+        bfm.feedback_matrix = Array3::zeros((4, 3, 1));
+        assert!(matches!(
+            to_bfa(&bfm),
+            Err(BfmConversionError::InvalidMatrixShape {
+                rows: 4,
+                cols: 3,
+                ..
+            })
+        ));
+    }
+
+    /// Test that `dequantize` yields the angles `to_bfm` multiplies with.
+    ///
+    /// A single Phi (2x1) rotates the phase of the first entry, a single Psi
+    /// splits the magnitude between both rows.
+    #[test]
+    fn test_dequantize() {
+        // SU codebook 0: 4 bit Phi, 2 bit Psi
+        let bfa = bfa_fixture(
+            crate::WifiStandard::He,
+            (0, 0),
+            (1, 0),
+            array![[0, 0], [5, 3], [15, 1]],
+        );
+        let radians = dequantize(&bfa).unwrap();
+        let expected = array![
+            [PI / 16.0, PI / 16.0],
+            [11.0 * PI / 16.0, 7.0 * PI / 16.0],
+            [31.0 * PI / 16.0, 3.0 * PI / 16.0],
+        ];
+        for (value, expected) in radians.iter().zip(expected.iter()) {
+            assert!((value - expected).abs() < 1e-12, "{value} vs {expected}");
+        }
+
+        let bfm = to_bfm(&bfa).unwrap();
+        for (sub, angles) in radians.rows().into_iter().enumerate() {
+            let (phi, psi) = (angles[0], angles[1]);
+            approx_eq_complex(
+                bfm.feedback_matrix[(0, 0, sub)],
+                Complex64::from_polar(psi.cos(), phi),
+                1e-12,
+            );
+            approx_eq_complex(
+                bfm.feedback_matrix[(1, 0, sub)],
+                Complex64::new(psi.sin(), 0.0),
+                1e-12,
+            );
+        }
+
+        let results = dequantize_batch(&[
+            bfa.clone(),
+            BfaData::from_angles(bfa.metadata, array![[0, 0, 0]]),
+        ]);
+        assert_eq!(results[0].as_ref().unwrap(), radians);
+        assert!(matches!(
+            results[1],
+            Err(BfmConversionError::Extraction(
+                BfaExtractionError::InvalidAngleCount {
+                    expected: 2,
+                    given: 3
+                }
+            ))
+        ));
+    }
+
+    /// Test that `to_bfm_batch` matches `to_bfm` and keeps the input order.
+    #[test]
+    fn test_to_bfm_batch() {
+        let data: Vec<BfaData> = (0..40u8)
+            .map(|i| {
+                let (nr_index, nc_index) = (1 + i % 7, i % 2);
+                let num_angles = ExtractionConfig::get_pattern(nr_index, nc_index)
+                    .unwrap()
+                    .len();
+                let bfa_angles = Array2::from_shape_fn((4, num_angles), |(sub, a)| {
+                    ((sub * 5 + a * 3 + i as usize) % 16) as u16
+                });
+                let mut bfa = bfa_fixture(
+                    crate::WifiStandard::He,
+                    (1, 0),
+                    (nr_index, nc_index),
+                    bfa_angles,
+                );
+                bfa.token_number = i;
+                bfa
+            })
+            .collect();
+        let mut invalid = data[0].clone();
+        invalid.metadata.nr_index = 0;
+        let mut batch = data.clone();
+        batch.insert(7, invalid);
+
+        let results = to_bfm_batch(&batch);
+        assert_eq!(results.len(), batch.len());
+        assert!(matches!(
+            results[7],
+            Err(BfmConversionError::Extraction(
+                BfaExtractionError::InvalidAntennaConfig { .. }
+            ))
+        ));
+        let converted = results
+            .into_iter()
+            .enumerate()
+            .filter(|&(i, _)| i != 7)
+            .map(|(_, result)| result.unwrap());
+        for (result, bfa) in converted.zip(&data) {
+            let expected = to_bfm(bfa).unwrap();
+            assert_eq!(result.token_number, bfa.token_number);
+            assert_eq!(result.feedback_matrix, expected.feedback_matrix);
+        }
+        assert!(to_bfm_batch(&[]).is_empty());
+    }
+
+    /// Test that single precision matrices are the rounded double precision ones.
+    #[test]
+    fn test_to_bfm_single_precision() {
+        let bfa = bfa_fixture(
+            crate::WifiStandard::He,
+            (1, 0),
+            (3, 1),
+            array![
+                [18, 33, 43, 15, 12, 9, 31, 15, 12, 1],
+                [11, 33, 43, 13, 13, 9, 46, 23, 10, 2],
+            ],
+        );
+        let double = to_bfm(&bfa).unwrap();
+        let single = to_bfm_as::<f32>(&bfa).unwrap();
+        assert_eq!(single.feedback_matrix.dim(), double.feedback_matrix.dim());
+        for (s, d) in single
+            .feedback_matrix
+            .iter()
+            .zip(double.feedback_matrix.iter())
+        {
+            assert_eq!(s.re, d.re as f32);
+            assert_eq!(s.im, d.im as f32);
+        }
+
+        let batch = to_bfm_batch_as::<f32>(&[bfa]);
+        assert_eq!(
+            batch[0].as_ref().unwrap().feedback_matrix,
+            single.feedback_matrix
+        );
+    }
 }
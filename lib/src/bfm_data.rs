@@ -1,18 +1,77 @@
 //! BFM data structs used throughout the library.
 
-use ndarray::Array3;
-use num_complex::Complex64;
+use ndarray::{Array2, Array3};
+use num_complex::Complex;
 
 use crate::BfiMetadata;
+use crate::MacHeader;
+#[cfg(feature = "ndpa")]
+use crate::Ndpa;
+#[cfg(feature = "radio_metadata")]
+use crate::RadioMetadata;
 
-pub type FeedbackMatrix = Array3<Complex64>;
+/// Floating point precision of feedback matrices (`f64` or `f32`)
+///
+/// Quantized angles carry at most 9 bits, so single precision suffices to
+/// represent the matrices at half the memory.
+pub trait Precision: Copy + Send + Sync + std::fmt::Debug + 'static {
+    /// Convert from double precision (rounding if required)
+    fn from_f64(value: f64) -> Self;
+    /// Convert to double precision
+    fn to_f64(self) -> f64;
+}
+
+impl Precision for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl Precision for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+/// Feedback matrices of shape (nr, nc, num_subcarrier)
+pub type FeedbackMatrix<T = f64> = Array3<Complex<T>>;
 
 /// Beamforming Feedback Matrix Data extracted from a single packet.
+///
+/// The matrices are stored in double precision unless requested otherwise
+/// (see `to_bfm_as`).
 #[derive(Debug, Clone)]
-pub struct BfmData {
+pub struct BfmData<T = f64> {
     #[cfg(feature = "bfi_metadata")]
     pub metadata: BfiMetadata,
+    /// Reception information from the link layer header
+    #[cfg(feature = "radio_metadata")]
+    pub radio: RadioMetadata,
     pub timestamp: f64,
+    /// Addressing and sequence information of the feedback frame
+    pub mac_header: MacHeader,
+    /// Result of the FCS verification (`None` if not verified)
+    pub fcs_valid: Option<bool>,
+    /// NDP Announcement that solicited the report (if captured)
+    #[cfg(feature = "ndpa")]
+    pub ndpa: Option<Ndpa>,
     pub token_number: u8,
-    pub feedback_matrix: FeedbackMatrix,
+    /// Average SNR (in dB) of each space-time stream
+    pub snr: Vec<f32>,
+    /// Delta SNR (in dB) per stream and subcarrier from the MU Exclusive
+    /// Beamforming Report (only present for MU feedback)
+    pub delta_snr: Option<Array2<i8>>,
+    /// Tone index of every subcarrier of the feedback matrix (if known for
+    /// the standard). Multiply by the subcarrier spacing to obtain the
+    /// frequency offset from the channel center.
+    pub subcarrier_indices: Option<Vec<i16>>,
+    pub feedback_matrix: FeedbackMatrix<T>,
 }
@@ -8,16 +8,20 @@
 //! This module implements
 
 use crossbeam_channel::{bounded, Receiver, Sender};
+use ndarray::Array2;
 use pcap::{Active, Capture, Offline, Savefile};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 
+use crate::bfa_to_bfm::to_bfm_with_scratch;
 use crate::bfm_data::BfmData;
-use crate::pcap::extract_from_packet;
 use crate::persistence::FileContentType;
-use crate::{to_bfm, BfaData, BfiFile, Writer};
+use crate::{
+    extract_trigger_from_packet, to_bfm, BfaData, BfaExtractionError, BfiFile, BfrpTrigger,
+    CqiData, FcsPolicy, Feedback, FeedbackReassembler, LinkType, Writer,
+};
 
 /// Size of batches to write.
 ///
@@ -34,10 +38,16 @@ pub struct StreamBee {
     pollen_sink: Option<PollenSink>,
     nectar_sink: Option<Sender<BfaData>>,
     honey_sink: Option<Sender<BfmData>>,
+    waggle_sink: Option<Sender<BfrpTrigger>>,
+    wax_sink: Option<Sender<CqiData>>,
+    fcs_policy: FcsPolicy,
+    honey_workers: usize,
     running: Arc<AtomicBool>,
     harvester: Option<JoinHandle<()>>,
+    honey_converters: Vec<JoinHandle<()>>,
     bfa_file_writer: Option<JoinHandle<()>>,
     bfm_file_writer: Option<JoinHandle<()>>,
+    cqi_file_writer: Option<JoinHandle<()>>,
 }
 
 /// Wrapper enum for pcap `Capture` types to avoid generics in StreamBee.
@@ -48,12 +58,20 @@ enum CaptureWrapper {
 
 impl CaptureWrapper {
     /// Reads the next packet, returning `Ok(Packet)` if successful, or an error if not.
-    fn next_packet(&mut self) -> Result<pcap::Packet, pcap::Error> {
+    fn next_packet(&mut self) -> Result<pcap::Packet<'_>, pcap::Error> {
         match self {
             CaptureWrapper::Live(cap) => cap.next_packet(),
             CaptureWrapper::File(cap) => cap.next_packet(),
         }
     }
+
+    /// Returns the link type of the capture.
+    fn datalink(&self) -> pcap::Linktype {
+        match self {
+            CaptureWrapper::Live(cap) => cap.get_datalink(),
+            CaptureWrapper::File(cap) => cap.get_datalink(),
+        }
+    }
 }
 
 /// A sink to receive Nectar, i.e. harvested Beamforming Feedback Angles
@@ -68,6 +86,20 @@ pub enum HoneySink {
     Queue(Sender<BfmData>),
 }
 
+/// A sink to receive wax, i.e. Channel Quality Indication (CQI) reports
+pub enum WaxSink {
+    File(BfiFile),
+    Queue(Sender<CqiData>),
+}
+
+/// A sink to receive waggles, i.e. Beamforming Report Poll triggers.
+///
+/// Like the waggle dance of a bee, the triggers tell the others where to go:
+/// They announce which stations send their MU feedback on which RU.
+pub enum WaggleSink {
+    Queue(Sender<BfrpTrigger>),
+}
+
 /// A sink to receive pollen, i.e. raw data.
 ///
 /// This is mainly used to store data captured live from an interface
@@ -95,10 +127,16 @@ impl StreamBee {
             pollen_sink: None,
             nectar_sink: None,
             honey_sink: None,
+            waggle_sink: None,
+            wax_sink: None,
+            fcs_policy: FcsPolicy::default(),
+            honey_workers: 0,
             running: Arc::new(AtomicBool::new(false)),
             harvester: None,
+            honey_converters: Vec::new(),
             bfa_file_writer: None,
             bfm_file_writer: None,
+            cqi_file_writer: None,
         }
     }
 
@@ -119,8 +157,11 @@ impl StreamBee {
                 let (tx, rx) = bounded(100);
 
                 // Spawn a thread to handle file writing from the channel
+                // NOTE: Matrices may be written in single or double precision
                 let mut file = file.clone();
-                file.file_content_type = FileContentType::Bfm;
+                if !matches!(file.file_content_type, FileContentType::BfmSingle) {
+                    file.file_content_type = FileContentType::Bfm;
+                }
                 log::trace!(
                     "Spawning background thread to write processed data to file {:?}",
                     file
@@ -151,8 +192,11 @@ impl StreamBee {
                 let (tx, rx) = bounded(100);
 
                 // Spawn a thread to handle file writing from the channel
+                // NOTE: Angles may be written as quantized values or radians
                 let mut file = file.clone();
-                file.file_content_type = FileContentType::Bfa;
+                if !matches!(file.file_content_type, FileContentType::BfaRadians) {
+                    file.file_content_type = FileContentType::Bfa;
+                }
 
                 log::trace!(
                     "Spawning background thread to write processed data to file {:?}",
@@ -167,6 +211,38 @@ impl StreamBee {
         self.nectar_sink = Some(sink);
     }
 
+    /// Registers a sink for wax (CQI reports)
+    ///
+    /// - `WaxSink::File`: Extracted CQI reports are batched, and saved to a specified file.
+    /// - `WaxSink::Queue`: Extracted CQI reports are sent to an in-process queue for real-time handling.
+    ///
+    /// # Parameters
+    /// - `sink`: The sink to stream the extracted CQI data to
+    pub fn subscribe_for_wax(&mut self, sink: WaxSink) {
+        if self.wax_sink.is_some() {
+            panic!("Cant set two CQI sinks (currently)");
+        }
+
+        let sink = match sink {
+            WaxSink::File(file) => {
+                let (tx, rx) = bounded(100);
+
+                // Spawn a thread to handle file writing from the channel
+                let mut file = file.clone();
+                file.file_content_type = FileContentType::Cqi;
+                log::trace!(
+                    "Spawning background thread to write CQI data to file {:?}",
+                    file
+                );
+                self.cqi_file_writer = Some(thread::spawn(|| write_cqi_reports_to_file(rx, file)));
+                tx
+            }
+            WaxSink::Queue(queue) => queue,
+        };
+
+        self.wax_sink = Some(sink);
+    }
+
     /// Registers a sink for pollen (raw packets)
     ///
     /// - `PollenSink::File`: Specifies a pcap file to write packets to
@@ -180,12 +256,49 @@ impl StreamBee {
         self.pollen_sink = Some(sink);
     }
 
+    /// Registers a sink for waggles (BFRP triggers of MU sounding sessions)
+    ///
+    /// - `WaggleSink::Queue`: Triggers are sent to an in-process queue for real-time handling.
+    ///
+    /// # Parameters
+    /// - `sink`: The sink to stream the parsed triggers to
+    pub fn subscribe_for_waggles(&mut self, sink: WaggleSink) {
+        if self.waggle_sink.is_some() {
+            panic!("Cant set two trigger sinks (currently)");
+        }
+        self.waggle_sink = Some(match sink {
+            WaggleSink::Queue(queue) => queue,
+        });
+    }
+
+    /// Sets the policy on how to treat the FCS of captured frames
+    ///
+    /// # Parameters
+    /// - `fcs_policy`: How to treat the FCS of captured frames
+    pub fn set_fcs_policy(&mut self, fcs_policy: FcsPolicy) {
+        self.fcs_policy = fcs_policy;
+    }
+
+    /// Sets the number of threads converting BFA to BFM for the honey sink
+    ///
+    /// By default (0), the conversion runs on the harvesting thread. With
+    /// workers, the harvester hands the BFA to a pool of converters instead,
+    /// which may deliver the BFM out of capture order.
+    ///
+    /// # Parameters
+    /// - `workers`: Number of conversion threads (0 to convert while harvesting)
+    pub fn set_honey_workers(&mut self, workers: usize) {
+        self.honey_workers = workers;
+    }
+
     /// Starts harvesting packets from the registered Capture
     ///
     /// Reads packets from the registered interface, then:
     ///
     /// 1. If a `PollenSink` is registered, first forwards the raw packets
-    /// 2. If a `NectarSink` is registered, extracts BFI data and forwards it.
+    /// 2. If a `WaggleSink` is registered, forwards parsed BFRP triggers.
+    /// 3. If a `NectarSink` is registered, extracts BFI data and forwards it.
+    /// 4. If a `WaxSink` is registered, forwards extracted CQI reports.
     ///
     /// Captures packets continuously until:
     /// - The capture ends (for offline captures),
@@ -204,8 +317,38 @@ impl StreamBee {
         let pollen_sink = self.pollen_sink.take();
         let nectar_sink = self.nectar_sink.take();
         let honey_sink = self.honey_sink.take();
+        let waggle_sink = self.waggle_sink.take();
+        let wax_sink = self.wax_sink.take();
+        let fcs_policy = self.fcs_policy;
+
+        // Start honey converters, fed with the BFA by the harvester
+        let (honey_sink, honey_pool) = match honey_sink {
+            Some(sink) if self.honey_workers > 0 => {
+                let (tx, rx) = bounded(100 * self.honey_workers);
+                self.honey_converters = (0..self.honey_workers)
+                    .map(|_| {
+                        let (rx, sink) = (rx.clone(), sink.clone());
+                        thread::spawn(move || convert_honey(rx, sink))
+                    })
+                    .collect();
+                (None, Some(tx))
+            }
+            sink => (sink, None),
+        };
+
         self.harvester = Some(thread::spawn(move || {
-            harvest(cap, running, pollen_sink, nectar_sink, honey_sink, print)
+            harvest(
+                cap,
+                running,
+                pollen_sink,
+                nectar_sink,
+                honey_sink,
+                honey_pool,
+                waggle_sink,
+                wax_sink,
+                fcs_policy,
+                print,
+            )
         }));
     }
 
@@ -226,11 +369,23 @@ impl StreamBee {
             }
         }
 
+        // Converters finish once the harvester dropped their queue
+        for converter in self.honey_converters.drain(..) {
+            if let Err(e) = converter.join() {
+                log::error!("Couldn't join honey converter thread. Error: {:?}", e);
+            }
+        }
+
         if let Some(file_writer) = self.bfa_file_writer.take() {
             if let Err(e) = file_writer.join() {
                 log::error!("Couldn't join file writer thread. Error: {:?}", e);
             }
         }
+        if let Some(file_writer) = self.cqi_file_writer.take() {
+            if let Err(e) = file_writer.join() {
+                log::error!("Couldn't join CQI file writer thread. Error: {:?}", e);
+            }
+        }
         // Ensure pcap file is flushed
         if let Some(PollenSink::File(file)) = &mut self.pollen_sink {
             if let Err(e) = file.flush() {
@@ -241,6 +396,8 @@ impl StreamBee {
         // Ensure the queues are destroyed so the file writer's are notified.
         self.nectar_sink = None;
         self.pollen_sink = None;
+        self.waggle_sink = None;
+        self.wax_sink = None;
         self.harvester = None
     }
 }
@@ -256,15 +413,35 @@ impl StreamBee {
 /// * `pollen_sink` - Optional sink for raw packets
 /// * `nectar_sink` - Optional sink for extracted BFA
 /// * `honey_sink` - Optional sink for extracted BFM
+/// * `honey_pool` - Optional queue of converters feeding the BFM sink
+/// * `waggle_sink` - Optional sink for parsed BFRP triggers
+/// * `wax_sink` - Optional sink for extracted CQI reports
+/// * `fcs_policy` - How to treat the FCS of captured frames
 /// * `print` - Flag whether to print extracted BFI data to `stdout`.
+#[allow(clippy::too_many_arguments)] // One per sink, bundling them gains nothing
 fn harvest(
     mut cap: CaptureWrapper,
     running: Arc<AtomicBool>,
     mut pollen_sink: Option<PollenSink>,
     nectar_sink: Option<Sender<BfaData>>,
     honey_sink: Option<Sender<BfmData>>,
+    honey_pool: Option<Sender<BfaData>>,
+    waggle_sink: Option<Sender<BfrpTrigger>>,
+    wax_sink: Option<Sender<CqiData>>,
+    fcs_policy: FcsPolicy,
     print: bool,
 ) {
+    let mut reassembler = FeedbackReassembler::default().with_fcs_policy(fcs_policy);
+
+    // NOTE: Raw packets may still be forwarded for unsupported link types
+    let link_type = match LinkType::try_from(cap.datalink()) {
+        Ok(link_type) => Some(link_type),
+        Err(e) => {
+            log::error!("Can't extract BFI data from capture: {}", e);
+            None
+        }
+    };
+
     while running.load(Ordering::SeqCst) {
         // Capture the next packet first, holding the mutable borrow only for this step
         let packet = {
@@ -289,10 +466,80 @@ fn harvest(
             }
         }
 
-        if honey_sink.is_some() || nectar_sink.is_some() || print {
+        let Some(link_type) = link_type else {
+            continue;
+        };
+
+        if let Some(sink) = &waggle_sink {
+            match extract_trigger_from_packet(&packet, link_type, fcs_policy) {
+                Ok(Some(trigger)) => {
+                    match sink.try_send(trigger) {
+                        Ok(_) => {}
+                        Err(crossbeam_channel::TrySendError::Full(_)) => {
+                            log::warn!("Waggle sink channel full; dropping trigger. Increase queue size or process more frequently.")
+                        }
+                        Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
+                            log::error!("Waggle sink channel disconnected")
+                        }
+                    }
+                    continue;
+                }
+                // Not a trigger frame, might carry feedback
+                Ok(None) => {}
+                Err(BfaExtractionError::NotApplicable(reason)) => {
+                    log::trace!("Skipping trigger: {}", reason);
+                    continue;
+                }
+                Err(BfaExtractionError::InvalidFcs) => {
+                    log::debug!("Dropping trigger with invalid FCS");
+                    continue;
+                }
+                Err(e) => {
+                    log::error!("Failed to parse trigger frame. Skipping. Error: {}", e);
+                    continue;
+                }
+            }
+        }
+
+        if honey_sink.is_some()
+            || honey_pool.is_some()
+            || nectar_sink.is_some()
+            || wax_sink.is_some()
+            || print
+        {
             // Try to extract data from packet.
-            let data = match extract_from_packet(&packet) {
-                Ok(data) => data,
+            let data = match reassembler.process_packet(&packet, link_type) {
+                Ok(Some(Feedback::Angles(data))) => data,
+                Ok(Some(Feedback::Cqi(cqi))) => {
+                    if print {
+                        println!(
+                            "Captured CQI:\n - timestamp: {}\n - token number: {}\n - RUs: {:?}\n - snr: {:?}",
+                            cqi.timestamp, cqi.token_number, cqi.ru_indices, cqi.snr
+                        );
+                    }
+                    if let Some(sink) = &wax_sink {
+                        match sink.try_send(cqi) {
+                            Ok(_) => {}
+                            Err(crossbeam_channel::TrySendError::Full(_)) => {
+                                log::warn!("Wax sink channel full; dropping CQI data. Increase queue size or process more frequently.")
+                            }
+                            Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
+                                log::error!("Wax sink channel disconnected")
+                            }
+                        }
+                    }
+                    continue;
+                }
+                // Segment of a report that is not complete yet
+                Ok(None) => continue,
+                Err(BfaExtractionError::NotApplicable(reason)) => {
+                    log::trace!("Skipping packet: {}", reason);
+                    continue;
+                }
+                Err(BfaExtractionError::InvalidFcs) => {
+                    log::debug!("Dropping packet with invalid FCS");
+                    continue;
+                }
                 Err(e) => {
                     log::error!(
                         "Failed to extract BFI data from packet. Skipping. Error: {}",
@@ -316,8 +563,8 @@ fn harvest(
 
             if print {
                 println!(
-                    "Captured data:\n - timestamp: {}\n - token number: {}\n{} - metadata {:?}",
-                    data.timestamp, data.token_number, metadata_info, data.bfa_angles
+                    "Captured data:\n - timestamp: {}\n - token number: {}\n - snr: {:?}\n{} - metadata {:?}",
+                    data.timestamp, data.token_number, data.snr, metadata_info, data.bfa_angles
                 );
             }
 
@@ -337,8 +584,30 @@ fn harvest(
                 }
             }
 
+            // Leave the honey to the converters, copying the data only if
+            // the nectar sink wants it as well.
+            let data = match &honey_pool {
+                Some(pool) => {
+                    let (queued, data) = match &nectar_sink {
+                        Some(_) => (data.clone(), Some(data)),
+                        None => (data, None),
+                    };
+                    match pool.try_send(queued) {
+                        Ok(_) => {}
+                        Err(crossbeam_channel::TrySendError::Full(_)) => {
+                            log::warn!("Honey converter queue full; dropping BFA data. Increase the number of workers.")
+                        }
+                        Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
+                            log::error!("Honey converter queue disconnected")
+                        }
+                    }
+                    data
+                }
+                None => Some(data),
+            };
+
             // Just nectar? sure, also fine.
-            if let Some(sink) = &nectar_sink {
+            if let (Some(sink), Some(data)) = (&nectar_sink, data) {
                 match sink.try_send(data) {
                     Ok(_) => {}
                     Err(crossbeam_channel::TrySendError::Full(_)) => {
@@ -355,6 +624,29 @@ fn harvest(
     log::info!("Packet capture completed!\n");
 }
 
+/// Converts BFA to BFM for the honey sink, receiving data from a queue.
+///
+/// # Parameters
+/// - `rx`: Receiver channel that receives `BfaData` to convert.
+/// - `sink`: The sink to forward the converted `BfmData` to.
+fn convert_honey(rx: Receiver<BfaData>, sink: Sender<BfmData>) {
+    let mut scratch = Array2::zeros((0, 0));
+    for data in rx {
+        match to_bfm_with_scratch(&data, &mut scratch) {
+            Err(e) => log::error!("Failed to convert to BFM: {}", e),
+            Ok(bfm) => match sink.try_send(bfm) {
+                Ok(_) => {}
+                Err(crossbeam_channel::TrySendError::Full(_)) => {
+                    log::warn!("Honey sink channel full; dropping BFM data. Increase queue size or process more frequently.")
+                }
+                Err(crossbeam_channel::TrySendError::Disconnected(_)) => {
+                    log::error!("Honey sink channel disconnected")
+                }
+            },
+        }
+    }
+}
+
 /// Writes captured BFA (angles) to a file in batches, receiving data from a queue.
 ///
 /// # Parameters
@@ -419,6 +711,38 @@ fn write_bfm_packets_to_file(rx: Receiver<BfmData>, out_file: BfiFile) {
     }
 }
 
+/// Writes captured CQI reports to a file in batches, receiving data from a queue.
+///
+/// # Parameters
+/// - `rx`: Receiver channel that receives `CqiData` reports to write.
+/// - `out_file`: The file to which reports are saved in batches.
+fn write_cqi_reports_to_file(rx: Receiver<CqiData>, out_file: BfiFile) {
+    let mut report_buffer = Vec::new();
+    let mut writer = Writer::new(out_file).expect("Couldn't create a file writer!");
+
+    while let Ok(cqi_data) = rx.recv() {
+        report_buffer.push(cqi_data);
+        if report_buffer.len() <= BATCH_SIZE {
+            continue;
+        }
+
+        if let Err(e) = writer.add_cqi_batch(&report_buffer) {
+            log::error!("Error encountered on batch writing: {}. Exiting writer.", e);
+            return;
+        }
+        report_buffer.clear();
+    }
+
+    if report_buffer.is_empty() {
+        return;
+    }
+
+    // Write any remaining reports when the channel is closed
+    if let Err(e) = writer.add_cqi_batch(&report_buffer) {
+        log::error!("Error encountered on batch writing: {}. Exiting writer.", e);
+    }
+}
+
 /// Creates a live capture to read packets from a specified network interface.
 ///
 /// # Parameters
@@ -457,8 +781,13 @@ pub fn create_live_capture(
         .expect("Setting nonblock failed");
 
     // Apply filter for ACK/NOACK management frames
-    log::trace!("Applying pcap filter to only receive ACK/NOACK management frames.");
-    let filter = "ether[0] == 0xe0";
+    log::trace!("Applying pcap filter to only receive ACK/NOACK management and sounding frames.");
+    // NOTE: Trigger frames are kept to forward BFRP triggers
+    #[cfg(not(feature = "ndpa"))]
+    let filter = "ether[0] == 0xe0 or ether[0] == 0x24";
+    // NOTE: NDP Announcements are required to join them with the reports
+    #[cfg(feature = "ndpa")]
+    let filter = "ether[0] == 0xe0 or ether[0] == 0x24 or ether[0] == 0x54";
     cap.filter(filter, true).expect("Failed to apply filter!");
 
     cap
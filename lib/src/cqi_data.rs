@@ -0,0 +1,37 @@
+//! CQI data structs used throughout the library.
+
+use ndarray::Array2;
+
+#[cfg(feature = "bfi_metadata")]
+use crate::BfiMetadata;
+use crate::MacHeader;
+#[cfg(feature = "ndpa")]
+use crate::Ndpa;
+#[cfg(feature = "radio_metadata")]
+use crate::RadioMetadata;
+
+/// Channel Quality Indication data extracted from a single packet.
+///
+/// CQI feedback carries no beamforming angles but only the average SNR of
+/// every space-time stream per RU26 of the requested span.
+#[derive(Debug, Clone)]
+pub struct CqiData {
+    #[cfg(feature = "bfi_metadata")]
+    pub metadata: BfiMetadata,
+    /// Reception information from the link layer header
+    #[cfg(feature = "radio_metadata")]
+    pub radio: RadioMetadata,
+    pub timestamp: f64,
+    /// Addressing and sequence information of the feedback frame
+    pub mac_header: MacHeader,
+    /// Result of the FCS verification (`None` if not verified)
+    pub fcs_valid: Option<bool>,
+    /// NDP Announcement that solicited the report (if captured)
+    #[cfg(feature = "ndpa")]
+    pub ndpa: Option<Ndpa>,
+    pub token_number: u8,
+    /// Index of every reported RU26
+    pub ru_indices: Vec<u8>,
+    /// Average SNR (in dB) per stream and RU
+    pub snr: Array2<f32>,
+}
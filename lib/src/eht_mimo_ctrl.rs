@@ -0,0 +1,139 @@
+//! Extremely High Throughput (EHT) MIMO Control header
+//!
+//! This module defines types and handles extraction of the EHT MIMO Control
+//! header from the bytestream of a captured WiFi packet.
+use bilge::prelude::*;
+
+/// Bandwidth enum corresponding to index in EHT MIMO Control field
+#[bitsize(3)]
+#[derive(FromBits, Debug, Eq, PartialEq, Copy, Clone)]
+pub enum EhtBandwidth {
+    Bw20,
+    Bw40,
+    Bw80,
+    Bw160,
+    Bw320,
+    #[fallback]
+    Reserved,
+}
+
+/// Bandwidth conversion functions
+impl EhtBandwidth {
+    /// Get bandwidth value in Megahertz (0 for reserved values)
+    pub fn to_mhz(self) -> u16 {
+        match self {
+            EhtBandwidth::Reserved => 0,
+            // Left shift is equal to taking power of 2
+            bw => (2 << (bw as u16)) * 10,
+        }
+    }
+
+    /// Get bandwidth value in Hertz (0 for reserved values)
+    pub fn to_hz(self) -> u32 {
+        self.to_mhz() as u32 * 1_000_000
+    }
+}
+
+/// Partial bandwidth info subfield of the EHT MIMO Control header
+///
+/// Every bit of the feedback bitmap indicates whether feedback is requested
+/// for the corresponding 20 MHz (resolution 0) or 40 MHz (resolution 1)
+/// subchannel, starting from the lowest frequency.
+#[bitsize(9)]
+#[derive(FromBits, DebugBits, Copy, Clone, PartialEq)]
+pub struct PartialBwInfo {
+    pub resolution: u1,      // Subchannel resolution (0=20 MHz, 1=40 MHz)
+    pub feedback_bitmap: u8, // Requested subchannels
+}
+
+impl PartialBwInfo {
+    /// Subchannel width in Megahertz of a single bit in the feedback bitmap
+    pub fn resolution_mhz(&self) -> u16 {
+        20 << self.resolution().value()
+    }
+}
+
+/// EHT Mimo Control header
+#[bitsize(40)]
+#[derive(FromBits, DebugBits)]
+pub struct EhtMimoControl {
+    pub nc_index: u4,                    // Index for number of "columns" (streams)
+    pub nr_index: u4,                    // Index for number of receive antennas
+    pub bandwidth: EhtBandwidth,         // channel bandwidth
+    pub grouping: u1,                    // Indicates subcarrier grouping (Ng=4 or 16)
+    pub feedback_type: u2,               // Feedback type (0=Single User, 1= Multi User, 2= CQI)
+    pub reserved_1: u3,                  // Reserved
+    pub remaining_feedback_segments: u3, // Indicate number of remaining feedback segments
+    pub first_feedback_segments: u1,     // Whether this is the first (or only) feedback segment
+    pub partial_bw_info: PartialBwInfo,  // Subchannels for which beamformer requests feedback
+    pub dialog_token_number: u6,         // To identify EHT NDP announcement frame
+    pub codebook_info: u1,               // Codebook size (depends on grouping and feedback)
+    pub reserved_padding: u3,            // Reserved padding
+}
+
+impl EhtMimoControl {
+    /// Extract EhtMimoControl header from the packet bytestream (requires first 5 bytes.)
+    pub fn from_buf(buf: &[u8]) -> Self {
+        let value: UInt<u64, 40> = UInt::<u64, 40>::new(
+            (buf[0] as u64)
+                | ((buf[1] as u64) << 8)
+                | ((buf[2] as u64) << 16)
+                | ((buf[3] as u64) << 24)
+                | ((buf[4] as u64) << 32),
+        );
+        EhtMimoControl::from(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eht_mimo_ctrl_extraction() {
+        // 0001 0101 1111 1111 1111 1110 0001 0100 0001 0001 = 0x15fffe1411
+        // EHT MIMO Control:
+        // .... .... .... .... .... .... .... .... .... 0001 = Nc Index: 2 Columns (1)
+        // .... .... .... .... .... .... .... .... 0001 .... = Nr Index: 2 Rows (1)
+        // .... .... .... .... .... .... .... .100 .... .... = BW: 320 MHz (4)
+        // .... .... .... .... .... .... .... 0... .... .... = Grouping: Carrier Groups of 4 (0)
+        // .... .... .... .... .... .... ..01 .... .... .... = Feedback Type: MU (1)
+        // .... .... .... .... .... ...0 00.. .... .... .... = Reserved: 0x0
+        // .... .... .... .... .... 111. .... .... .... .... = Remaining Feedback Segments: 7
+        // .... .... .... .... ...1 .... .... .... .... .... = First Feedback Segment: 1
+        // .... .... ..11 1111 111. .... .... .... .... .... = Partial BW Info: 0x1ff
+        // .... 0101 11.. .... .... .... .... .... .... .... = Sounding Dialog Token Number: 23
+        // ...1 .... .... .... .... .... .... .... .... .... = Codebook Information: 1
+        // 000. .... .... .... .... .... .... .... .... .... = Reserved: 0x0
+
+        // bytestream (little endian)
+        let byte_stream: &[u8] = &[0b00010001, 0b00010100, 0b11111110, 0b11111111, 0b00010101];
+
+        let result = EhtMimoControl::from_buf(byte_stream);
+        assert_eq!(result.nc_index(), UInt::<u8, 4>::new(1));
+        assert_eq!(result.nr_index(), UInt::<u8, 4>::new(1));
+        assert_eq!(result.bandwidth(), EhtBandwidth::Bw320);
+        assert_eq!(result.grouping(), UInt::<u8, 1>::new(0));
+        assert_eq!(result.feedback_type(), UInt::<u8, 2>::new(1));
+        assert_eq!(result.reserved_1(), UInt::<u8, 3>::new(0));
+        assert_eq!(result.remaining_feedback_segments(), UInt::<u8, 3>::new(7));
+        assert_eq!(result.first_feedback_segments(), UInt::<u8, 1>::new(1));
+        assert_eq!(result.partial_bw_info().resolution(), UInt::<u8, 1>::new(1));
+        assert_eq!(result.partial_bw_info().feedback_bitmap(), 0xff);
+        assert_eq!(result.partial_bw_info().resolution_mhz(), 40);
+        assert_eq!(result.dialog_token_number(), UInt::<u8, 6>::new(23));
+        assert_eq!(result.codebook_info(), UInt::<u8, 1>::new(1));
+        assert_eq!(result.reserved_padding(), UInt::<u8, 3>::new(0));
+    }
+
+    #[test]
+    fn eht_bandwidth_to_mhz() {
+        assert_eq!(EhtBandwidth::Bw20.to_mhz(), 20);
+        assert_eq!(EhtBandwidth::Bw40.to_mhz(), 40);
+        assert_eq!(EhtBandwidth::Bw80.to_mhz(), 80);
+        assert_eq!(EhtBandwidth::Bw160.to_mhz(), 160);
+        assert_eq!(EhtBandwidth::Bw320.to_mhz(), 320);
+        assert_eq!(EhtBandwidth::Reserved.to_mhz(), 0);
+        assert_eq!(EhtBandwidth::Bw320.to_hz(), 320_000_000);
+    }
+}
@@ -1,6 +1,8 @@
 //! Error types used by this lib.
 use thiserror::Error;
 
+use crate::{LinkType, WifiStandard};
+
 #[allow(dead_code)]
 #[derive(Debug, Error)]
 pub enum BfaExtractionError {
@@ -8,10 +10,53 @@ pub enum BfaExtractionError {
     InsufficientBitsize { required: usize, available: usize },
     #[error("Bitsize {given} exceeds maximum handled bitsize of {allowed}")]
     InvalidBitfieldSize { given: u8, allowed: u8 },
+    #[error("Received {given} angles per subcarrier (expected: {expected})")]
+    InvalidAngleCount { expected: usize, given: usize },
+    #[error("Angle {angle} exceeds its bitsize of {bits}")]
+    AngleOutOfRange { angle: u16, bits: u8 },
     #[error("Encountered invalid/unhandled antenna config: nr: {nr_index}, nc: {nc_index}")]
     InvalidAntennaConfig { nr_index: u8, nc_index: u8 },
     #[error("Encountered invalid feedback type: {fb}")]
     InvalidFeedbackType { fb: u8 },
+    #[error("Encountered invalid/reserved subcarrier grouping: {grouping}")]
+    InvalidGrouping { grouping: u8 },
+    #[error("Encountered invalid/reserved bandwidth: {bandwidth}")]
+    InvalidBandwidth { bandwidth: u8 },
+    #[error("Encountered invalid codebook info: {codebook_info}")]
+    InvalidCodebookInfo { codebook_info: u8 },
+    #[error("Malformed radiotap header: {reason}")]
+    MalformedRadiotap { reason: String },
+    #[error("Malformed {link_type:?} header: {reason}")]
+    MalformedLinkHeader { link_type: LinkType, reason: String },
+    #[error("Unsupported capture link type: {linktype}")]
+    UnsupportedLinkType { linktype: i32 },
+    #[error("Encountered invalid RU range: {start} to {end}")]
+    InvalidRuRange { start: u8, end: u8 },
+    #[error("Frame carries a feedback segment ({remaining} remaining) and requires reassembly")]
+    SegmentedFeedback { remaining: u8 },
+    #[error("Frame truncated: {available} bytes available (required: {required})")]
+    TruncatedFrame { required: usize, available: usize },
+    #[error("Report truncated: {available} bytes available (required: {required})")]
+    TruncatedReport { required: usize, available: usize },
+    #[error("Frame check sequence mismatch, frame is corrupted")]
+    InvalidFcs,
+    #[error("Frame carries no beamforming feedback: {0}")]
+    NotApplicable(#[from] NotApplicableReason),
+}
+
+/// Reason why a frame is not considered for extraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum NotApplicableReason {
+    #[error("frame type {frame_type}, subtype {subtype} is not an action frame")]
+    FrameType { frame_type: u8, subtype: u8 },
+    #[error("action category {category}, action {action} is not a compressed beamforming frame")]
+    Action { category: u8, action: u8 },
+    #[error("NDP Announcement variant {variant} is not supported")]
+    NdpaVariant { variant: u8 },
+    #[error("trigger type {trigger_type} is not a Beamforming Report Poll")]
+    TriggerType { trigger_type: u8 },
+    #[error("CQI feedback carries no beamforming angles")]
+    CqiFeedback,
 }
 
 #[derive(Debug, Error)]
@@ -24,6 +69,26 @@ pub enum PersistenceError {
     #[cfg(feature = "parquet")]
     #[error("Error in writing parquet file: {0}")]
     Parquet(String),
+    #[error("Conversion of data to write failed: {0}")]
+    Conversion(#[from] BfmConversionError),
+}
+
+#[derive(Debug, Error)]
+pub enum GeneratorError {
+    #[error("Frames of {standard:?} reports can not be generated")]
+    UnsupportedStandard { standard: WifiStandard },
+    #[error("Encountered invalid/unhandled bandwidth: {bandwidth} MHz")]
+    InvalidBandwidth { bandwidth: u16 },
+    #[error("Received {given} entries of {field} (expected: {expected})")]
+    Mismatch {
+        field: &'static str,
+        expected: usize,
+        given: usize,
+    },
+    #[error("Information packing failed: {0}")]
+    Extraction(#[from] BfaExtractionError),
+    #[error("Error in writing pcap file: {0}")]
+    Pcap(#[from] pcap::Error),
 }
 
 #[allow(dead_code)]
@@ -31,6 +96,15 @@ pub enum PersistenceError {
 pub enum BfmConversionError {
     #[error("Encountered invalid/unhandled antenna config: nr: {nr_index}, nc: {nc_index}")]
     InvalidAntennaConfig { nr_index: u8, nc_index: u8 },
+    #[error(
+        "Feedback matrix of shape {rows}x{cols} does not match nr: {nr_index}, nc: {nc_index}"
+    )]
+    InvalidMatrixShape {
+        nr_index: u8,
+        nc_index: u8,
+        rows: usize,
+        cols: usize,
+    },
     #[error("Information extraction failed: {0}")]
     Extraction(#[from] BfaExtractionError),
 }
@@ -3,19 +3,28 @@
 //! At the end of a beamforming sensing procedure, the feedback matrix is sent
 //! unencrypted but compressed in a WiFi packet. In this module, we handle the
 //! decompression to obtain the original BFA angles, which parametrize the BFI.
+use crate::bfa_data::{BfaData, BfiMetadata, WifiStandard};
+use crate::eht_mimo_ctrl::EhtMimoControl;
 use crate::errors::BfaExtractionError;
 use crate::he_mimo_ctrl::Bandwidth;
 use crate::he_mimo_ctrl::HeMimoControl;
+use crate::ht_mimo_ctrl::HtMimoControl;
+use crate::subcarriers::{eht_subcarrier_indices, he_subcarrier_indices};
+use crate::vht_mimo_ctrl::VhtMimoControl;
+use ndarray::Array2;
+use std::sync::OnceLock;
 
 /// Config containing all required parameters to extract the original Phi
 /// and Psi angles from the compressed beamforming feedback information.
 #[rustfmt::skip]
 pub struct ExtractionConfig {
-	pub bitfield_pattern : Vec<u8>, // Length of bitfields per subcarrier-chunk
-	pub num_subcarrier   : usize,   // Number of subcarriers
+	pub bitfield_pattern   : Vec<u8>,          // Length of bitfields per subcarrier-chunk
+	pub num_subcarrier     : usize,            // Number of subcarriers
+	pub subcarrier_indices : Option<Vec<i16>>, // Tone indices of the subcarriers (if known)
 }
 
 /// Compressed Feedback contains two types of angles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Angles {
     Phi,
     Psi,
@@ -32,27 +41,50 @@ pub struct CompressedAngleBitSizes {
     pub psi_bit: u8,
 }
 
-/// Hardcoded angle patterns to retrieve for a few different combinations of number
-/// of receive antennas and spatial streams.
-/// 
+/// Maximum number of receive antennas (rows of the feedback matrix)
+const MAX_NUM_ROWS: usize = 8;
+
+/// Generate the angle pattern of a feedback matrix with `nr` rows and `nc` columns.
+///
 /// Every pattern captures the order and association to nr/nc number (the subscripts
 /// of the angles) in the order in which they appear in the Beamforming Feedback
-/// Information.
-/// 
-/// For different configurations, the exact parametrization differs, requiring
-/// different numbers of Phi and Psi angles. This array lists all the patterns
-/// of Phi/Psi that may occur.
-/// 
-/// TODO: Cite standard page
-#[rustfmt::skip]
-const ANGLE_PATTERNS: &[&[(Angles, usize, usize)]] = &[                                                                                          // (nr_index, nc_index):
-    &[(Phi,1,1), (Psi,2,1)],                                                                                                               // (1, 0) | (1, 2)
-    &[(Phi,1,1), (Phi,2,1), (Psi,2,1), (Psi,3,1)],                                                                                         // (2, 0)
-    &[(Phi,1,1), (Phi,2,1), (Psi,2,1), (Psi,3,1), (Phi,2,2), (Psi,3,2)],                                                                   // (2, 1) | (2, 2)
-    &[(Phi,1,1), (Phi,2,1), (Phi,3,1), (Psi,2,1), (Psi,3,1), (Psi,4,1)],                                                                   // (3, 0)
-    &[(Phi,1,1), (Phi,2,1), (Phi,3,1), (Psi,2,1), (Psi,3,1), (Psi,4,1), (Phi,2,2), (Phi,3,2), (Psi,3,2), (Psi,4,2)],                       // (3, 1)
-    &[(Phi,1,1), (Phi,2,1), (Phi,3,1), (Psi,2,1), (Psi,3,1), (Psi,4,1), (Phi,2,2), (Phi,3,2), (Psi,3,2), (Psi,4,2), (Phi,3,3), (Psi,4,3)]  // (3, 2) | (3, 3)
-];
+/// Information. For every column `i` of the Givens decomposition, first the Phi
+/// angles Phi(i,i) ... Phi(nr-1,i) are reported, followed by the Psi angles
+/// Psi(i+1,i) ... Psi(nr,i). Since the last column of a square matrix is fully
+/// determined by the others, at most `nr - 1` columns carry angles.
+///
+/// This is the order of the Givens decomposition of the feedback matrix (see
+/// IEEE 802.11-2016 19.3.12.3.6, Equation 19-79), which the standard tabulates
+/// for up to 8x8 in Table 9-68 (Order of angles in the Compressed Beamforming
+/// Feedback Matrix subfield). The same order applies to HT, VHT, HE and EHT.
+fn generate_pattern(nr: usize, nc: usize) -> Vec<(Angles, usize, usize)> {
+    let mut pattern = Vec::with_capacity(nr * (nr - 1));
+    for i in 1..=nc.min(nr - 1) {
+        pattern.extend((i..nr).map(|l| (Phi, l, i)));
+        pattern.extend((i + 1..=nr).map(|l| (Psi, l, i)));
+    }
+    pattern
+}
+
+/// Lazily generated angle patterns of all antenna configurations.
+///
+/// Indexed by `nr_index * MAX_NUM_ROWS + nc_index`. Configurations that do
+/// not exist (e.g. more columns than rows) are left empty.
+fn angle_patterns() -> &'static [Vec<(Angles, usize, usize)>] {
+    static PATTERNS: OnceLock<Vec<Vec<(Angles, usize, usize)>>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        (0..MAX_NUM_ROWS * MAX_NUM_ROWS)
+            .map(|i| {
+                let (nr, nc) = (i / MAX_NUM_ROWS + 1, i % MAX_NUM_ROWS + 1);
+                if nr < 2 || nc > nr {
+                    Vec::new()
+                } else {
+                    generate_pattern(nr, nc)
+                }
+            })
+            .collect()
+    })
+}
 
 pub fn get_angle_bit_sizes(
     codebook_info: u8,
@@ -75,7 +107,85 @@ pub fn get_angle_bit_sizes(
 
     Ok(bitsizes)
 }
+
+/// Bit-sizes of the angles in HT compressed beamforming feedback
+///
+/// HT uses a separate codebook with four different sizes, see
+/// IEEE 802.11n Table 7-25f.
+pub fn get_ht_angle_bit_sizes(
+    codebook_info: u8,
+) -> Result<CompressedAngleBitSizes, BfaExtractionError> {
+    #[rustfmt::skip]
+    let bitsizes = match codebook_info {
+        0 => CompressedAngleBitSizes { phi_bit: 3, psi_bit: 1 },
+        1 => CompressedAngleBitSizes { phi_bit: 4, psi_bit: 2 },
+        2 => CompressedAngleBitSizes { phi_bit: 5, psi_bit: 3 },
+        3 => CompressedAngleBitSizes { phi_bit: 6, psi_bit: 4 },
+        _ => return Err(BfaExtractionError::InvalidCodebookInfo { codebook_info }),
+    };
+
+    Ok(bitsizes)
+}
+
+/// Number of subcarriers of the VHT MU Exclusive Beamforming Report
+///
+/// VHT reports the delta SNR on a coarser grid than the angles.
+/// For more details see IEEE 802.11ac Table 8-53h.
+///
+/// # Parameters
+/// * `mimo_ctrl` - The MIMO control header
+pub fn vht_delta_snr_subcarriers(mimo_ctrl: &VhtMimoControl) -> Result<usize, BfaExtractionError> {
+    let num_sub = match (mimo_ctrl.grouping().value(), mimo_ctrl.bandwidth()) {
+        (0, Bandwidth::Bw20) => 30,
+        (0, Bandwidth::Bw40) => 58,
+        (0, Bandwidth::Bw80) => 122,
+        (0, Bandwidth::Bw160) => 244,
+        (1, Bandwidth::Bw20) => 16,
+        (1, Bandwidth::Bw40) => 30,
+        (1, Bandwidth::Bw80) => 62,
+        (1, Bandwidth::Bw160) => 124,
+        (2, Bandwidth::Bw20) => 10,
+        (2, Bandwidth::Bw40) => 16,
+        (2, Bandwidth::Bw80) => 32,
+        (2, Bandwidth::Bw160) => 64,
+        (grouping, _) => return Err(BfaExtractionError::InvalidGrouping { grouping }),
+    };
+
+    Ok(num_sub)
+}
+
+/// Number of angles reported per subcarrier
+///
+/// # Parameters
+/// * `nr_index` - Index for number of receive chains
+/// * `nc_index` - Index for number of columns (spatial streams)
+pub fn num_angles(nr_index: u8, nc_index: u8) -> Result<usize, BfaExtractionError> {
+    Ok(ExtractionConfig::get_pattern(nr_index, nc_index)?.len())
+}
+
+/// Bit-sizes of the angles of a report, depending on its standard
+///
+/// # Parameters
+/// * `metadata` - Metadata of the report
+pub(crate) fn angle_bit_sizes(
+    metadata: &BfiMetadata,
+) -> Result<CompressedAngleBitSizes, BfaExtractionError> {
+    match metadata.standard {
+        WifiStandard::Ht => get_ht_angle_bit_sizes(metadata.codebook_info),
+        _ => get_angle_bit_sizes(metadata.codebook_info, metadata.feedback_type),
+    }
+}
+
 impl ExtractionConfig {
+    /// Number of bytes occupied by the compressed angles in the payload.
+    ///
+    /// The angles of all subcarriers are packed back to back, the report
+    /// is padded to a full octet.
+    pub fn payload_length(&self) -> usize {
+        let bits_per_chunk: usize = self.bitfield_pattern.iter().map(|&b| b as usize).sum();
+        (bits_per_chunk * self.num_subcarrier).div_ceil(8)
+    }
+
     /// Find the Phi/Psi angle pattern from a configuration.
     ///
     /// # Parameters
@@ -85,15 +195,36 @@ impl ExtractionConfig {
         nr_index: u8,
         nc_index: u8,
     ) -> Result<&'static [(Angles, usize, usize)], BfaExtractionError> {
-        match (nr_index, nc_index) {
-            (1, 0) | (1, 2) => Ok(ANGLE_PATTERNS[0]),
-            (2, 0) => Ok(ANGLE_PATTERNS[1]),
-            (2, 1) | (2, 2) => Ok(ANGLE_PATTERNS[2]),
-            (3, 0) => Ok(ANGLE_PATTERNS[3]),
-            (3, 1) => Ok(ANGLE_PATTERNS[4]),
-            (3, 2) | (3, 3) => Ok(ANGLE_PATTERNS[5]),
-            _ => Err(BfaExtractionError::InvalidAntennaConfig { nr_index, nc_index }),
+        let (nr, nc) = (nr_index as usize + 1, nc_index as usize + 1);
+        if !(2..=MAX_NUM_ROWS).contains(&nr) || nc > nr {
+            return Err(BfaExtractionError::InvalidAntennaConfig { nr_index, nc_index });
         }
+
+        Ok(&angle_patterns()[nr_index as usize * MAX_NUM_ROWS + nc_index as usize])
+    }
+
+    /// Find the bitsizes of all angles of a subcarrier chunk in the order in
+    /// which they appear in the payload.
+    ///
+    /// # Parameters
+    /// * `nr_index` - Index for number of receive chains
+    /// * `nc_index` - Index for number of columns (spatial streams)
+    /// * `phi_psi` - Bit sizes of the Phi and Psi angles
+    fn get_bitfield_pattern(
+        nr_index: u8,
+        nc_index: u8,
+        phi_psi: &CompressedAngleBitSizes,
+    ) -> Result<Vec<u8>, BfaExtractionError> {
+        let bitfield_pattern = Self::get_pattern(nr_index, nc_index)?
+            .iter()
+            // First tuple element is the angle type
+            .map(|pattern| match pattern.0 {
+                Angles::Phi => phi_psi.phi_bit,
+                Angles::Psi => phi_psi.psi_bit,
+            })
+            .collect();
+
+        Ok(bitfield_pattern)
     }
 
     /// Get an extraction configuration from the HeMimoControl header specification
@@ -107,41 +238,138 @@ impl ExtractionConfig {
         let phi_psi = get_angle_bit_sizes(mimo_ctrl.codebook_info().value(),
         mimo_ctrl.feedback_type().value())?;
 
-        let nr_index = mimo_ctrl.nr_index().value();
-        let nc_index = mimo_ctrl.nc_index().value();
+        let bitfield_pattern = Self::get_bitfield_pattern(
+            mimo_ctrl.nr_index().value(),
+            mimo_ctrl.nc_index().value(),
+            &phi_psi,
+        )?;
 
-        let bitfield_pattern: Vec<u8> = Self::get_pattern(nr_index, nc_index)?
-            .iter()
-            // First tuple element is the angle type
-            .map(|pattern| match pattern.0 {
-                Angles::Phi => phi_psi.phi_bit,
-                Angles::Psi => phi_psi.psi_bit,
-            })
-            .collect();
+        // NOTE: based on grouping bit and the requested RU range the number
+        // of subcarrier change, see IEEE 802.11ax Table 9-91e for details.
+        let indices = he_subcarrier_indices(
+            mimo_ctrl.bandwidth(),
+            mimo_ctrl.grouping().value(),
+            mimo_ctrl.ru_start_index().value(),
+            mimo_ctrl.ru_end_index().value(),
+        )?;
+
+        Ok(ExtractionConfig {
+            bitfield_pattern,
+            num_subcarrier: indices.len(),
+            subcarrier_indices: Some(indices),
+        })
+    }
+
+    /// Get an extraction configuration from the VhtMimoControl header specification
+    ///
+    /// # Parameters
+    /// * `mimo_ctrl` - The MIMO control header
+    pub fn from_vht_mimo_ctrl(mimo_ctrl: &VhtMimoControl) -> Result<Self, BfaExtractionError> {
+        // NOTE: VHT uses the same codebook as HE for both SU and MU feedback.
+        #[rustfmt::skip]
+        let phi_psi = get_angle_bit_sizes(mimo_ctrl.codebook_info().value(),
+        mimo_ctrl.feedback_type().value())?;
+
+        let bitfield_pattern = Self::get_bitfield_pattern(
+            mimo_ctrl.nr_index().value(),
+            mimo_ctrl.nc_index().value(),
+            &phi_psi,
+        )?;
 
-        // NOTE: based on grouping bit the number of subcarrier change
-        // for more details see IEEE 802.11ax Table 9-91a and Table 9-91e
+        // NOTE: grouping encodes Ng=1, 2 and 4, value 3 is reserved.
+        // Pilot and DC tones carry no feedback and are not counted.
+        // for more details see IEEE 802.11ac Table 8-53g
         let num_sub = match (mimo_ctrl.grouping().value(), mimo_ctrl.bandwidth()) {
-            (0, Bandwidth::Bw20) => 64,
-            (0, Bandwidth::Bw40) => 122,
-            (0, Bandwidth::Bw80) => 250,
-            (0, Bandwidth::Bw160) => 500,
-            (1, Bandwidth::Bw20) => 50,
-            (1, Bandwidth::Bw40) => 32,
-            (1, Bandwidth::Bw80) => 64,
-            (1, Bandwidth::Bw160) => 160,
-            _ => unreachable!("Grouping is a single bit; This branch should be impossible!"),
+            (0, Bandwidth::Bw20) => 52,
+            (0, Bandwidth::Bw40) => 108,
+            (0, Bandwidth::Bw80) => 234,
+            (0, Bandwidth::Bw160) => 468,
+            (1, Bandwidth::Bw20) => 30,
+            (1, Bandwidth::Bw40) => 58,
+            (1, Bandwidth::Bw80) => 122,
+            (1, Bandwidth::Bw160) => 244,
+            (2, Bandwidth::Bw20) => 16,
+            (2, Bandwidth::Bw40) => 30,
+            (2, Bandwidth::Bw80) => 62,
+            (2, Bandwidth::Bw160) => 124,
+            (grouping, _) => return Err(BfaExtractionError::InvalidGrouping { grouping }),
         };
 
         Ok(ExtractionConfig {
             bitfield_pattern,
             num_subcarrier: num_sub,
+            subcarrier_indices: None,
+        })
+    }
+
+    /// Get an extraction configuration from the HtMimoControl header specification
+    ///
+    /// # Parameters
+    /// * `mimo_ctrl` - The MIMO control header
+    pub fn from_ht_mimo_ctrl(mimo_ctrl: &HtMimoControl) -> Result<Self, BfaExtractionError> {
+        let phi_psi = get_ht_angle_bit_sizes(mimo_ctrl.codebook_info().value())?;
+
+        let bitfield_pattern = Self::get_bitfield_pattern(
+            mimo_ctrl.nr_index().value(),
+            mimo_ctrl.nc_index().value(),
+            &phi_psi,
+        )?;
+
+        // NOTE: grouping encodes Ng=1, 2 and 4, value 3 is reserved.
+        // for more details see IEEE 802.11n Table 7-25e
+        let num_sub = match (
+            mimo_ctrl.grouping().value(),
+            mimo_ctrl.channel_width().value(),
+        ) {
+            (0, 0) => 56,
+            (0, 1) => 114,
+            (1, 0) => 30,
+            (1, 1) => 58,
+            (2, 0) => 16,
+            (2, 1) => 30,
+            (grouping, _) => return Err(BfaExtractionError::InvalidGrouping { grouping }),
+        };
+
+        Ok(ExtractionConfig {
+            bitfield_pattern,
+            num_subcarrier: num_sub,
+            subcarrier_indices: None,
+        })
+    }
+
+    /// Get an extraction configuration from the EhtMimoControl header specification
+    ///
+    /// # Parameters
+    /// * `mimo_ctrl` - The MIMO control header
+    pub fn from_eht_mimo_ctrl(mimo_ctrl: &EhtMimoControl) -> Result<Self, BfaExtractionError> {
+        #[rustfmt::skip]
+        let phi_psi = get_angle_bit_sizes(mimo_ctrl.codebook_info().value(),
+        mimo_ctrl.feedback_type().value())?;
+
+        let bitfield_pattern = Self::get_bitfield_pattern(
+            mimo_ctrl.nr_index().value(),
+            mimo_ctrl.nc_index().value(),
+            &phi_psi,
+        )?;
+
+        // NOTE: EHT only reports the subchannels requested in the partial
+        // bandwidth info, so the number of subcarriers is taken from the
+        // reported tone set.
+        let indices = eht_subcarrier_indices(
+            mimo_ctrl.bandwidth(),
+            mimo_ctrl.grouping().value(),
+            mimo_ctrl.partial_bw_info(),
+        )?;
+
+        Ok(ExtractionConfig {
+            bitfield_pattern,
+            num_subcarrier: indices.len(),
+            subcarrier_indices: Some(indices),
         })
     }
 }
 
 /// Some sanity checks for the BFA bitfield extraction
-#[cfg(debug_assertions)]
 fn sanity_check_extraction(
     bitfield_pattern: &[u8],
     num_chunks: usize,
@@ -182,13 +410,8 @@ fn sanity_check_extraction(
 /// To extract bits from LSB, we pre-shift new bytes' bitpattern to the
 /// front and simply mask out the correct bits to extract.
 ///
-/// Also assumes that bitfield_pattern never contains a value greater
-/// than 16.
-///
-/// # Warning
-///
-/// This function assumes that bfa_payload is at least of size 2.
-/// This requirement is not tested, so it will panic if violated.
+/// The byte stream must contain enough bits for all chunks and the pattern
+/// must not contain bitsizes greater than 9, otherwise an error is returned.
 ///
 /// # Parameters
 /// * `byte_stream` - The bytestream (packet payload containing compressed BFI)
@@ -201,11 +424,9 @@ fn extract_bitfields(
     byte_stream: &[u8],
     bitfield_pattern: Vec<u8>,
     num_chunks: usize,
-) -> Result<Vec<Vec<u16>>, BfaExtractionError> {
-    // Start with some sanity checks in debug mode. In release mode, we
-    // leave them out for performance reasons. This will cause a crash in
-    // API violations, but that's on you  ¯\_(ツ)_/¯
-    #[cfg(debug_assertions)]
+) -> Result<Array2<u16>, BfaExtractionError> {
+    // Start with some sanity checks. These guarantee that the window below
+    // never advances past the end of the byte stream.
     sanity_check_extraction(bitfield_pattern.as_slice(), num_chunks, byte_stream.len())?;
 
     // --------------------------------------------------------------------------
@@ -216,17 +437,17 @@ fn extract_bitfields(
     // in the buffer. Therefore, to extract a bitfield of size N, the window must
     // be at least N+7 bits to handle the worst case. For BFI, the WiFi standard
     // specifies at most a bitsize of 9 for an angle, so a 16bit buffer suffices.
-    let mut bit_window = u16::from_le_bytes([byte_stream[0], byte_stream[1]]);
+    // NOTE: Streams shorter than the window are padded with zeros
+    let window_byte = |i: usize| byte_stream.get(i).copied().unwrap_or(0);
+    let mut bit_window = u16::from_le_bytes([window_byte(0), window_byte(1)]);
     let mut window_offset = 0; // bit-offset pointing past last processed bit
     let mut curr_byte = 2; // stream offset past current window edge
 
-    // Preallocate result vectors and bitmasks
-    let mut result = Vec::with_capacity(num_chunks);
-    let mut chunk = Vec::with_capacity(bitfield_pattern.len());
+    // Preallocate the (flat) result and bitmasks
+    let mut result = Vec::with_capacity(num_chunks * bitfield_pattern.len());
     let masks: Vec<u16> = bitfield_pattern.iter().map(|&l| (1 << l) - 1).collect();
 
     for _ in 0..num_chunks {
-        chunk.clear();
         for (i, &bit_length) in bitfield_pattern.iter().enumerate() {
             // If the to-be-processed bitfield is not completely within the
             // 16 bit, we need to advance the window.
@@ -242,17 +463,18 @@ fn extract_bitfields(
             let mask = masks[i];
             let bitfield = (bit_window >> window_offset) & mask;
 
-            // Add the extracted bitfield to the chunk and advance pointer to
+            // Add the extracted bitfield to the result and advance pointer to
             // next bits in window to be processed.
-            chunk.push(bitfield);
+            result.push(bitfield);
             window_offset += bit_length;
         }
-
-        // Collect the chunk
-        result.push(chunk.clone());
     }
 
-    Ok(result)
+    // Chunks are stored back to back, one row per subcarrier
+    Ok(
+        Array2::from_shape_vec((num_chunks, bitfield_pattern.len()), result)
+            .expect("one bitfield per pattern entry and chunk"),
+    )
 }
 
 /// Extract BFA from payload using the corresponding extraction config
@@ -263,7 +485,7 @@ fn extract_bitfields(
 pub fn extract_bfa(
     bfa_payload: &[u8],
     extraction_config: ExtractionConfig,
-) -> Result<Vec<Vec<u16>>, BfaExtractionError> {
+) -> Result<Array2<u16>, BfaExtractionError> {
     extract_bitfields(
         bfa_payload,
         extraction_config.bitfield_pattern,
@@ -271,12 +493,213 @@ pub fn extract_bfa(
     )
 }
 
+/// Pack bitfields into a bytestream
+///
+/// Inverse of `extract_bitfields`: the bitfields of all chunks are written
+/// back to back starting from the LSB, and the stream is padded to a full
+/// octet.
+///
+/// # Parameters
+/// * `bitfields` - Array of bitfields of dimension (num_chunks, num_bitfields)
+/// * `bitfield_pattern` - Bitsize of every bitfield of a chunk
+fn pack_bitfields(
+    bitfields: &Array2<u16>,
+    bitfield_pattern: &[u8],
+) -> Result<Vec<u8>, BfaExtractionError> {
+    if bitfields.ncols() != bitfield_pattern.len() {
+        return Err(BfaExtractionError::InvalidAngleCount {
+            expected: bitfield_pattern.len(),
+            given: bitfields.ncols(),
+        });
+    }
+
+    // Same window idea as for the extraction, in reverse: bitfields are
+    // shifted in above the pending bits and full bytes are flushed from the
+    // bottom. A 32 bit window leaves enough room for 16 bit fields.
+    let bits_per_chunk: usize = bitfield_pattern.iter().map(|&b| b as usize).sum();
+    let mut byte_stream = Vec::with_capacity((bits_per_chunk * bitfields.nrows()).div_ceil(8));
+    let mut bit_window: u32 = 0;
+    let mut window_offset = 0; // number of pending bits in the window
+
+    for chunk in bitfields.rows() {
+        for (&bitfield, &bit_length) in chunk.iter().zip(bitfield_pattern) {
+            if (bitfield as u32) >> bit_length != 0 {
+                return Err(BfaExtractionError::AngleOutOfRange {
+                    angle: bitfield,
+                    bits: bit_length,
+                });
+            }
+            bit_window |= (bitfield as u32) << window_offset;
+            window_offset += bit_length as u32;
+            while window_offset >= 8 {
+                byte_stream.push(bit_window as u8);
+                bit_window >>= 8;
+                window_offset -= 8;
+            }
+        }
+    }
+
+    // Pad the remaining bits to a full octet
+    if window_offset > 0 {
+        byte_stream.push(bit_window as u8);
+    }
+
+    Ok(byte_stream)
+}
+
+/// Pack BFA angles into the payload of a compressed beamforming report
+///
+/// Inverse of `extract_bfa`, using the bitsizes of the angles described by
+/// the metadata of the data.
+///
+/// # Parameters
+/// * `bfa` - The BFA data to pack
+pub fn pack_bfa(bfa: &BfaData) -> Result<Vec<u8>, BfaExtractionError> {
+    let phi_psi = angle_bit_sizes(&bfa.metadata)?;
+    let bitfield_pattern = ExtractionConfig::get_bitfield_pattern(
+        bfa.metadata.nr_index,
+        bfa.metadata.nc_index,
+        &phi_psi,
+    )?;
+    pack_bitfields(&bfa.bfa_angles, &bitfield_pattern)
+}
+
+/// Decode the average SNR fields of a compressed beamforming report
+///
+/// Each space-time stream reports its average SNR as an 8 bit two's complement
+/// integer, mapping -128 to -10 dB and 127 to 53.75 dB in steps of 0.25 dB.
+/// See IEEE 802.11ax Table 9-91b (Average SNR of Space-Time Stream i).
+///
+/// # Parameters
+/// * `snr_payload` - Bytestream starting at the first SNR field
+/// * `num_streams` - Number of space-time streams (i.e. Nc)
+///
+/// # Returns
+/// * Average SNR per stream in dB
+pub fn extract_snr(snr_payload: &[u8], num_streams: usize) -> Result<Vec<f32>, BfaExtractionError> {
+    let snr_fields = snr_payload
+        .get(..num_streams)
+        .ok_or(BfaExtractionError::TruncatedReport {
+            required: num_streams,
+            available: snr_payload.len(),
+        })?;
+
+    Ok(snr_fields
+        .iter()
+        .map(|&raw| 22.0 + (raw as i8) as f32 * 0.25)
+        .collect())
+}
+
+/// Encode the average SNR fields of a compressed beamforming report
+///
+/// Inverse of `extract_snr`; values are rounded to the closest step and
+/// saturate at the ends of the range.
+///
+/// # Parameters
+/// * `snr` - Average SNR per stream in dB
+#[cfg_attr(not(feature = "bfi_metadata"), allow(dead_code))]
+pub(crate) fn pack_snr(snr: &[f32]) -> Vec<u8> {
+    snr.iter()
+        .map(|&snr| ((snr - 22.0) * 4.0).round().clamp(-128.0, 127.0) as i8 as u8)
+        .collect()
+}
+
+/// Decode the MU Exclusive Beamforming Report (delta SNR per subcarrier)
+///
+/// For each reported subcarrier, the report contains a 4 bit two's complement
+/// delta SNR value (in dB, ranging from -8 to 7) of every space-time stream.
+/// See IEEE 802.11ax 9.4.1.65 (HE MU Exclusive Beamforming Report field).
+///
+/// Padding nibbles and trailing bytes beyond the reported subcarriers are ignored.
+///
+/// # Parameters
+/// * `delta_snr_payload` - Bytestream containing the MU exclusive report
+/// * `num_streams` - Number of space-time streams (i.e. Nc)
+/// * `num_subcarrier` - Number of reported subcarriers
+///
+/// # Returns
+/// * Delta SNR of dimension (num_streams, num_subcarrier)
+pub fn extract_delta_snr(
+    delta_snr_payload: &[u8],
+    num_streams: usize,
+    num_subcarrier: usize,
+) -> Result<Array2<i8>, BfaExtractionError> {
+    let required = (num_streams * num_subcarrier).div_ceil(2);
+    let delta_snr_fields =
+        delta_snr_payload
+            .get(..required)
+            .ok_or(BfaExtractionError::TruncatedReport {
+                required,
+                available: delta_snr_payload.len(),
+            })?;
+
+    // NOTE: Nibbles are packed starting from the least significant bits
+    let nibbles = delta_snr_fields
+        .iter()
+        .flat_map(|&byte| [byte << 4, byte & 0xf0])
+        // Sign-extend the 4 bit value by an arithmetic shift
+        .map(|nibble| (nibble as i8) >> 4);
+
+    let mut delta_snr = Array2::zeros((num_streams, num_subcarrier));
+    for (i, value) in nibbles.take(num_streams * num_subcarrier).enumerate() {
+        delta_snr[(i % num_streams, i / num_streams)] = value;
+    }
+    Ok(delta_snr)
+}
+
+/// Encode the MU Exclusive Beamforming Report (delta SNR per subcarrier)
+///
+/// Inverse of `extract_delta_snr`; values saturate at the ends of the range.
+///
+/// # Parameters
+/// * `delta_snr` - Delta SNR of dimension (num_streams, num_subcarrier)
+#[cfg_attr(not(feature = "bfi_metadata"), allow(dead_code))]
+pub(crate) fn pack_delta_snr(delta_snr: &Array2<i8>) -> Vec<u8> {
+    // NOTE: Column-major iteration yields the streams of a subcarrier consecutively
+    let nibbles: Vec<u8> = delta_snr
+        .t()
+        .iter()
+        .map(|&value| value.clamp(-8, 7) as u8 & 0x0f)
+        .collect();
+    nibbles
+        .chunks(2)
+        .map(|pair| pair[0] | pair.get(1).map_or(0, |high| high << 4))
+        .collect()
+}
+
+/// Decode the HE CQI Report (average SNR per RU26 and stream)
+///
+/// For each RU26 of the requested span, the report contains the average SNR
+/// of every space-time stream, encoded like the average SNR of compressed
+/// beamforming reports. See IEEE 802.11ax 9.4.1.66 (HE CQI Report field).
+///
+/// # Parameters
+/// * `cqi_payload` - Bytestream containing the CQI report
+/// * `num_streams` - Number of space-time streams (i.e. Nc)
+/// * `num_ru` - Number of reported RU26
+///
+/// # Returns
+/// * Average SNR (in dB) of dimension (num_streams, num_ru)
+pub fn extract_cqi(
+    cqi_payload: &[u8],
+    num_streams: usize,
+    num_ru: usize,
+) -> Result<Array2<f32>, BfaExtractionError> {
+    let snr = extract_snr(cqi_payload, num_streams * num_ru)?;
+
+    // NOTE: The SNR values of all streams of an RU are reported consecutively
+    Ok(Array2::from_shape_vec((num_ru, num_streams), snr)
+        .expect("Number of SNR values matches the shape")
+        .reversed_axes())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ndarray::array;
     #[test]
     fn extractioncfg_parsing_2by1() {
-        let byte_stream: &[u8] = &[0b11001000, 0b10000100, 0b00000000, 0b11000100, 0b00001101];
+        let byte_stream: &[u8] = &[0b11001000, 0b10000100, 0b10000000, 0b11100100, 0b00001101];
 
         let result_he_mimo = HeMimoControl::from_buf(byte_stream);
         let result_he_ctrl = ExtractionConfig::from_he_mimo_ctrl(&result_he_mimo).unwrap();
@@ -288,7 +711,7 @@ mod tests {
 
     #[test]
     fn extractioncfg_parsing_3by2() {
-        let byte_stream: &[u8] = &[0b10010001, 0b10000000, 0b00000000, 0b11000100, 0b00001101];
+        let byte_stream: &[u8] = &[0b10010001, 0b10000000, 0b00000000, 0b11010010, 0b00001101];
 
         let result_he_mimo = HeMimoControl::from_buf(byte_stream);
         let result_he_ctrl = ExtractionConfig::from_he_mimo_ctrl(&result_he_mimo).unwrap();
@@ -300,7 +723,7 @@ mod tests {
 
     #[test]
     fn extractioncfg_parsing_4by1() {
-        let byte_stream: &[u8] = &[0b01011000, 0b10000010, 0b00000000, 0b11000100, 0b00001101];
+        let byte_stream: &[u8] = &[0b01011000, 0b10000010, 0b10000000, 0b11001000, 0b00001101];
 
         let result_he_mimo = HeMimoControl::from_buf(byte_stream);
         let result_he_ctrl = ExtractionConfig::from_he_mimo_ctrl(&result_he_mimo).unwrap();
@@ -324,14 +747,153 @@ mod tests {
 
     #[test]
     fn extractioncfg_parsing_4by4() {
-        let byte_stream: &[u8] = &[0b11011011, 0b10000111, 0b00000000, 0b11000100, 0b00001101];
+        let byte_stream: &[u8] = &[0b11011011, 0b10000111, 0b10000000, 0b11100100, 0b00001101];
 
         let result_he_mimo = HeMimoControl::from_buf(byte_stream);
         let result_he_ctrl = ExtractionConfig::from_he_mimo_ctrl(&result_he_mimo).unwrap();
         let expected_bitfield_pattern = vec![9, 9, 9, 7, 7, 7, 9, 9, 7, 7, 9, 7]; // 9 phi, 7 psi
 
         assert_eq!(result_he_ctrl.bitfield_pattern, expected_bitfield_pattern);
-        assert_eq!(result_he_ctrl.num_subcarrier, 160); //BW 160
+        assert_eq!(result_he_ctrl.num_subcarrier, 128); //BW 160, Ng 16
+    }
+
+    #[test]
+    fn extractioncfg_parsing_partial_bw() {
+        // 4x4 BW 160, Ng 16 with feedback requested only for RU26 0 to 8
+        let byte_stream: &[u8] = &[0b11011011, 0b10000111, 0b00000000, 0b11000100, 0b00001101];
+
+        let result_he_mimo = HeMimoControl::from_buf(byte_stream);
+        let result_he_ctrl = ExtractionConfig::from_he_mimo_ctrl(&result_he_mimo).unwrap();
+
+        assert_eq!(result_he_ctrl.num_subcarrier, 16);
+    }
+
+    #[test]
+    fn extractioncfg_parsing_vht_4by2() {
+        // Nc=2, Nr=4, BW 80, Ng=1, codebook 1, SU
+        let byte_stream: &[u8] = &[0b10011001, 0b10000100, 0b10101000];
+
+        let result_vht_mimo = VhtMimoControl::from_buf(byte_stream);
+        let result_vht_ctrl = ExtractionConfig::from_vht_mimo_ctrl(&result_vht_mimo).unwrap();
+        let expected_bitfield_pattern = vec![6, 6, 6, 4, 4, 4, 6, 6, 4, 4]; // 6 phi, 4 psi
+
+        assert_eq!(result_vht_ctrl.bitfield_pattern, expected_bitfield_pattern);
+        assert_eq!(result_vht_ctrl.num_subcarrier, 234); //BW 80
+    }
+
+    #[test]
+    fn extractioncfg_parsing_vht_subcarriers() {
+        // (grouping, bandwidth, Ns) for every Ng and bandwidth
+        let cases = [
+            (0, 0, 52),
+            (0, 1, 108),
+            (0, 2, 234),
+            (0, 3, 468),
+            (1, 0, 30),
+            (1, 1, 58),
+            (1, 2, 122),
+            (1, 3, 244),
+            (2, 0, 16),
+            (2, 1, 30),
+            (2, 2, 62),
+            (2, 3, 124),
+        ];
+        for (grouping, bandwidth, expected) in cases {
+            // Nc=1, Nr=2, codebook 0, SU
+            let byte_stream: &[u8] = &[0b00001000 | (bandwidth << 6), grouping, 0b00000100];
+
+            let result_vht_mimo = VhtMimoControl::from_buf(byte_stream);
+            let result_vht_ctrl = ExtractionConfig::from_vht_mimo_ctrl(&result_vht_mimo).unwrap();
+
+            assert_eq!(
+                result_vht_ctrl.num_subcarrier, expected,
+                "grouping {grouping}, bandwidth {bandwidth}"
+            );
+        }
+    }
+
+    #[test]
+    fn extractioncfg_parsing_vht_2by1_mu() {
+        // Nc=1, Nr=2, BW 20, Ng=4, codebook 0, MU
+        let byte_stream: &[u8] = &[0b00001000, 0b10001010, 0b00000100];
+
+        let result_vht_mimo = VhtMimoControl::from_buf(byte_stream);
+        let result_vht_ctrl = ExtractionConfig::from_vht_mimo_ctrl(&result_vht_mimo).unwrap();
+        let expected_bitfield_pattern = vec![7, 5]; // 7 phi, 5 psi
+
+        assert_eq!(result_vht_ctrl.bitfield_pattern, expected_bitfield_pattern);
+        assert_eq!(result_vht_ctrl.num_subcarrier, 16); //BW 20, Ng=4
+        assert_eq!(vht_delta_snr_subcarriers(&result_vht_mimo).unwrap(), 10);
+    }
+
+    #[test]
+    fn vht_delta_snr_subcarrier_parsing() {
+        // (grouping, bandwidth, Ns') for every Ng and bandwidth
+        let cases = [
+            (0, 0, 30),
+            (0, 1, 58),
+            (0, 2, 122),
+            (0, 3, 244),
+            (1, 0, 16),
+            (1, 1, 30),
+            (1, 2, 62),
+            (1, 3, 124),
+            (2, 0, 10),
+            (2, 1, 16),
+            (2, 2, 32),
+            (2, 3, 64),
+        ];
+        for (grouping, bandwidth, expected) in cases {
+            // Nc=1, Nr=2, codebook 0, MU
+            let byte_stream: &[u8] = &[0b00001000 | (bandwidth << 6), 0b00001000 | grouping, 0];
+
+            let result_vht_mimo = VhtMimoControl::from_buf(byte_stream);
+            assert_eq!(
+                vht_delta_snr_subcarriers(&result_vht_mimo).unwrap(),
+                expected,
+                "grouping {grouping}, bandwidth {bandwidth}"
+            );
+        }
+    }
+
+    #[test]
+    fn extractioncfg_parsing_vht_reserved_grouping() {
+        // Nc=1, Nr=2, BW 20, grouping 3 (reserved)
+        let byte_stream: &[u8] = &[0b00001000, 0b10000011, 0b00000100];
+
+        let result_vht_mimo = VhtMimoControl::from_buf(byte_stream);
+        let result_vht_ctrl = ExtractionConfig::from_vht_mimo_ctrl(&result_vht_mimo);
+
+        assert!(matches!(
+            result_vht_ctrl,
+            Err(BfaExtractionError::InvalidGrouping { grouping: 3 })
+        ));
+    }
+
+    #[test]
+    fn extractioncfg_parsing_eht_4by2() {
+        // Nc=2, Nr=4, BW 320, Ng=4, codebook 1, MU, full bandwidth
+        let byte_stream: &[u8] = &[0b00110001, 0b00010100, 0b11111110, 0b11111111, 0b00010101];
+
+        let result_eht_mimo = EhtMimoControl::from_buf(byte_stream);
+        let result_eht_ctrl = ExtractionConfig::from_eht_mimo_ctrl(&result_eht_mimo).unwrap();
+        let expected_bitfield_pattern = vec![9, 9, 9, 7, 7, 7, 9, 9, 7, 7]; // 9 phi, 7 psi
+
+        assert_eq!(result_eht_ctrl.bitfield_pattern, expected_bitfield_pattern);
+        assert_eq!(result_eht_ctrl.num_subcarrier, 1000); //BW 320
+    }
+
+    #[test]
+    fn extractioncfg_parsing_ht_4by2() {
+        // Nc=2, Nr=4, BW 40, Ng=4, codebook 2
+        let byte_stream: &[u8] = &[0b11011101, 0b00000100, 0x78, 0x56, 0x34, 0x12];
+
+        let result_ht_mimo = HtMimoControl::from_buf(byte_stream);
+        let result_ht_ctrl = ExtractionConfig::from_ht_mimo_ctrl(&result_ht_mimo).unwrap();
+        let expected_bitfield_pattern = vec![5, 5, 5, 3, 3, 3, 5, 5, 3, 3]; // 5 phi, 3 psi
+
+        assert_eq!(result_ht_ctrl.bitfield_pattern, expected_bitfield_pattern);
+        assert_eq!(result_ht_ctrl.num_subcarrier, 30); //BW 40, Ng=4
     }
 
     #[test]
@@ -341,10 +903,7 @@ mod tests {
         // Chunk:          010100 1100 0011 110011 1010 0111 (1100)
         // Reverse:        001010 0011 1100 110011 0101 1110
         let byte_stream: &[u8] = &[0b11001010, 0b11110000, 0b01011100, 0b00111110];
-        let expected: Vec<Vec<u16>> = vec![
-            vec![0b001010, 0b0011, 0b1100],
-            vec![0b110011, 0b0101, 0b1110],
-        ];
+        let expected: Array2<u16> = array![[0b001010, 0b0011, 0b1100], [0b110011, 0b0101, 0b1110],];
 
         // Example pattern (6 bits, 4 bits, 4 bits) x 2
         let bitfield_pattern = vec![6, 4, 4];
@@ -369,7 +928,7 @@ mod tests {
         // Chunk:          010100110 00011 11
         // Reverse:        011001010 11000 11
         let byte_stream: &[u8] = &[0b11001010, 0b11110000];
-        let expected: Vec<Vec<u16>> = vec![vec![0b011001010, 0b11000, 0b11]];
+        let expected: Array2<u16> = array![[0b011001010, 0b11000, 0b11]];
 
         // use longer bitsize of 9
         let bitfield_pattern = vec![9, 5, 2];
@@ -405,12 +964,12 @@ mod tests {
         assert!(result.is_ok());
 
         let result = result.unwrap();
-        let expected: Vec<Vec<u16>> = vec![
-            vec![
+        let expected: Array2<u16> = array![
+            [
                 0b010111, 0b111110, 0b111001, 0b0100, 0b0101, 0b0111, 0b100111, 0b100011, 0b1011,
                 0b1000,
             ],
-            vec![
+            [
                 0b010111, 0b111110, 0b111001, 0b0100, 0b0101, 0b0111, 0b100111, 0b100011, 0b1011,
                 0b0111,
             ],
@@ -440,7 +999,7 @@ mod tests {
         let result = extract_bitfields(byte_stream_extract, expected_bitfield_pattern, num_chunks);
         assert!(result.is_ok());
         let result = result.unwrap();
-        let expected: Vec<Vec<u16>> = vec![vec![
+        let expected: Array2<u16> = array![[
             0b110010111,
             0b111001111,
             0b101010100,
@@ -489,5 +1048,207 @@ mod tests {
         assert_eq!(ExtractionConfig::get_pattern(3, 3).unwrap().len(), 12);
         // Invalid case: (nr_index, nc_index) = (0, 0) should yield an error.
         assert!(ExtractionConfig::get_pattern(0, 0).is_err());
+        // Invalid case: more columns than rows should yield an error.
+        assert!(ExtractionConfig::get_pattern(1, 2).is_err());
+        // Invalid case: more than 8 rows should yield an error.
+        assert!(ExtractionConfig::get_pattern(8, 0).is_err());
+    }
+
+    /// Validates the generated patterns against the previously hand-written table.
+    #[test]
+    fn test_get_pattern_legacy_table() {
+        type Pattern = &'static [(Angles, usize, usize)];
+        #[rustfmt::skip]
+        let legacy: &[(&[(u8, u8)], Pattern)] = &[
+            (&[(1, 0), (1, 2)], &[(Phi,1,1), (Psi,2,1)]),
+            (&[(2, 0)],         &[(Phi,1,1), (Phi,2,1), (Psi,2,1), (Psi,3,1)]),
+            (&[(2, 1), (2, 2)], &[(Phi,1,1), (Phi,2,1), (Psi,2,1), (Psi,3,1), (Phi,2,2), (Psi,3,2)]),
+            (&[(3, 0)],         &[(Phi,1,1), (Phi,2,1), (Phi,3,1), (Psi,2,1), (Psi,3,1), (Psi,4,1)]),
+            (&[(3, 1)],         &[(Phi,1,1), (Phi,2,1), (Phi,3,1), (Psi,2,1), (Psi,3,1), (Psi,4,1), (Phi,2,2), (Phi,3,2), (Psi,3,2), (Psi,4,2)]),
+            (&[(3, 2), (3, 3)], &[(Phi,1,1), (Phi,2,1), (Phi,3,1), (Psi,2,1), (Psi,3,1), (Psi,4,1), (Phi,2,2), (Phi,3,2), (Psi,3,2), (Psi,4,2), (Phi,3,3), (Psi,4,3)]),
+        ];
+
+        for (configs, expected) in legacy {
+            for &(nr_index, nc_index) in configs.iter() {
+                // NOTE: The table accepted (1, 2), i.e. more columns than rows,
+                // which is no valid antenna configuration and rejected now.
+                if nc_index > nr_index {
+                    assert!(matches!(
+                        ExtractionConfig::get_pattern(nr_index, nc_index),
+                        Err(BfaExtractionError::InvalidAntennaConfig {
+                            nr_index: 1,
+                            nc_index: 2
+                        })
+                    ));
+                    continue;
+                }
+                let pattern = ExtractionConfig::get_pattern(nr_index, nc_index).unwrap();
+                assert_eq!(
+                    pattern, *expected,
+                    "nr_index: {nr_index}, nc_index: {nc_index}"
+                );
+            }
+        }
+    }
+
+    /// Validates the generated patterns of all antenna configurations up to 8x8.
+    ///
+    /// Every column `i` of the decomposition contributes `nr - i` Phi and as many
+    /// Psi angles, all of which are ordered by their row subscript.
+    #[test]
+    fn test_get_pattern_all_shapes() {
+        for nr in 2..=MAX_NUM_ROWS {
+            for nc in 1..=nr {
+                let pattern = ExtractionConfig::get_pattern(nr as u8 - 1, nc as u8 - 1).unwrap();
+                let num_columns = nc.min(nr - 1);
+                let expected_len: usize = (1..=num_columns).map(|i| 2 * (nr - i)).sum();
+                assert_eq!(pattern.len(), expected_len, "nr: {nr}, nc: {nc}");
+
+                let mut offset = 0;
+                for i in 1..=num_columns {
+                    let phis = &pattern[offset..offset + nr - i];
+                    let psis = &pattern[offset + nr - i..offset + 2 * (nr - i)];
+                    let expected_phis: Vec<_> = (i..nr).map(|l| (Phi, l, i)).collect();
+                    let expected_psis: Vec<_> = (i + 1..=nr).map(|l| (Psi, l, i)).collect();
+                    assert_eq!(phis, expected_phis.as_slice(), "nr: {nr}, nc: {nc}");
+                    assert_eq!(psis, expected_psis.as_slice(), "nr: {nr}, nc: {nc}");
+                    offset += 2 * (nr - i);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_pattern_8by2() {
+        #[rustfmt::skip]
+        let expected = [
+            (Phi,1,1), (Phi,2,1), (Phi,3,1), (Phi,4,1), (Phi,5,1), (Phi,6,1), (Phi,7,1),
+            (Psi,2,1), (Psi,3,1), (Psi,4,1), (Psi,5,1), (Psi,6,1), (Psi,7,1), (Psi,8,1),
+            (Phi,2,2), (Phi,3,2), (Phi,4,2), (Phi,5,2), (Phi,6,2), (Phi,7,2),
+            (Psi,3,2), (Psi,4,2), (Psi,5,2), (Psi,6,2), (Psi,7,2), (Psi,8,2),
+        ];
+        assert_eq!(ExtractionConfig::get_pattern(7, 1).unwrap(), expected);
+    }
+
+    #[test]
+    fn snr_decoding() {
+        let byte_stream: &[u8] = &[0x80, 0x7f, 0x00, 0xd8, 0xff];
+        let result = extract_snr(byte_stream, 4).unwrap();
+        assert_eq!(result, vec![-10.0, 53.75, 22.0, 12.0]);
+
+        assert!(matches!(
+            extract_snr(&byte_stream[..2], 4),
+            Err(BfaExtractionError::TruncatedReport {
+                required: 4,
+                available: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn bitfield_packing() {
+        // Inverse of `bitfield_extraction_short_stream`
+        let packed = pack_bitfields(&array![[0b101, 0b10110]], &[3, 5]).unwrap();
+        assert_eq!(packed, vec![0b10110101]);
+
+        // Inverse of `extract_as_4by2` (trailing bits of the last byte are padding)
+        let bitfields: Array2<u16> = array![
+            [
+                0b010111, 0b111110, 0b111001, 0b0100, 0b0101, 0b0111, 0b100111, 0b100011, 0b1011,
+                0b1000,
+            ],
+            [
+                0b010111, 0b111110, 0b111001, 0b0100, 0b0101, 0b0111, 0b100111, 0b100011, 0b1011,
+                0b0111,
+            ],
+        ];
+        let bitfield_pattern = [6, 6, 6, 4, 4, 4, 6, 6, 4, 4];
+        let packed = pack_bitfields(&bitfields, &bitfield_pattern).unwrap();
+        assert_eq!(packed.len(), 13);
+        assert_eq!(
+            extract_bitfields(&packed, bitfield_pattern.to_vec(), 2).unwrap(),
+            bitfields
+        );
+
+        assert!(matches!(
+            pack_bitfields(&array![[0b1000, 0]], &[3, 5]),
+            Err(BfaExtractionError::AngleOutOfRange {
+                angle: 0b1000,
+                bits: 3
+            })
+        ));
+        assert!(matches!(
+            pack_bitfields(&array![[0, 0]], &[3, 5, 2]),
+            Err(BfaExtractionError::InvalidAngleCount {
+                expected: 3,
+                given: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn bitfield_extraction_short_stream() {
+        // Single byte streams are valid as long as they contain all bits
+        let result = extract_bitfields(&[0b10110101], vec![3, 5], 1).unwrap();
+        assert_eq!(result, array![[0b101u16, 0b10110]]);
+
+        let result = extract_bitfields(&[0b10110101], vec![7, 5], 1);
+        assert!(matches!(
+            result,
+            Err(BfaExtractionError::InsufficientBitsize {
+                required: 12,
+                available: 8
+            })
+        ));
+    }
+
+    #[test]
+    fn delta_snr_decoding() {
+        // 2 streams, 3 subcarriers
+        let byte_stream: &[u8] = &[0x8f, 0x07, 0x21];
+        let result = extract_delta_snr(byte_stream, 2, 3).unwrap();
+        assert_eq!(result, ndarray::array![[-1, 7, 1], [-8, 0, 2]]);
+
+        // Padding nibble and trailing bytes (e.g. an FCS) are ignored
+        let byte_stream: &[u8] = &[0x8f, 0x07, 0xf1, 0xde, 0xad];
+        let result = extract_delta_snr(byte_stream, 1, 5).unwrap();
+        assert_eq!(result, ndarray::array![[-1, -8, 7, 0, 1]]);
+
+        assert!(matches!(
+            extract_delta_snr(&byte_stream[..2], 2, 3),
+            Err(BfaExtractionError::TruncatedReport {
+                required: 3,
+                available: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn cqi_decoding() {
+        // 2 streams, 3 RUs
+        let byte_stream: &[u8] = &[0x00, 0x04, 0x08, 0x0c, 0x80, 0x7f];
+        let result = extract_cqi(byte_stream, 2, 3).unwrap();
+        assert_eq!(
+            result,
+            ndarray::array![[22.0, 24.0, -10.0], [23.0, 25.0, 53.75]]
+        );
+
+        assert!(matches!(
+            extract_cqi(&byte_stream[..5], 2, 3),
+            Err(BfaExtractionError::TruncatedReport {
+                required: 6,
+                available: 5
+            })
+        ));
+    }
+
+    #[test]
+    fn payload_length() {
+        let config = ExtractionConfig {
+            bitfield_pattern: vec![7, 5],
+            num_subcarrier: 3,
+            subcarrier_indices: None,
+        };
+        assert_eq!(config.payload_length(), 5);
     }
 }
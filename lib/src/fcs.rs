@@ -0,0 +1,120 @@
+//! Frame Check Sequence handling.
+//!
+//! 802.11 frames end with a 4 byte FCS (CRC-32 over the whole frame). Not all
+//! drivers deliver it; whether it is present is signalled by the link layer
+//! header (e.g. the radiotap Flags field). Corrupted frames yield garbage
+//! angles, so the FCS can optionally be verified.
+
+/// Length of the Frame Check Sequence in bytes
+pub(crate) const FCS_LENGTH: usize = 4;
+
+/// Reversed polynomial of the IEEE 802.3 CRC-32 used for the FCS
+const CRC32_POLYNOMIAL: u32 = 0xEDB88320;
+
+/// Lookup table for byte-wise CRC-32 computation
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ CRC32_POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Policy on how to treat frames carrying an FCS
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FcsPolicy {
+    /// Don't verify the FCS
+    #[default]
+    Ignore,
+    /// Verify the FCS and flag the result in the extracted data
+    Flag,
+    /// Verify the FCS and drop frames with a mismatch
+    Drop,
+}
+
+impl std::str::FromStr for FcsPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ignore" => Ok(FcsPolicy::Ignore),
+            "flag" => Ok(FcsPolicy::Flag),
+            "drop" => Ok(FcsPolicy::Drop),
+            _ => Err(format!(
+                "Invalid FCS policy: {}. Expected one of ignore, flag, drop.",
+                s
+            )),
+        }
+    }
+}
+
+/// Compute the IEEE 802.3 CRC-32 of a bytestream
+fn crc32(buf: &[u8]) -> u32 {
+    let crc = buf.iter().fold(!0u32, |crc, &byte| {
+        CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    });
+    !crc
+}
+
+/// Compute the FCS of a frame
+///
+/// # Parameters
+/// * `frame` - Bytestream of the 802.11 frame without FCS
+#[cfg_attr(not(feature = "bfi_metadata"), allow(dead_code))]
+pub(crate) fn compute_fcs(frame: &[u8]) -> [u8; FCS_LENGTH] {
+    crc32(frame).to_le_bytes()
+}
+
+/// Verify the FCS at the end of a frame
+///
+/// # Parameters
+/// * `frame` - Bytestream of the 802.11 frame including the trailing FCS
+pub(crate) fn verify_fcs(frame: &[u8]) -> bool {
+    let Some(split) = frame.len().checked_sub(FCS_LENGTH) else {
+        return false;
+    };
+    let (content, fcs) = frame.split_at(split);
+    let fcs = u32::from_le_bytes(fcs.try_into().expect("FCS has 4 bytes"));
+    crc32(content) == fcs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF43926);
+        assert_eq!(crc32(&[]), 0);
+    }
+
+    #[test]
+    fn fcs_verification() {
+        let mut frame = b"\xe0\x00beamforming".to_vec();
+        frame.extend_from_slice(&crc32(&frame).to_le_bytes());
+        assert!(verify_fcs(&frame));
+
+        frame[3] ^= 0x01;
+        assert!(!verify_fcs(&frame));
+        assert!(!verify_fcs(&frame[..3]));
+    }
+
+    #[test]
+    fn fcs_policy_parsing() {
+        assert_eq!("Drop".parse::<FcsPolicy>(), Ok(FcsPolicy::Drop));
+        assert_eq!("flag".parse::<FcsPolicy>(), Ok(FcsPolicy::Flag));
+        assert!("strict".parse::<FcsPolicy>().is_err());
+    }
+}
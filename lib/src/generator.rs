@@ -0,0 +1,475 @@
+//! Synthetic beamforming feedback frames
+//!
+//! Builds complete HE compressed beamforming frames (radiotap header, action
+//! frame header, HE MIMO Control, SNR, angles and FCS) from extracted data
+//! and writes them to pcap files. Together with the extraction, this allows
+//! to test all antenna, bandwidth and codebook combinations end to end
+//! without captures of real devices.
+use std::path::PathBuf;
+
+use bilge::prelude::*;
+use pcap::{Capture, Linktype, Packet, PacketHeader};
+
+use crate::errors::{BfaExtractionError, GeneratorError};
+use crate::extraction::{pack_bfa, pack_delta_snr, pack_snr, ExtractionConfig};
+use crate::fcs::compute_fcs;
+use crate::he_mimo_ctrl::{Bandwidth, HeMimoControl};
+use crate::radiotap::build_radiotap;
+use crate::subcarriers::he_ru26_spans;
+use crate::{BfaData, BfiMetadata, WifiStandard};
+
+/// Category and action code of HE compressed beamforming frames
+const HE_COMPRESSED_BEAMFORMING: [u8; 2] = [30, 0];
+/// Link type of the written captures (radiotap header)
+const DLT_IEEE802_11_RADIOTAP: i32 = 127;
+/// Feedback type of MU feedback in the HE MIMO Control header
+const FEEDBACK_TYPE_MU: u8 = 1;
+
+/// Configuration of the generated frames
+///
+/// Fields not covered by the extracted data are set here.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GeneratorConfig {
+    /// Grouping bit of the HE MIMO Control header (0: Ng=4, 1: Ng=16)
+    pub grouping: u8,
+    /// Whether to append the FCS to the frames (signaled in the radiotap header)
+    pub include_fcs: bool,
+}
+
+/// Build the HE MIMO Control header describing a report
+///
+/// The RU span defaults to the full bandwidth if not set in the metadata.
+///
+/// # Parameters
+/// * `metadata` - Metadata of the report
+/// * `token_number` - Sounding dialog token of the report
+/// * `config` - Configuration of the generated frame
+fn he_mimo_control(
+    metadata: &BfiMetadata,
+    token_number: u8,
+    config: &GeneratorConfig,
+) -> Result<HeMimoControl, GeneratorError> {
+    if metadata.standard != WifiStandard::He {
+        return Err(GeneratorError::UnsupportedStandard {
+            standard: metadata.standard,
+        });
+    }
+    let bandwidth =
+        Bandwidth::from_mhz(metadata.bandwidth).ok_or(GeneratorError::InvalidBandwidth {
+            bandwidth: metadata.bandwidth,
+        })?;
+    if metadata.nr_index > 7 || metadata.nc_index > 7 {
+        return Err(BfaExtractionError::InvalidAntennaConfig {
+            nr_index: metadata.nr_index,
+            nc_index: metadata.nc_index,
+        }
+        .into());
+    }
+    if metadata.codebook_info > 1 {
+        return Err(BfaExtractionError::InvalidCodebookInfo {
+            codebook_info: metadata.codebook_info,
+        }
+        .into());
+    }
+    if metadata.feedback_type > 3 {
+        return Err(BfaExtractionError::InvalidFeedbackType {
+            fb: metadata.feedback_type,
+        }
+        .into());
+    }
+    if config.grouping > 1 {
+        return Err(BfaExtractionError::InvalidGrouping {
+            grouping: config.grouping,
+        }
+        .into());
+    }
+    let ru_start_index = metadata.ru_start_index.unwrap_or(0);
+    let ru_end_index = metadata
+        .ru_end_index
+        .unwrap_or(he_ru26_spans(bandwidth).len() as u8 - 1);
+    if ru_start_index > ru_end_index || ru_end_index > 0x7f {
+        return Err(BfaExtractionError::InvalidRuRange {
+            start: ru_start_index,
+            end: ru_end_index,
+        }
+        .into());
+    }
+
+    Ok(HeMimoControl::new(
+        u3::new(metadata.nc_index),
+        u3::new(metadata.nr_index),
+        bandwidth,
+        u1::new(config.grouping),
+        u1::new(metadata.codebook_info),
+        u2::new(metadata.feedback_type),
+        u3::new(0),
+        u1::new(1),
+        u7::new(ru_start_index),
+        u7::new(ru_end_index),
+        // NOTE: The HE dialog token only has 6 bits
+        u6::new(token_number & 0x3f),
+    ))
+}
+
+/// Convert a capture timestamp (in seconds) to the timeval of a pcap record
+fn timeval(timestamp: f64) -> libc::timeval {
+    // NOTE: Round once to keep the microseconds below one second
+    let micros = (timestamp * 1e6).round() as i64;
+    libc::timeval {
+        tv_sec: micros.div_euclid(1_000_000) as libc::time_t,
+        tv_usec: micros.rem_euclid(1_000_000) as libc::suseconds_t,
+    }
+}
+
+/// Build a complete HE compressed beamforming frame from extracted data
+///
+/// The frame is an unsegmented Action No Ack frame preceded by a radiotap
+/// header. Extracting it again yields the same data (up to the resolution
+/// of the SNR fields).
+///
+/// # Parameters
+/// * `bfa` - Data of the report
+/// * `config` - Configuration of the generated frame
+pub fn build_frame(bfa: &BfaData, config: &GeneratorConfig) -> Result<Vec<u8>, GeneratorError> {
+    let mimo_control = he_mimo_control(&bfa.metadata, bfa.token_number, config)?;
+    let extraction_config = ExtractionConfig::from_he_mimo_ctrl(&mimo_control)?;
+    let num_streams = bfa.metadata.nc_index as usize + 1;
+
+    if bfa.bfa_angles.nrows() != extraction_config.num_subcarrier {
+        return Err(GeneratorError::Mismatch {
+            field: "subcarriers",
+            expected: extraction_config.num_subcarrier,
+            given: bfa.bfa_angles.nrows(),
+        });
+    }
+    if bfa.snr.len() != num_streams {
+        return Err(GeneratorError::Mismatch {
+            field: "average SNR",
+            expected: num_streams,
+            given: bfa.snr.len(),
+        });
+    }
+    let delta_snr = match (&bfa.delta_snr, bfa.metadata.feedback_type) {
+        (Some(delta_snr), FEEDBACK_TYPE_MU) => {
+            let expected = num_streams * extraction_config.num_subcarrier;
+            if delta_snr.len() != expected {
+                return Err(GeneratorError::Mismatch {
+                    field: "delta SNR",
+                    expected,
+                    given: delta_snr.len(),
+                });
+            }
+            pack_delta_snr(delta_snr)
+        }
+        (None, FEEDBACK_TYPE_MU) => {
+            return Err(GeneratorError::Mismatch {
+                field: "delta SNR",
+                expected: num_streams * extraction_config.num_subcarrier,
+                given: 0,
+            })
+        }
+        _ => Vec::new(),
+    };
+
+    #[cfg(feature = "radio_metadata")]
+    let radio = &bfa.radio;
+    #[cfg(not(feature = "radio_metadata"))]
+    let radio = &Default::default();
+
+    let mut frame = bfa.mac_header.to_action_no_ack_buf().to_vec();
+    frame.extend_from_slice(&HE_COMPRESSED_BEAMFORMING);
+    frame.extend_from_slice(&mimo_control.to_buf());
+    frame.extend_from_slice(&pack_snr(bfa.snr.as_slice()));
+    frame.extend_from_slice(&pack_bfa(bfa)?);
+    frame.extend_from_slice(&delta_snr);
+    if config.include_fcs {
+        let fcs = compute_fcs(&frame);
+        frame.extend_from_slice(&fcs);
+    }
+
+    let mut packet = build_radiotap(radio, config.include_fcs);
+    packet.extend_from_slice(&frame);
+    Ok(packet)
+}
+
+/// Write synthetic frames of the given data to a pcap file
+///
+/// # Parameters
+/// * `pcap_file` - Path to the pcap file to write
+/// * `data` - Data of the reports, one frame is written per entry
+/// * `config` - Configuration of the generated frames
+pub fn write_pcap(
+    pcap_file: PathBuf,
+    data: &[BfaData],
+    config: &GeneratorConfig,
+) -> Result<(), GeneratorError> {
+    log::trace!(
+        "Writing {} synthetic frames to pcap file: {}",
+        data.len(),
+        pcap_file.display()
+    );
+
+    let capture = Capture::dead(Linktype(DLT_IEEE802_11_RADIOTAP))?;
+    let mut savefile = capture.savefile(pcap_file)?;
+    for bfa in data {
+        let packet = build_frame(bfa, config)?;
+        let header = PacketHeader {
+            ts: timeval(bfa.timestamp),
+            caplen: packet.len() as u32,
+            len: packet.len() as u32,
+        };
+        savefile.write(&Packet::new(&header, &packet));
+    }
+    savefile.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "radio_metadata")]
+    use crate::RadioMetadata;
+    use crate::{
+        create_offline_capture, extract_from_pcap, FcsPolicy, MacHeader, NectarSink, StreamBee,
+    };
+    use ndarray::Array2;
+
+    /// Path of a temporary pcap file unique to a test
+    fn temp_pcap(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("beefi_{}_{}.pcap", name, std::process::id()))
+    }
+
+    /// Create data of a HE report with pseudo-random angles and SNR
+    fn he_bfa(
+        nr_index: u8,
+        nc_index: u8,
+        bandwidth: u16,
+        codebook_info: u8,
+        feedback_type: u8,
+        grouping: u8,
+        seed: usize,
+    ) -> BfaData {
+        let mut metadata = BfiMetadata {
+            standard: WifiStandard::He,
+            bandwidth,
+            nr_index,
+            nc_index,
+            codebook_info,
+            feedback_type,
+            partial_bw_info: None,
+            ru_start_index: None,
+            ru_end_index: None,
+        };
+        let token_number = (seed % 64) as u8;
+        let mimo_control = he_mimo_control(
+            &metadata,
+            token_number,
+            &GeneratorConfig {
+                grouping,
+                include_fcs: false,
+            },
+        )
+        .unwrap();
+        metadata.ru_start_index = Some(mimo_control.ru_start_index().value());
+        metadata.ru_end_index = Some(mimo_control.ru_end_index().value());
+
+        let config = ExtractionConfig::from_he_mimo_ctrl(&mimo_control).unwrap();
+        let num_subcarrier = config.num_subcarrier;
+        let pattern = config.bitfield_pattern;
+        let num_streams = nc_index as usize + 1;
+        let bfa_angles = Array2::from_shape_fn((num_subcarrier, pattern.len()), |(s, a)| {
+            ((s * 31 + a * 7 + seed) % (1 << pattern[a])) as u16
+        });
+        let snr = (0..num_streams)
+            .map(|i| 22.0 + 0.25 * (((i + seed) % 256) as f32 - 128.0))
+            .collect();
+        let delta_snr = (feedback_type == FEEDBACK_TYPE_MU).then(|| {
+            Array2::from_shape_fn((num_streams, num_subcarrier), |(i, s)| {
+                ((i + s + seed) % 16) as i8 - 8
+            })
+        });
+
+        BfaData {
+            metadata,
+            #[cfg(feature = "radio_metadata")]
+            radio: RadioMetadata {
+                tsft: Some(seed as u64),
+                antenna_signal: Some(-40),
+                antenna_noise: Some(-95),
+                channel_freq: Some(5180),
+                channel_flags: Some(0x0140),
+                antenna_signals: vec![-41, -39],
+            },
+            timestamp: 1000.0 + seed as f64 * 0.001,
+            mac_header: MacHeader {
+                receiver: [0xc8, 0x7f, 0x54, 0x3c, 0x27, 0x54],
+                transmitter: [0x00, 0x11, 0x22, 0x33, 0x44, seed as u8],
+                bssid: [0xc8, 0x7f, 0x54, 0x3c, 0x27, 0x54],
+                sequence_number: seed as u16,
+                fragment_number: 0,
+                retry: seed % 2 == 1,
+            },
+            fcs_valid: None,
+            #[cfg(feature = "ndpa")]
+            ndpa: None,
+            token_number,
+            snr,
+            delta_snr,
+            subcarrier_indices: config.subcarrier_indices,
+            bfa_angles,
+        }
+    }
+
+    /// Data of every antenna, bandwidth, codebook and feedback type combination
+    fn all_combinations(grouping: u8) -> Vec<BfaData> {
+        let mut data = Vec::new();
+        for nr_index in 1..8 {
+            for nc_index in 0..=nr_index {
+                for bandwidth in [20, 40, 80, 160] {
+                    for codebook_info in 0..2 {
+                        for feedback_type in 0..2 {
+                            let seed = data.len();
+                            data.push(he_bfa(
+                                nr_index,
+                                nc_index,
+                                bandwidth,
+                                codebook_info,
+                                feedback_type,
+                                grouping,
+                                seed,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        data
+    }
+
+    fn assert_same(extracted: &BfaData, expected: &BfaData, include_fcs: bool) {
+        assert_eq!(extracted.metadata.standard, expected.metadata.standard);
+        assert_eq!(extracted.metadata.bandwidth, expected.metadata.bandwidth);
+        assert_eq!(extracted.metadata.nr_index, expected.metadata.nr_index);
+        assert_eq!(extracted.metadata.nc_index, expected.metadata.nc_index);
+        assert_eq!(
+            extracted.metadata.codebook_info,
+            expected.metadata.codebook_info
+        );
+        assert_eq!(
+            extracted.metadata.feedback_type,
+            expected.metadata.feedback_type
+        );
+        assert_eq!(
+            extracted.metadata.ru_end_index,
+            expected.metadata.ru_end_index
+        );
+        #[cfg(feature = "radio_metadata")]
+        assert_eq!(extracted.radio, expected.radio);
+        assert!((extracted.timestamp - expected.timestamp).abs() < 1e-6);
+        assert_eq!(extracted.mac_header, expected.mac_header);
+        assert_eq!(extracted.fcs_valid, include_fcs.then_some(true));
+        assert_eq!(extracted.token_number, expected.token_number);
+        assert_eq!(extracted.snr, expected.snr);
+        assert_eq!(extracted.delta_snr, expected.delta_snr);
+        assert_eq!(extracted.subcarrier_indices, expected.subcarrier_indices);
+        assert_eq!(extracted.bfa_angles, expected.bfa_angles);
+    }
+
+    #[test]
+    fn generated_frames_roundtrip() {
+        for (grouping, include_fcs) in [(0, true), (1, false)] {
+            let config = GeneratorConfig {
+                grouping,
+                include_fcs,
+            };
+            let data = all_combinations(grouping);
+            let pcap_file = temp_pcap(&format!("generator_roundtrip_{}", grouping));
+            write_pcap(pcap_file.clone(), &data, &config).unwrap();
+
+            let extracted = extract_from_pcap(pcap_file.clone(), FcsPolicy::Flag);
+            std::fs::remove_file(pcap_file).unwrap();
+            assert_eq!(extracted.len(), data.len());
+            for (extracted, expected) in extracted.iter().zip(&data) {
+                assert_same(extracted, expected, include_fcs);
+            }
+        }
+    }
+
+    #[test]
+    fn generated_frames_are_streamed() {
+        let config = GeneratorConfig {
+            grouping: 0,
+            include_fcs: true,
+        };
+        let data = all_combinations(0);
+        let pcap_file = temp_pcap("generator_stream");
+        write_pcap(pcap_file.clone(), &data, &config).unwrap();
+
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let mut bee = StreamBee::from_file_capture(create_offline_capture(pcap_file.clone()));
+        bee.set_fcs_policy(FcsPolicy::Flag);
+        bee.subscribe_for_nectar(NectarSink::Queue(sender));
+        bee.start_harvesting(false);
+        let streamed: Vec<BfaData> = receiver.iter().collect();
+        bee.stop();
+        std::fs::remove_file(pcap_file).unwrap();
+
+        assert_eq!(streamed.len(), data.len());
+        for (streamed, expected) in streamed.iter().zip(&data) {
+            assert_same(streamed, expected, true);
+        }
+    }
+
+    #[test]
+    fn generator_rejects_invalid_data() {
+        let config = GeneratorConfig::default();
+        let mut bfa = he_bfa(1, 0, 20, 0, 0, 0, 0);
+        bfa.snr.push(22.0);
+        assert!(matches!(
+            build_frame(&bfa, &config),
+            Err(GeneratorError::Mismatch {
+                field: "average SNR",
+                expected: 1,
+                given: 2
+            })
+        ));
+
+        let mut bfa = he_bfa(1, 0, 20, 0, 0, 0, 0);
+        bfa.metadata.bandwidth = 320;
+        assert!(matches!(
+            build_frame(&bfa, &config),
+            Err(GeneratorError::InvalidBandwidth { bandwidth: 320 })
+        ));
+
+        let mut bfa = he_bfa(1, 0, 20, 0, 0, 0, 0);
+        bfa.metadata.standard = WifiStandard::Vht;
+        assert!(matches!(
+            build_frame(&bfa, &config),
+            Err(GeneratorError::UnsupportedStandard {
+                standard: WifiStandard::Vht
+            })
+        ));
+
+        // Angles of a single subcarrier
+        let mut bfa = he_bfa(1, 0, 20, 0, 0, 0, 0);
+        bfa.bfa_angles = Array2::zeros((1, 2));
+        assert!(matches!(
+            build_frame(&bfa, &config),
+            Err(GeneratorError::Mismatch {
+                field: "subcarriers",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn timestamp_conversion() {
+        let ts = timeval(12.25);
+        assert_eq!((ts.tv_sec, ts.tv_usec), (12, 250_000));
+        // Fractions rounding up to a full second carry over
+        let ts = timeval(12.9999996);
+        assert_eq!((ts.tv_sec, ts.tv_usec), (13, 0));
+        let ts = timeval(-0.5);
+        assert_eq!((ts.tv_sec, ts.tv_usec), (-1, 500_000));
+    }
+}
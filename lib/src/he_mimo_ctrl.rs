@@ -22,6 +22,17 @@ impl Bandwidth {
         (2 << (self as u16)) * 10
     }
 
+    /// Get the bandwidth from its value in Megahertz (if valid)
+    pub fn from_mhz(mhz: u16) -> Option<Self> {
+        match mhz {
+            20 => Some(Bandwidth::Bw20),
+            40 => Some(Bandwidth::Bw40),
+            80 => Some(Bandwidth::Bw80),
+            160 => Some(Bandwidth::Bw160),
+            _ => None,
+        }
+    }
+
     /// Get bandwidth value in Hertz
     pub fn to_hz(self) -> u32 {
         self.to_mhz() as u32 * 1_000_000
@@ -58,6 +69,12 @@ impl HeMimoControl {
         );
         HeMimoControl::from(value)
     }
+
+    /// Encode the HeMimoControl header into its 5 byte representation.
+    pub fn to_buf(&self) -> [u8; 5] {
+        let bytes = self.value.value().to_le_bytes();
+        [bytes[0], bytes[1], bytes[2], bytes[3], bytes[4]]
+    }
 }
 
 #[cfg(test)]
@@ -97,6 +114,7 @@ mod tests {
         assert_eq!(result.ru_end_index(), UInt::<u8, 7>::new(0x08));
         assert_eq!(result.dialog_token_number(), UInt::<u8, 6>::new(55));
         assert_eq!(result.reserved_padding(), UInt::<u8, 4>::new(0));
+        assert_eq!(result.to_buf(), byte_stream);
     }
 
     #[test]
@@ -113,5 +131,7 @@ mod tests {
         assert_eq!(Bandwidth::Bw40.to_mhz(), 40);
         assert_eq!(Bandwidth::Bw80.to_mhz(), 80);
         assert_eq!(Bandwidth::Bw160.to_mhz(), 160);
+        assert_eq!(Bandwidth::from_mhz(80), Some(Bandwidth::Bw80));
+        assert_eq!(Bandwidth::from_mhz(320), None);
     }
 }
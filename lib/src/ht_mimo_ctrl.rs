@@ -0,0 +1,74 @@
+//! High Throughput (HT) MIMO Control header
+//!
+//! This module defines types and handles extraction of the HT MIMO Control
+//! header from the bytestream of a captured WiFi packet.
+use bilge::prelude::*;
+
+/// HT Mimo Control header
+#[bitsize(48)]
+#[derive(FromBits, DebugBits)]
+pub struct HtMimoControl {
+    pub nc_index: u2,                 // Index for number of "columns" (streams)
+    pub nr_index: u2,                 // Index for number of receive antennas
+    pub channel_width: u1,            // channel bandwidth (0=20 MHz, 1=40 MHz)
+    pub grouping: u2,                 // Subcarrier grouping (Ng=1, 2 or 4; 3 is reserved)
+    pub coefficient_size: u2,         // Coefficient size (only for non-compressed feedback)
+    pub codebook_info: u2,            // Codebook size
+    pub remaining_matrix_segment: u3, // Indicate number of remaining feedback segments
+    pub reserved_padding: u2,         // Reserved padding
+    pub sounding_timestamp: u32,      // Timestamp of the sounding PPDU used for feedback
+}
+
+impl HtMimoControl {
+    /// Extract HtMimoControl header from the packet bytestream (requires first 6 bytes.)
+    pub fn from_buf(buf: &[u8]) -> Self {
+        let value: UInt<u64, 48> = UInt::<u64, 48>::new(
+            (buf[0] as u64)
+                | ((buf[1] as u64) << 8)
+                | ((buf[2] as u64) << 16)
+                | ((buf[3] as u64) << 24)
+                | ((buf[4] as u64) << 32)
+                | ((buf[5] as u64) << 40),
+        );
+        HtMimoControl::from(value)
+    }
+
+    /// Get bandwidth value in Megahertz
+    pub fn bandwidth_mhz(&self) -> u16 {
+        20 << self.channel_width().value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ht_mimo_ctrl_extraction() {
+        // Sounding Timestamp: 0x12345678 | 0000 0100 1101 1101
+        // HT MIMO Control:
+        // .... .... .... ..01 = Nc Index: 2 Columns (1)
+        // .... .... .... 11.. = Nr Index: 4 Rows (3)
+        // .... .... ...1 .... = MIMO Control Channel Width: 40 MHz (1)
+        // .... .... .10. .... = Grouping: Ng=4 (2)
+        // .... ...0 1... .... = Coefficient Size: 1
+        // .... .10. .... .... = Codebook Information: 2
+        // ..00 0... .... .... = Remaining Matrix Segment: 0
+        // 00.. .... .... .... = Reserved: 0x0
+
+        // bytestream (little endian)
+        let byte_stream: &[u8] = &[0b11011101, 0b00000100, 0x78, 0x56, 0x34, 0x12];
+
+        let result = HtMimoControl::from_buf(byte_stream);
+        assert_eq!(result.nc_index(), UInt::<u8, 2>::new(1));
+        assert_eq!(result.nr_index(), UInt::<u8, 2>::new(3));
+        assert_eq!(result.channel_width(), UInt::<u8, 1>::new(1));
+        assert_eq!(result.grouping(), UInt::<u8, 2>::new(2));
+        assert_eq!(result.coefficient_size(), UInt::<u8, 2>::new(1));
+        assert_eq!(result.codebook_info(), UInt::<u8, 2>::new(2));
+        assert_eq!(result.remaining_matrix_segment(), UInt::<u8, 3>::new(0));
+        assert_eq!(result.reserved_padding(), UInt::<u8, 2>::new(0));
+        assert_eq!(result.sounding_timestamp(), 0x12345678);
+        assert_eq!(result.bandwidth_mhz(), 40);
+    }
+}
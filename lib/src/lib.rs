@@ -2,19 +2,57 @@ mod bfa_data;
 mod bfa_to_bfm;
 mod bfm_data;
 mod capture;
+mod cqi_data;
+mod eht_mimo_ctrl;
 mod errors;
 mod extraction;
+mod fcs;
+#[cfg(feature = "bfi_metadata")]
+mod generator;
 mod he_mimo_ctrl;
+mod ht_mimo_ctrl;
+mod link_layer;
+mod mac_header;
+#[cfg(feature = "ndpa")]
+mod ndpa;
 mod pcap;
 mod persistence;
+mod radiotap;
+mod reassembly;
+mod subcarriers;
+mod trigger;
+mod vht_mimo_ctrl;
 
 // Public re-export
-pub use crate::bfa_data::{split_bfi_data, BfaData, BfiMetadata};
-pub use crate::bfm_data::{BfmData, FeedbackMatrix};
+pub use crate::bfa_data::{split_bfi_data, BfaData, BfiMetadata, WifiStandard};
+pub use crate::bfm_data::{BfmData, FeedbackMatrix, Precision};
 
-pub use crate::bfa_to_bfm::to_bfm;
+pub use crate::bfa_to_bfm::{
+    dequantize, dequantize_batch, to_bfa, to_bfm, to_bfm_as, to_bfm_batch, to_bfm_batch_as,
+};
 pub use crate::capture::{
     create_live_capture, create_offline_capture, HoneySink, NectarSink, PollenSink, StreamBee,
+    WaggleSink, WaxSink,
+};
+pub use crate::cqi_data::CqiData;
+pub use crate::eht_mimo_ctrl::{EhtBandwidth, PartialBwInfo};
+pub use crate::errors::{
+    BfaExtractionError, BfmConversionError, GeneratorError, NotApplicableReason,
 };
+pub use crate::extraction::{num_angles, pack_bfa};
+pub use crate::fcs::FcsPolicy;
+#[cfg(feature = "bfi_metadata")]
+pub use crate::generator::{build_frame, write_pcap, GeneratorConfig};
+pub use crate::he_mimo_ctrl::Bandwidth;
+pub use crate::link_layer::LinkType;
+pub use crate::mac_header::MacHeader;
+#[cfg(feature = "ndpa")]
+pub use crate::ndpa::{Ndpa, StaInfo};
 pub use crate::persistence::{BfiFile, FileContentType, FileType, Writer};
-pub use pcap::{extract_from_packet, extract_from_pcap};
+pub use crate::radiotap::RadioMetadata;
+pub use crate::reassembly::FeedbackReassembler;
+pub use crate::subcarriers::{
+    eht_subcarrier_indices, he_subcarrier_indices, HE_SUBCARRIER_SPACING_HZ,
+};
+pub use crate::trigger::{extract_trigger_from_packet, BfrpTrigger, TriggerUserInfo};
+pub use pcap::{extract_cqi_from_pcap, extract_from_packet, extract_from_pcap, Feedback};
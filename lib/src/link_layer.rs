@@ -0,0 +1,350 @@
+//! Link layer header handling.
+//!
+//! Depending on the driver and capture tool, captured WiFi frames are
+//! prefixed with different headers carrying reception information (or none
+//! at all). The link type of a capture determines which header is present.
+//! This module strips these headers and maps their information onto the
+//! radiotap-like [`RadioMetadata`].
+//!
+//! See <https://www.tcpdump.org/linktypes.html> for the link types.
+use crate::errors::BfaExtractionError;
+use crate::radiotap::parse_radiotap;
+use crate::RadioMetadata;
+
+/// `LINKTYPE_IEEE802_11`
+const DLT_IEEE802_11: i32 = 105;
+/// `LINKTYPE_IEEE802_11_PRISM`
+const DLT_PRISM_HEADER: i32 = 119;
+/// `LINKTYPE_IEEE802_11_RADIOTAP`
+const DLT_IEEE802_11_RADIOTAP: i32 = 127;
+/// `LINKTYPE_IEEE802_11_AVS`
+const DLT_IEEE802_11_AVS: i32 = 163;
+/// `LINKTYPE_PPI`
+const DLT_PPI: i32 = 192;
+
+/// Length of the fixed PPI packet header
+const PPI_HEADER_LENGTH: usize = 8;
+/// Length of a PPI field header (type, length)
+const PPI_FIELD_HEADER_LENGTH: usize = 4;
+/// PPI field type of the 802.11-Common field
+const PPI_FIELD_80211_COMMON: u16 = 2;
+/// Length of the PPI 802.11-Common field
+const PPI_80211_COMMON_LENGTH: usize = 20;
+/// Bit of the 802.11-Common flags signalling that the frame includes the FCS
+const PPI_FLAGS_FCS_PRESENT: u16 = 0x0001;
+
+/// Length of the Prism header (message code, length, device name, 10 items)
+const PRISM_HEADER_LENGTH: usize = 144;
+/// Offset of the first item in the Prism header
+const PRISM_ITEMS_OFFSET: usize = 24;
+/// Length of a single Prism item (DID, status, length, data)
+const PRISM_ITEM_LENGTH: usize = 12;
+/// Position of items of interest in the Prism header
+const PRISM_ITEM_MACTIME: usize = 1;
+const PRISM_ITEM_SIGNAL: usize = 5;
+const PRISM_ITEM_NOISE: usize = 6;
+
+/// Magic number (version 1) at the start of an AVS header
+const AVS_MAGIC: u32 = 0x80211001;
+/// Length of the AVS header (version 1)
+const AVS_HEADER_LENGTH: usize = 64;
+/// AVS SSI type of signal and noise given in dBm
+const AVS_SSI_TYPE_DBM: u32 = 2;
+
+/// Link type of a capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkType {
+    /// Raw 802.11 frames without additional header
+    Ieee80211,
+    /// 802.11 frames prefixed with a radiotap header
+    Radiotap,
+    /// 802.11 frames prefixed with a Per-Packet Information header
+    Ppi,
+    /// 802.11 frames prefixed with a Prism monitoring header
+    Prism,
+    /// 802.11 frames prefixed with an AVS monitoring header
+    Avs,
+}
+
+impl TryFrom<pcap::Linktype> for LinkType {
+    type Error = BfaExtractionError;
+
+    fn try_from(linktype: pcap::Linktype) -> Result<Self, Self::Error> {
+        match linktype.0 {
+            DLT_IEEE802_11 => Ok(LinkType::Ieee80211),
+            DLT_IEEE802_11_RADIOTAP => Ok(LinkType::Radiotap),
+            DLT_PPI => Ok(LinkType::Ppi),
+            DLT_PRISM_HEADER => Ok(LinkType::Prism),
+            DLT_IEEE802_11_AVS => Ok(LinkType::Avs),
+            linktype => Err(BfaExtractionError::UnsupportedLinkType { linktype }),
+        }
+    }
+}
+
+/// Parsed link layer header
+#[derive(Debug, Clone)]
+pub(crate) struct LinkHeader {
+    /// Length of the header preceding the 802.11 frame in bytes
+    pub length: usize,
+    /// Reception information
+    #[cfg_attr(not(feature = "radio_metadata"), allow(dead_code))]
+    pub metadata: RadioMetadata,
+    /// Whether the frame includes the FCS (`None` if not signalled by the header)
+    pub fcs_present: Option<bool>,
+}
+
+/// Parse the link layer header at the start of a captured frame
+///
+/// # Parameters
+/// * `link_type` - Link type of the capture
+/// * `buf` - Bytestream of the captured frame
+pub(crate) fn parse_link_header(
+    link_type: LinkType,
+    buf: &[u8],
+) -> Result<LinkHeader, BfaExtractionError> {
+    match link_type {
+        LinkType::Ieee80211 => Ok(LinkHeader {
+            length: 0,
+            metadata: RadioMetadata::default(),
+            fcs_present: None,
+        }),
+        LinkType::Radiotap => {
+            let radiotap = parse_radiotap(buf)?;
+            Ok(LinkHeader {
+                length: radiotap.length,
+                fcs_present: radiotap.fcs_present(),
+                metadata: radiotap.metadata,
+            })
+        }
+        LinkType::Ppi => parse_ppi(buf),
+        // NOTE: Some drivers capturing with the Prism link type actually
+        // prefix frames with an AVS header, identified by its magic number.
+        LinkType::Prism if read_u32_be(buf, 0) == Some(AVS_MAGIC) => parse_avs(buf),
+        LinkType::Prism => parse_prism(buf),
+        LinkType::Avs => parse_avs(buf),
+    }
+}
+
+/// Error for a malformed header of the given link type
+fn malformed(link_type: LinkType, reason: &str) -> BfaExtractionError {
+    BfaExtractionError::MalformedLinkHeader {
+        link_type,
+        reason: reason.to_string(),
+    }
+}
+
+fn read_u32_be(buf: &[u8], offset: usize) -> Option<u32> {
+    let bytes = buf.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes(bytes.try_into().ok()?))
+}
+
+fn read_u32_le(buf: &[u8], offset: usize) -> Option<u32> {
+    let bytes = buf.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Parse a PPI header, extracting the 802.11-Common field if present
+fn parse_ppi(buf: &[u8]) -> Result<LinkHeader, BfaExtractionError> {
+    if buf.len() < PPI_HEADER_LENGTH {
+        return Err(malformed(LinkType::Ppi, "header truncated"));
+    }
+    let length = u16::from_le_bytes([buf[2], buf[3]]) as usize;
+    if length < PPI_HEADER_LENGTH || length > buf.len() {
+        return Err(malformed(LinkType::Ppi, "invalid header length"));
+    }
+    let dlt = read_u32_le(buf, 4).expect("PPI header has been checked") as i32;
+    if dlt != DLT_IEEE802_11 {
+        return Err(malformed(LinkType::Ppi, "encapsulated frame is not 802.11"));
+    }
+
+    let mut metadata = RadioMetadata::default();
+    let mut fcs_present = None;
+    let mut offset = PPI_HEADER_LENGTH;
+    while offset + PPI_FIELD_HEADER_LENGTH <= length {
+        let field_type = u16::from_le_bytes([buf[offset], buf[offset + 1]]);
+        let field_length = u16::from_le_bytes([buf[offset + 2], buf[offset + 3]]) as usize;
+        let data_start = offset + PPI_FIELD_HEADER_LENGTH;
+        if data_start + field_length > length {
+            return Err(malformed(LinkType::Ppi, "field exceeds header"));
+        }
+
+        if field_type == PPI_FIELD_80211_COMMON && field_length >= PPI_80211_COMMON_LENGTH {
+            let field = &buf[data_start..data_start + field_length];
+            // NOTE: Zero values signal that a field is not available
+            let tsft = u64::from_le_bytes(field[0..8].try_into().expect("TSFT has 8 bytes"));
+            let channel_freq = u16::from_le_bytes([field[12], field[13]]);
+            let flags = u16::from_le_bytes([field[8], field[9]]);
+            let channel_flags = u16::from_le_bytes([field[14], field[15]]);
+            metadata.tsft = (tsft != 0).then_some(tsft);
+            metadata.channel_freq = (channel_freq != 0).then_some(channel_freq);
+            metadata.channel_flags = (channel_flags != 0).then_some(channel_flags);
+            metadata.antenna_signal = (field[18] != 0).then_some(field[18] as i8);
+            metadata.antenna_noise = (field[19] != 0).then_some(field[19] as i8);
+            fcs_present = Some(flags & PPI_FLAGS_FCS_PRESENT != 0);
+        }
+        offset = data_start + field_length;
+    }
+
+    Ok(LinkHeader {
+        length,
+        metadata,
+        fcs_present,
+    })
+}
+
+/// Parse a Prism header
+fn parse_prism(buf: &[u8]) -> Result<LinkHeader, BfaExtractionError> {
+    if buf.len() < PRISM_HEADER_LENGTH {
+        return Err(malformed(LinkType::Prism, "header truncated"));
+    }
+    let length = read_u32_le(buf, 4).expect("Prism header has been checked") as usize;
+    if length < PRISM_HEADER_LENGTH || length > buf.len() {
+        return Err(malformed(LinkType::Prism, "invalid header length"));
+    }
+
+    // NOTE: Every item carries a status, which is zero if the value is set.
+    let item = |index: usize| -> Option<u32> {
+        let start = PRISM_ITEMS_OFFSET + index * PRISM_ITEM_LENGTH;
+        let status = u16::from_le_bytes([buf[start + 4], buf[start + 5]]);
+        (status == 0).then(|| read_u32_le(buf, start + 8).expect("Item is within header"))
+    };
+
+    let metadata = RadioMetadata {
+        tsft: item(PRISM_ITEM_MACTIME).map(u64::from),
+        antenna_signal: item(PRISM_ITEM_SIGNAL).map(|v| v as i32 as i8),
+        antenna_noise: item(PRISM_ITEM_NOISE).map(|v| v as i32 as i8),
+        ..Default::default()
+    };
+
+    Ok(LinkHeader {
+        length,
+        metadata,
+        fcs_present: None,
+    })
+}
+
+/// Parse an AVS header (fields are big endian)
+fn parse_avs(buf: &[u8]) -> Result<LinkHeader, BfaExtractionError> {
+    if buf.len() < AVS_HEADER_LENGTH {
+        return Err(malformed(LinkType::Avs, "header truncated"));
+    }
+    if read_u32_be(buf, 0) != Some(AVS_MAGIC) {
+        return Err(malformed(LinkType::Avs, "unknown header version"));
+    }
+    let length = read_u32_be(buf, 4).expect("AVS header has been checked") as usize;
+    if length < AVS_HEADER_LENGTH || length > buf.len() {
+        return Err(malformed(LinkType::Avs, "invalid header length"));
+    }
+
+    let field = |offset: usize| read_u32_be(buf, offset).expect("AVS header has been checked");
+    let mactime = u64::from_be_bytes(buf[8..16].try_into().expect("Mactime has 8 bytes"));
+    let signal_in_dbm = field(44) == AVS_SSI_TYPE_DBM;
+
+    let metadata = RadioMetadata {
+        tsft: Some(mactime),
+        antenna_signal: signal_in_dbm.then(|| field(48) as i32 as i8),
+        antenna_noise: signal_in_dbm.then(|| field(52) as i32 as i8),
+        ..Default::default()
+    };
+
+    Ok(LinkHeader {
+        length,
+        metadata,
+        fcs_present: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn link_type_conversion() {
+        let link_type = LinkType::try_from(pcap::Linktype(127)).unwrap();
+        assert_eq!(link_type, LinkType::Radiotap);
+        assert!(matches!(
+            LinkType::try_from(pcap::Linktype::ETHERNET),
+            Err(BfaExtractionError::UnsupportedLinkType { linktype: 1 })
+        ));
+    }
+
+    #[test]
+    fn ppi_parsing() {
+        let mut buf = vec![
+            0x00, 0x00, 0x20, 0x00, 0x69, 0x00, 0x00,
+            0x00, // version, flags, length 32, DLT 105
+            0x02, 0x00, 0x14, 0x00, // 802.11-Common, length 20
+        ];
+        buf.extend_from_slice(&3482987u64.to_le_bytes()); // TSFT
+        buf.extend_from_slice(&[0x01, 0x00, 0x00, 0x00]); // flags (FCS present), rate
+        buf.extend_from_slice(&[0x99, 0x16, 0x40, 0x01]); // frequency 5785, channel flags
+        buf.extend_from_slice(&[0x00, 0x00, 0xe9, 0x00]); // FHSS, signal -23, no noise
+        buf.extend_from_slice(&[0xe0, 0x00]); // start of the 802.11 frame
+
+        let result = parse_link_header(LinkType::Ppi, &buf).unwrap();
+        assert_eq!(result.length, 32);
+        assert_eq!(result.fcs_present, Some(true));
+        assert_eq!(
+            result.metadata,
+            RadioMetadata {
+                tsft: Some(3482987),
+                antenna_signal: Some(-23),
+                antenna_noise: None,
+                channel_freq: Some(5785),
+                channel_flags: Some(0x0140),
+                antenna_signals: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn prism_parsing() {
+        let mut buf = vec![0u8; PRISM_HEADER_LENGTH];
+        buf[0..4].copy_from_slice(&0x44u32.to_le_bytes()); // message code
+        buf[4..8].copy_from_slice(&(PRISM_HEADER_LENGTH as u32).to_le_bytes());
+        for index in 0..10 {
+            let start = PRISM_ITEMS_OFFSET + index * PRISM_ITEM_LENGTH;
+            // Mark all items except the noise as set
+            let status: u16 = if index == PRISM_ITEM_NOISE { 1 } else { 0 };
+            buf[start + 4..start + 6].copy_from_slice(&status.to_le_bytes());
+        }
+        let mactime = PRISM_ITEMS_OFFSET + PRISM_ITEM_MACTIME * PRISM_ITEM_LENGTH + 8;
+        buf[mactime..mactime + 4].copy_from_slice(&1234u32.to_le_bytes());
+        let signal = PRISM_ITEMS_OFFSET + PRISM_ITEM_SIGNAL * PRISM_ITEM_LENGTH + 8;
+        buf[signal..signal + 4].copy_from_slice(&(-42i32).to_le_bytes());
+
+        let result = parse_link_header(LinkType::Prism, &buf).unwrap();
+        assert_eq!(result.length, PRISM_HEADER_LENGTH);
+        assert_eq!(result.metadata.tsft, Some(1234));
+        assert_eq!(result.metadata.antenna_signal, Some(-42));
+        assert_eq!(result.metadata.antenna_noise, None);
+    }
+
+    #[test]
+    fn avs_parsing() {
+        let mut buf = vec![0u8; AVS_HEADER_LENGTH];
+        buf[0..4].copy_from_slice(&AVS_MAGIC.to_be_bytes());
+        buf[4..8].copy_from_slice(&(AVS_HEADER_LENGTH as u32).to_be_bytes());
+        buf[8..16].copy_from_slice(&1234u64.to_be_bytes()); // mactime
+        buf[44..48].copy_from_slice(&AVS_SSI_TYPE_DBM.to_be_bytes());
+        buf[48..52].copy_from_slice(&(-42i32).to_be_bytes()); // signal
+        buf[52..56].copy_from_slice(&(-95i32).to_be_bytes()); // noise
+
+        // NOTE: AVS headers may also be captured with the Prism link type
+        for link_type in [LinkType::Avs, LinkType::Prism] {
+            let result = parse_link_header(link_type, &buf).unwrap();
+            assert_eq!(result.length, AVS_HEADER_LENGTH);
+            assert_eq!(result.metadata.tsft, Some(1234));
+            assert_eq!(result.metadata.antenna_signal, Some(-42));
+            assert_eq!(result.metadata.antenna_noise, Some(-95));
+        }
+    }
+
+    #[test]
+    fn raw_frame_has_no_header() {
+        let result = parse_link_header(LinkType::Ieee80211, &[0xe0, 0x00]).unwrap();
+        assert_eq!(result.length, 0);
+        assert_eq!(result.metadata, RadioMetadata::default());
+        assert_eq!(result.fcs_present, None);
+    }
+}
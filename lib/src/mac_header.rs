@@ -0,0 +1,165 @@
+//! 802.11 management frame header
+//!
+//! Beamforming feedback is sent in action frames, which carry the header of
+//! management frames. This module handles extraction of the addresses and
+//! sequence information from the bytestream of a captured WiFi packet.
+use crate::errors::NotApplicableReason;
+use bilge::prelude::*;
+
+/// Length of the management frame header in bytes
+pub(crate) const MAC_HEADER_LENGTH: usize = 24;
+
+/// Retry bit in the flags (second byte) of the frame control field
+const FRAME_CONTROL_RETRY: u8 = 0b00001000;
+/// Frame type of management frames
+const FRAME_TYPE_MANAGEMENT: u8 = 0;
+/// Management frame subtype of action frames
+const SUBTYPE_ACTION: u8 = 13;
+/// Management frame subtype of action frames without acknowledgement
+const SUBTYPE_ACTION_NO_ACK: u8 = 14;
+/// Individual/Group bit of a MAC address, set in bandwidth signaling TAs
+pub(crate) const GROUP_BIT: u8 = 0x01;
+
+/// Sequence Control field of the MAC header
+#[bitsize(16)]
+#[derive(FromBits, DebugBits)]
+struct SequenceControl {
+    fragment_number: u4,  // Number of the fragment of an MSDU/MMPDU
+    sequence_number: u12, // Sequence number of the MSDU/MMPDU
+}
+
+/// Addressing and sequence information of a management frame
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MacHeader {
+    /// Receiver address (Address 1; the beamformer)
+    pub receiver: [u8; 6],
+    /// Transmitter address (Address 2; the beamformee sending the feedback)
+    pub transmitter: [u8; 6],
+    /// BSSID (Address 3)
+    pub bssid: [u8; 6],
+    /// Sequence number of the frame
+    pub sequence_number: u16,
+    /// Fragment number of the frame
+    pub fragment_number: u8,
+    /// Whether the frame is a retransmission
+    pub retry: bool,
+}
+
+impl MacHeader {
+    /// Extract the MAC header from the packet bytestream (requires first 24 bytes.)
+    pub fn from_buf(buf: &[u8]) -> Self {
+        let address = |start: usize| -> [u8; 6] {
+            buf[start..start + 6]
+                .try_into()
+                .expect("Address has 6 bytes")
+        };
+        let sequence_control = SequenceControl::from(u16::from_le_bytes([buf[22], buf[23]]));
+
+        Self {
+            receiver: address(4),
+            transmitter: address(10),
+            bssid: address(16),
+            sequence_number: sequence_control.sequence_number().value(),
+            fragment_number: sequence_control.fragment_number().value(),
+            retry: buf[1] & FRAME_CONTROL_RETRY != 0,
+        }
+    }
+
+    /// Build the header of an Action No Ack frame carrying this information
+    #[cfg_attr(not(feature = "bfi_metadata"), allow(dead_code))]
+    pub(crate) fn to_action_no_ack_buf(self) -> [u8; MAC_HEADER_LENGTH] {
+        let mut buf = [0; MAC_HEADER_LENGTH];
+        buf[0] = (SUBTYPE_ACTION_NO_ACK << 4) | (FRAME_TYPE_MANAGEMENT << 2);
+        if self.retry {
+            buf[1] |= FRAME_CONTROL_RETRY;
+        }
+        buf[4..10].copy_from_slice(&self.receiver);
+        buf[10..16].copy_from_slice(&self.transmitter);
+        buf[16..22].copy_from_slice(&self.bssid);
+        let sequence_control = SequenceControl::new(
+            u4::new(self.fragment_number),
+            u12::new(self.sequence_number),
+        );
+        buf[22..24].copy_from_slice(&u16::from(sequence_control).to_le_bytes());
+        buf
+    }
+}
+
+/// Extract the transmitter address of a control frame (requires first 16 bytes.)
+///
+/// The TA of control frames may be a bandwidth signaling TA with the group
+/// bit set, which is cleared to obtain the address of the transmitter.
+pub(crate) fn control_frame_transmitter(buf: &[u8]) -> [u8; 6] {
+    let mut transmitter: [u8; 6] = buf[10..16].try_into().expect("Address has 6 bytes");
+    transmitter[0] &= !GROUP_BIT;
+    transmitter
+}
+
+/// Check that a frame is an action frame (with or without acknowledgement)
+///
+/// # Parameters
+/// * `frame_control` - First byte of the frame control field
+pub(crate) fn check_action_frame(frame_control: u8) -> Result<(), NotApplicableReason> {
+    let frame_type = (frame_control >> 2) & 0b11;
+    let subtype = frame_control >> 4;
+
+    match (frame_type, subtype) {
+        (FRAME_TYPE_MANAGEMENT, SUBTYPE_ACTION | SUBTYPE_ACTION_NO_ACK) => Ok(()),
+        _ => Err(NotApplicableReason::FrameType {
+            frame_type,
+            subtype,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mac_header_extraction() {
+        // MAC header of the first packet in data/test_data/bfi.pcap with the
+        // retry bit set and a fragment number of 3
+        let byte_stream: &[u8] = &[
+            0xe0, 0x08, // Frame control: Action No Ack, retry
+            0x20, 0x00, // Duration
+            0xc8, 0x7f, 0x54, 0x3c, 0x27, 0x54, // Receiver address
+            0x04, 0x42, 0x1a, 0xcc, 0x7f, 0x34, // Transmitter address
+            0x00, 0x00, 0x00, 0x00, 0x99, 0x37, // BSSID
+            0x73, 0x03, // Sequence control
+        ];
+
+        let result = MacHeader::from_buf(byte_stream);
+        assert_eq!(result.receiver, [0xc8, 0x7f, 0x54, 0x3c, 0x27, 0x54]);
+        assert_eq!(result.transmitter, [0x04, 0x42, 0x1a, 0xcc, 0x7f, 0x34]);
+        assert_eq!(result.bssid, [0x00, 0x00, 0x00, 0x00, 0x99, 0x37]);
+        assert_eq!(result.sequence_number, 0x37);
+        assert_eq!(result.fragment_number, 3);
+        assert!(result.retry);
+
+        // Building the header again only drops the duration
+        let mut expected = byte_stream.to_vec();
+        expected[2] = 0x00;
+        assert_eq!(result.to_action_no_ack_buf().to_vec(), expected);
+    }
+
+    #[test]
+    fn action_frame_check() {
+        assert!(check_action_frame(0xe0).is_ok()); // Action No Ack
+        assert!(check_action_frame(0xd0).is_ok()); // Action
+        assert_eq!(
+            check_action_frame(0x80), // Beacon
+            Err(NotApplicableReason::FrameType {
+                frame_type: 0,
+                subtype: 8
+            })
+        );
+        assert_eq!(
+            check_action_frame(0x88), // QoS Data
+            Err(NotApplicableReason::FrameType {
+                frame_type: 2,
+                subtype: 8
+            })
+        );
+    }
+}
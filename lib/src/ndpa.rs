@@ -0,0 +1,343 @@
+//! NDP Announcement frames
+//!
+//! A beamformer starts a sounding sequence with an NDP Announcement (NDPA),
+//! listing the stations it requests feedback from and the kind of feedback it
+//! wants. The sounding dialog token of the NDPA is repeated in the MIMO
+//! Control field of every feedback report, which allows to join reports with
+//! the NDPA that solicited them.
+//!
+//! See IEEE 802.11-2020 9.3.1.19 (VHT) and IEEE 802.11ax-2021 9.3.1.19 (HE)
+//! for the frame format.
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bilge::prelude::*;
+
+use crate::errors::{BfaExtractionError, NotApplicableReason};
+use crate::fcs::FcsPolicy;
+use crate::mac_header::control_frame_transmitter;
+use crate::pcap::CapturedFrame;
+use crate::WifiStandard;
+
+/// Frame control (first byte) of NDP Announcement frames (control frame, subtype 5)
+const FRAME_CONTROL_NDPA: u8 = 0x54;
+/// Offset of the Sounding Dialog Token field (after frame control, duration, RA, TA)
+const SOUNDING_DIALOG_TOKEN_OFFSET: usize = 16;
+/// Offset of the first STA Info field
+const STA_INFO_OFFSET: usize = SOUNDING_DIALOG_TOKEN_OFFSET + 1;
+/// Length of a STA Info field of the different NDPA variants in bytes
+const VHT_STA_INFO_LENGTH: usize = 2;
+const HE_STA_INFO_LENGTH: usize = 4;
+/// NDP Announcement Variant subfield (B0-B1 of the Sounding Dialog Token)
+const VARIANT_VHT: u8 = 0b00;
+const VARIANT_HE: u8 = 0b10;
+
+/// Time after which an NDPA is no longer joined with reports
+const NDPA_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// VHT STA Info field
+#[bitsize(16)]
+#[derive(FromBits, DebugBits)]
+struct VhtStaInfo {
+    aid: u12,          // AID12 of the beamformee
+    feedback_type: u1, // Feedback type (0=SU, 1=MU)
+    nc_index: u3,      // Number of columns (MU only)
+}
+
+/// HE STA Info field
+#[bitsize(32)]
+#[derive(FromBits, DebugBits)]
+struct HeStaInfo {
+    aid: u11,                 // AID11 of the beamformee
+    ru_start_index: u7,       // First RU26 for which feedback is requested
+    ru_end_index: u7,         // Last RU26 for which feedback is requested
+    feedback_type_and_ng: u2, // Feedback type and subcarrier grouping
+    disambiguation: u1,       // Always set to prevent VHT STAs from misinterpreting
+    codebook_size: u1,        // Codebook size
+    nc_index: u3,             // Number of columns
+}
+
+/// Feedback requested from a single station
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaInfo {
+    /// Association ID of the beamformee (AID12 for VHT, AID11 for HE)
+    pub aid: u16,
+    /// Requested feedback type (0=SU, 1=MU, 2=CQI)
+    pub feedback_type: u8,
+    /// Requested subcarrier grouping Ng (HE only)
+    pub grouping: Option<u8>,
+    /// Requested codebook size (HE only)
+    pub codebook_info: Option<u8>,
+    /// Index of the number of columns (streams) requested
+    pub nc_index: u8,
+    /// First RU26 of the requested span (HE only)
+    pub ru_start_index: Option<u8>,
+    /// Last RU26 of the requested span (HE only)
+    pub ru_end_index: Option<u8>,
+}
+
+impl From<VhtStaInfo> for StaInfo {
+    fn from(info: VhtStaInfo) -> Self {
+        Self {
+            aid: info.aid().value(),
+            feedback_type: info.feedback_type().value(),
+            grouping: None,
+            codebook_info: None,
+            nc_index: info.nc_index().value(),
+            ru_start_index: None,
+            ru_end_index: None,
+        }
+    }
+}
+
+impl From<HeStaInfo> for StaInfo {
+    fn from(info: HeStaInfo) -> Self {
+        let codebook_size = info.codebook_size().value();
+        // NOTE: IEEE 802.11ax-2021, Table 9-31b
+        let (feedback_type, grouping) = match (info.feedback_type_and_ng().value(), codebook_size) {
+            (0, _) => (0, 4),
+            (1, _) => (0, 16),
+            (2, _) => (1, 4),
+            (_, 0) => (1, 16),
+            (_, _) => (2, 4),
+        };
+        Self {
+            aid: info.aid().value(),
+            feedback_type,
+            grouping: Some(grouping),
+            codebook_info: Some(codebook_size),
+            nc_index: info.nc_index().value(),
+            ru_start_index: Some(info.ru_start_index().value()),
+            ru_end_index: Some(info.ru_end_index().value()),
+        }
+    }
+}
+
+/// NDP Announcement soliciting beamforming feedback
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ndpa {
+    /// Timestamp of the pcap capture
+    pub timestamp: f64,
+    /// Address of the beamformer soliciting the feedback
+    pub beamformer: [u8; 6],
+    /// Sounding dialog token number identifying the sounding sequence
+    pub dialog_token: u8,
+    /// Standard of the announced sounding (VHT or HE)
+    pub standard: WifiStandard,
+    /// Feedback requested from each station
+    pub sta_info: Vec<StaInfo>,
+}
+
+/// Whether a frame is an NDP Announcement
+///
+/// # Parameters
+/// * `frame_control` - First byte of the frame control field
+pub(crate) fn is_ndpa(frame_control: u8) -> bool {
+    frame_control == FRAME_CONTROL_NDPA
+}
+
+/// Parse a captured NDP Announcement frame
+///
+/// NDPAs with an invalid FCS are rejected if the FCS is verified.
+///
+/// # Parameters
+/// * `captured` - The captured frame
+/// * `fcs_policy` - How to treat the FCS of the frame
+pub(crate) fn parse_ndpa(
+    captured: &CapturedFrame,
+    fcs_policy: FcsPolicy,
+) -> Result<Ndpa, BfaExtractionError> {
+    let (frame, fcs_valid) = captured.strip_fcs(STA_INFO_OFFSET, fcs_policy)?;
+    if fcs_valid == Some(false) {
+        return Err(BfaExtractionError::InvalidFcs);
+    }
+
+    let sounding_dialog_token = frame[SOUNDING_DIALOG_TOKEN_OFFSET];
+    let variant = sounding_dialog_token & 0b11;
+    let (standard, sta_info_length) = match variant {
+        VARIANT_VHT => (WifiStandard::Vht, VHT_STA_INFO_LENGTH),
+        VARIANT_HE => (WifiStandard::He, HE_STA_INFO_LENGTH),
+        _ => return Err(NotApplicableReason::NdpaVariant { variant }.into()),
+    };
+
+    let sta_info_bytes = &frame[STA_INFO_OFFSET..];
+    if sta_info_bytes.len() % sta_info_length != 0 {
+        return Err(BfaExtractionError::TruncatedFrame {
+            required: STA_INFO_OFFSET + sta_info_bytes.len().next_multiple_of(sta_info_length),
+            available: frame.len(),
+        });
+    }
+    let sta_info = sta_info_bytes
+        .chunks_exact(sta_info_length)
+        .map(|chunk| match standard {
+            WifiStandard::Vht => {
+                StaInfo::from(VhtStaInfo::from(u16::from_le_bytes([chunk[0], chunk[1]])))
+            }
+            _ => StaInfo::from(HeStaInfo::from(u32::from_le_bytes([
+                chunk[0], chunk[1], chunk[2], chunk[3],
+            ]))),
+        })
+        .collect();
+
+    Ok(Ndpa {
+        timestamp: captured.timestamp,
+        beamformer: control_frame_transmitter(frame),
+        dialog_token: sounding_dialog_token >> 2,
+        standard,
+        sta_info,
+    })
+}
+
+/// Keeps track of recent NDPAs to join them with the reports they solicited.
+///
+/// NDPAs are identified by the beamformer address and the sounding dialog
+/// token. Since the token wraps around quickly, NDPAs older than a timeout
+/// are not considered anymore.
+pub(crate) struct NdpaTracker {
+    timeout: f64,
+    ndpas: HashMap<([u8; 6], u8), Ndpa>,
+}
+
+impl Default for NdpaTracker {
+    fn default() -> Self {
+        Self {
+            timeout: NDPA_TIMEOUT.as_secs_f64(),
+            ndpas: HashMap::new(),
+        }
+    }
+}
+
+impl NdpaTracker {
+    /// Track a new NDPA, replacing previous ones of the same sounding sequence
+    pub fn insert(&mut self, ndpa: Ndpa) {
+        let now = ndpa.timestamp;
+        let timeout = self.timeout;
+        self.ndpas.retain(|_, ndpa| now - ndpa.timestamp <= timeout);
+        self.ndpas
+            .insert((ndpa.beamformer, ndpa.dialog_token), ndpa);
+    }
+
+    /// Find the NDPA that solicited a report
+    ///
+    /// # Parameters
+    /// * `beamformer` - Address of the beamformer (receiver of the report)
+    /// * `dialog_token` - Sounding dialog token number of the report
+    /// * `timestamp` - Timestamp of the report
+    pub fn find(&self, beamformer: [u8; 6], dialog_token: u8, timestamp: f64) -> Option<&Ndpa> {
+        self.ndpas
+            .get(&(beamformer, dialog_token))
+            .filter(|ndpa| (0.0..=self.timeout).contains(&(timestamp - ndpa.timestamp)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::link_layer::LinkHeader;
+    use crate::mac_header::GROUP_BIT;
+
+    const BEAMFORMER: [u8; 6] = [0xc8, 0x7f, 0x54, 0x3c, 0x27, 0x54];
+
+    /// Create a captured NDPA (without FCS) with the given token and STA infos
+    fn ndpa_frame(sounding_dialog_token: u8, sta_info: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0x54, 0x00, 0x00, 0x00];
+        frame.extend_from_slice(&[0xff; 6]); // RA (broadcast)
+        frame.extend_from_slice(&BEAMFORMER);
+        frame[10] |= GROUP_BIT; // bandwidth signaling TA
+        frame.push(sounding_dialog_token);
+        frame.extend_from_slice(sta_info);
+        frame
+    }
+
+    fn captured(frame: &[u8]) -> CapturedFrame<'_> {
+        CapturedFrame {
+            timestamp: 1.0,
+            link_header: LinkHeader {
+                length: 0,
+                metadata: Default::default(),
+                fcs_present: Some(false),
+            },
+            frame,
+        }
+    }
+
+    #[test]
+    fn vht_ndpa_parsing() {
+        // Token 7, single STA with AID 5, MU feedback, Nc index 1
+        let frame = ndpa_frame(7 << 2, &[0x05, 0b0011_0000]);
+        let ndpa = parse_ndpa(&captured(&frame), FcsPolicy::Ignore).unwrap();
+        assert_eq!(ndpa.beamformer, BEAMFORMER);
+        assert_eq!(ndpa.dialog_token, 7);
+        assert_eq!(ndpa.standard, WifiStandard::Vht);
+        assert_eq!(
+            ndpa.sta_info,
+            vec![StaInfo {
+                aid: 5,
+                feedback_type: 1,
+                grouping: None,
+                codebook_info: None,
+                nc_index: 1,
+                ru_start_index: None,
+                ru_end_index: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn he_ndpa_parsing() {
+        // AID 1, RU 0-8, SU Ng4, codebook 1, Nc index 1
+        let su: u32 = 1 | (8 << 18) | (1 << 27) | (1 << 28) | (1 << 29);
+        // AID 2, RU 0-36, CQI (feedback type and Ng 3 with codebook 1)
+        let cqi: u32 = 2 | (36 << 18) | (3 << 25) | (1 << 27) | (1 << 28);
+        let mut sta_info = su.to_le_bytes().to_vec();
+        sta_info.extend_from_slice(&cqi.to_le_bytes());
+        let frame = ndpa_frame((42 << 2) | VARIANT_HE, &sta_info);
+
+        let ndpa = parse_ndpa(&captured(&frame), FcsPolicy::Ignore).unwrap();
+        assert_eq!(ndpa.dialog_token, 42);
+        assert_eq!(ndpa.standard, WifiStandard::He);
+        assert_eq!(ndpa.sta_info.len(), 2);
+        assert_eq!(ndpa.sta_info[0].aid, 1);
+        assert_eq!(ndpa.sta_info[0].feedback_type, 0);
+        assert_eq!(ndpa.sta_info[0].grouping, Some(4));
+        assert_eq!(ndpa.sta_info[0].codebook_info, Some(1));
+        assert_eq!(ndpa.sta_info[0].nc_index, 1);
+        assert_eq!(ndpa.sta_info[0].ru_end_index, Some(8));
+        assert_eq!(ndpa.sta_info[1].aid, 2);
+        assert_eq!(ndpa.sta_info[1].feedback_type, 2);
+        assert_eq!(ndpa.sta_info[1].ru_end_index, Some(36));
+
+        // Truncated STA Info
+        let result = parse_ndpa(&captured(&frame[..frame.len() - 1]), FcsPolicy::Ignore);
+        assert!(matches!(
+            result,
+            Err(BfaExtractionError::TruncatedFrame { .. })
+        ));
+    }
+
+    #[test]
+    fn ranging_ndpa_is_not_applicable() {
+        let frame = ndpa_frame(0b01, &[]);
+        assert!(matches!(
+            parse_ndpa(&captured(&frame), FcsPolicy::Ignore),
+            Err(BfaExtractionError::NotApplicable(
+                NotApplicableReason::NdpaVariant { variant: 0b01 }
+            ))
+        ));
+    }
+
+    #[test]
+    fn ndpa_tracking() {
+        let frame = ndpa_frame(7 << 2, &[0x05, 0x00]);
+        let ndpa = parse_ndpa(&captured(&frame), FcsPolicy::Ignore).unwrap();
+        let mut tracker = NdpaTracker::default();
+        tracker.insert(ndpa.clone());
+
+        assert_eq!(tracker.find(BEAMFORMER, 7, 1.002), Some(&ndpa));
+        assert_eq!(tracker.find(BEAMFORMER, 8, 1.002), None);
+        assert_eq!(tracker.find([0; 6], 7, 1.002), None);
+        // Reports before the NDPA or after the timeout
+        assert_eq!(tracker.find(BEAMFORMER, 7, 0.9), None);
+        assert_eq!(tracker.find(BEAMFORMER, 7, 2.0), None);
+    }
+}
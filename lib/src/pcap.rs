@@ -1,63 +1,601 @@
 //! Some pcap handling helpers
 
-use crate::errors::BfaExtractionError;
-use crate::extraction::{extract_bfa, ExtractionConfig};
+use crate::eht_mimo_ctrl::EhtMimoControl;
+use crate::errors::{BfaExtractionError, NotApplicableReason};
+use crate::extraction::{
+    extract_bfa, extract_cqi, extract_delta_snr, extract_snr, vht_delta_snr_subcarriers,
+    ExtractionConfig,
+};
+use crate::fcs::{verify_fcs, FcsPolicy, FCS_LENGTH};
 use crate::he_mimo_ctrl::HeMimoControl;
-use crate::BfaData;
+use crate::ht_mimo_ctrl::HtMimoControl;
+use crate::link_layer::{parse_link_header, LinkHeader, LinkType};
+use crate::mac_header::{check_action_frame, MacHeader, MAC_HEADER_LENGTH};
+use crate::reassembly::FeedbackReassembler;
+use crate::vht_mimo_ctrl::VhtMimoControl;
+use crate::{BfaData, CqiData};
 use pcap::{Capture, Packet};
 use std::path::PathBuf;
 
-/// Extract BFI data from a single WiFi packet captured with pcap
-pub fn extract_from_packet(packet: &Packet) -> Result<BfaData, BfaExtractionError> {
-    const MIMO_CTRL_HEADER_OFFSET: usize = 26;
-    const BFA_HEADER_OFFSET: usize = 7;
-    const FCS_LENGTH: usize = 4;
+/// Length of the MIMO Control field of the different standards in bytes
+// NOTE: HT MIMO control includes a 4 byte sounding timestamp
+const HT_MIMO_CTRL_LENGTH: usize = 6;
+const VHT_MIMO_CTRL_LENGTH: usize = 3;
+const HE_MIMO_CTRL_LENGTH: usize = 5;
+const EHT_MIMO_CTRL_LENGTH: usize = 5;
+
+/// Category code of HT action frames
+const CATEGORY_HT: u8 = 7;
+/// Action code of the HT Compressed Beamforming frame
+const ACTION_HT_COMPRESSED_BEAMFORMING: u8 = 6;
+/// Category code of VHT action frames
+const CATEGORY_VHT: u8 = 21;
+/// Action code of the VHT Compressed Beamforming frame
+const ACTION_VHT_COMPRESSED_BEAMFORMING: u8 = 0;
+/// Category code of HE action frames
+const CATEGORY_HE: u8 = 30;
+/// Action code of the HE Compressed Beamforming/CQI frame
+const ACTION_HE_COMPRESSED_BEAMFORMING: u8 = 0;
+/// Category code of EHT action frames
+const CATEGORY_EHT: u8 = 36;
+/// Action code of the EHT Compressed Beamforming/CQI frame
+const ACTION_EHT_COMPRESSED_BEAMFORMING: u8 = 0;
+/// Feedback type of CQI feedback (HE/EHT only)
+const FEEDBACK_TYPE_CQI: u8 = 2;
+
+/// Feedback decoded from a compressed beamforming/CQI frame
+#[derive(Debug, Clone)]
+pub enum Feedback {
+    /// Compressed beamforming feedback (angles)
+    Angles(BfaData),
+    /// Channel quality indication feedback
+    Cqi(CqiData),
+}
+
+/// MIMO Control header of a compressed beamforming frame
+pub(crate) enum MimoControl {
+    Ht(HtMimoControl),
+    Vht(VhtMimoControl),
+    He(HeMimoControl),
+    Eht(EhtMimoControl),
+}
+
+impl MimoControl {
+    /// Parse the MIMO Control header matching the action frame category and action.
+    ///
+    /// # Parameters
+    /// * `category` - Category code of the action frame
+    /// * `action` - Action code of the action frame
+    /// * `buf` - Bytestream starting at the MIMO Control field
+    fn from_buf(category: u8, action: u8, buf: &[u8]) -> Result<Self, BfaExtractionError> {
+        // Ensure that the buffer holds the complete MIMO Control field
+        let field = |length: usize| {
+            buf.get(..length).ok_or(BfaExtractionError::TruncatedFrame {
+                required: length,
+                available: buf.len(),
+            })
+        };
+
+        let mimo_control = match (category, action) {
+            (CATEGORY_HT, ACTION_HT_COMPRESSED_BEAMFORMING) => {
+                MimoControl::Ht(HtMimoControl::from_buf(field(HT_MIMO_CTRL_LENGTH)?))
+            }
+            (CATEGORY_VHT, ACTION_VHT_COMPRESSED_BEAMFORMING) => {
+                MimoControl::Vht(VhtMimoControl::from_buf(field(VHT_MIMO_CTRL_LENGTH)?))
+            }
+            (CATEGORY_HE, ACTION_HE_COMPRESSED_BEAMFORMING) => {
+                MimoControl::He(HeMimoControl::from_buf(field(HE_MIMO_CTRL_LENGTH)?))
+            }
+            (CATEGORY_EHT, ACTION_EHT_COMPRESSED_BEAMFORMING) => {
+                MimoControl::Eht(EhtMimoControl::from_buf(field(EHT_MIMO_CTRL_LENGTH)?))
+            }
+            _ => return Err(NotApplicableReason::Action { category, action }.into()),
+        };
+        Ok(mimo_control)
+    }
+
+    /// Length of the MIMO Control field in bytes.
+    fn header_length(&self) -> usize {
+        match self {
+            MimoControl::Ht(_) => HT_MIMO_CTRL_LENGTH,
+            MimoControl::Vht(_) => VHT_MIMO_CTRL_LENGTH,
+            MimoControl::He(_) => HE_MIMO_CTRL_LENGTH,
+            MimoControl::Eht(_) => EHT_MIMO_CTRL_LENGTH,
+        }
+    }
+
+    /// Number of space-time streams (columns) reported in the feedback.
+    fn num_streams(&self) -> usize {
+        let nc_index = match self {
+            MimoControl::Ht(ctrl) => ctrl.nc_index().value(),
+            MimoControl::Vht(ctrl) => ctrl.nc_index().value(),
+            MimoControl::He(ctrl) => ctrl.nc_index().value(),
+            MimoControl::Eht(ctrl) => ctrl.nc_index().value(),
+        };
+        nc_index as usize + 1
+    }
+
+    /// Whether the frame carries MU feedback, i.e. is followed by a MU
+    /// Exclusive Beamforming Report.
+    fn is_mu_feedback(&self) -> bool {
+        match self {
+            // NOTE: HT only knows single user feedback
+            MimoControl::Ht(_) => false,
+            MimoControl::Vht(ctrl) => ctrl.feedback_type().value() == 1,
+            MimoControl::He(ctrl) => ctrl.feedback_type().value() == 1,
+            MimoControl::Eht(ctrl) => ctrl.feedback_type().value() == 1,
+        }
+    }
+
+    /// Number of subcarriers of the MU Exclusive Beamforming Report.
+    ///
+    /// # Parameters
+    /// * `num_subcarrier` - Number of subcarriers of the angles
+    fn num_delta_snr_subcarrier(&self, num_subcarrier: usize) -> Result<usize, BfaExtractionError> {
+        match self {
+            // NOTE: VHT reports the delta SNR on a coarser grid
+            MimoControl::Vht(ctrl) => vht_delta_snr_subcarriers(ctrl),
+            _ => Ok(num_subcarrier),
+        }
+    }
+
+    /// Whether the frame carries CQI feedback, i.e. a CQI Report instead of angles.
+    fn is_cqi_feedback(&self) -> bool {
+        match self {
+            // NOTE: CQI feedback was introduced with HE
+            MimoControl::Ht(_) | MimoControl::Vht(_) => false,
+            MimoControl::He(ctrl) => ctrl.feedback_type().value() == FEEDBACK_TYPE_CQI,
+            MimoControl::Eht(ctrl) => ctrl.feedback_type().value() == FEEDBACK_TYPE_CQI,
+        }
+    }
+
+    /// Number of feedback segments following this one (0 for the last segment).
+    pub(crate) fn remaining_segments(&self) -> u8 {
+        match self {
+            MimoControl::Ht(ctrl) => ctrl.remaining_matrix_segment().value(),
+            MimoControl::Vht(ctrl) => ctrl.remaining_feedback_segments().value(),
+            MimoControl::He(ctrl) => ctrl.remaining_feedback_segments().value(),
+            MimoControl::Eht(ctrl) => ctrl.remaining_feedback_segments().value(),
+        }
+    }
+
+    /// Whether this is the first segment of a feedback report.
+    ///
+    /// HT does not signal the first segment, `None` is returned in that case.
+    pub(crate) fn is_first_segment(&self) -> Option<bool> {
+        match self {
+            MimoControl::Ht(_) => None,
+            MimoControl::Vht(ctrl) => Some(ctrl.first_feedback_segments().value() == 1),
+            MimoControl::He(ctrl) => Some(ctrl.first_feedback_segments().value() == 1),
+            MimoControl::Eht(ctrl) => Some(ctrl.first_feedback_segments().value() == 1),
+        }
+    }
+
+    /// Whether the frame carries only a segment of a feedback report.
+    ///
+    /// The last HT segment cannot be told apart from an unsegmented report,
+    /// only the reassembler knows whether a report is pending.
+    pub(crate) fn is_segmented(&self) -> bool {
+        self.remaining_segments() > 0 || self.is_first_segment() == Some(false)
+    }
+
+    fn extraction_config(&self) -> Result<ExtractionConfig, BfaExtractionError> {
+        match self {
+            MimoControl::Ht(ctrl) => ExtractionConfig::from_ht_mimo_ctrl(ctrl),
+            MimoControl::Vht(ctrl) => ExtractionConfig::from_vht_mimo_ctrl(ctrl),
+            MimoControl::He(ctrl) => ExtractionConfig::from_he_mimo_ctrl(ctrl),
+            MimoControl::Eht(ctrl) => ExtractionConfig::from_eht_mimo_ctrl(ctrl),
+        }
+    }
+
+    #[cfg(feature = "bfi_metadata")]
+    fn metadata(&self) -> crate::BfiMetadata {
+        match self {
+            MimoControl::Ht(ctrl) => crate::BfiMetadata::from_ht_mimo_ctrl_header(ctrl),
+            MimoControl::Vht(ctrl) => crate::BfiMetadata::from_vht_mimo_ctrl_header(ctrl),
+            MimoControl::He(ctrl) => crate::BfiMetadata::from_mimo_ctrl_header(ctrl),
+            MimoControl::Eht(ctrl) => crate::BfiMetadata::from_eht_mimo_ctrl_header(ctrl),
+        }
+    }
+
+    pub(crate) fn dialog_token_number(&self) -> u8 {
+        match self {
+            // NOTE: HT sounding has no dialog token
+            MimoControl::Ht(_) => 0,
+            MimoControl::Vht(ctrl) => u8::from(ctrl.dialog_token_number()),
+            MimoControl::He(ctrl) => u8::from(ctrl.dialog_token_number()),
+            MimoControl::Eht(ctrl) => u8::from(ctrl.dialog_token_number()),
+        }
+    }
+
+    /// Identifier of the sounding the feedback answers.
+    ///
+    /// This is the dialog token, except for HT which has none and uses the
+    /// timestamp of the sounding PPDU instead.
+    pub(crate) fn sounding_id(&self) -> u32 {
+        match self {
+            MimoControl::Ht(ctrl) => ctrl.sounding_timestamp(),
+            _ => self.dialog_token_number() as u32,
+        }
+    }
+}
+
+/// Capture information attached to the extracted data
+#[derive(Debug, Clone)]
+pub(crate) struct CaptureInfo {
+    /// Timestamp of the pcap capture
+    pub timestamp: f64,
+    /// MAC header of the frame
+    pub mac_header: MacHeader,
+    /// Result of the FCS verification (`None` if not verified)
+    pub fcs_valid: Option<bool>,
+    /// NDP Announcement that solicited the report (if captured)
+    #[cfg(feature = "ndpa")]
+    pub ndpa: Option<crate::Ndpa>,
+    /// Reception information from the link layer header
+    #[cfg(feature = "radio_metadata")]
+    pub radio: crate::RadioMetadata,
+}
+
+/// A single compressed beamforming frame.
+///
+/// For segmented feedback, the frame only carries a part of the report.
+pub(crate) struct FeedbackFrame<'a> {
+    /// Capture information of the frame
+    pub capture: CaptureInfo,
+    /// MIMO Control header of the frame
+    pub mimo_control: MimoControl,
+    /// Report bytes following the MIMO Control field (excluding the FCS)
+    pub report: &'a [u8],
+}
+
+/// A captured 802.11 frame with the link layer header stripped
+pub(crate) struct CapturedFrame<'a> {
+    /// Timestamp of the pcap capture
+    pub timestamp: f64,
+    /// Parsed link layer header
+    pub link_header: LinkHeader,
+    /// Bytestream of the 802.11 frame (including the FCS, if present)
+    pub frame: &'a [u8],
+}
+
+impl<'a> CapturedFrame<'a> {
+    /// Strip the link layer header from a captured packet
+    ///
+    /// # Parameters
+    /// * `packet` - The captured packet
+    /// * `link_type` - Link type of the capture the packet stems from
+    pub(crate) fn from_packet(
+        packet: &'a Packet,
+        link_type: LinkType,
+    ) -> Result<Self, BfaExtractionError> {
+        // Extract the timestamp from the pcap packet
+        let timestamp = packet.header.ts;
+        let timestamp_secs = timestamp.tv_sec as f64 + timestamp.tv_usec as f64 * 1e-6;
+
+        // NOTE: The link layer header length never exceeds the packet length
+        let link_header = parse_link_header(link_type, packet.data)?;
+        let frame = &packet.data[link_header.length..];
+        if frame.is_empty() {
+            return Err(BfaExtractionError::TruncatedFrame {
+                required: 1,
+                available: 0,
+            });
+        }
+
+        Ok(Self {
+            timestamp: timestamp_secs,
+            link_header,
+            frame,
+        })
+    }
+
+    /// First byte of the frame control field (protocol version, type, subtype)
+    pub(crate) fn frame_control(&self) -> u8 {
+        self.frame[0]
+    }
+
+    /// Strip the FCS from the frame (if included) and verify it according to the policy
+    ///
+    /// Headers not signalling the presence of the FCS are assumed to include it.
+    ///
+    /// # Parameters
+    /// * `min_length` - Minimum length of the frame without FCS
+    /// * `fcs_policy` - How to treat the FCS of the frame
+    ///
+    /// # Returns
+    /// * The frame without FCS and the result of the verification (if verified)
+    pub(crate) fn strip_fcs(
+        &self,
+        min_length: usize,
+        fcs_policy: FcsPolicy,
+    ) -> Result<(&'a [u8], Option<bool>), BfaExtractionError> {
+        let fcs_length = match self.link_header.fcs_present {
+            Some(false) => 0,
+            Some(true) | None => FCS_LENGTH,
+        };
+        let required = min_length + fcs_length;
+        if self.frame.len() < required {
+            return Err(BfaExtractionError::TruncatedFrame {
+                required,
+                available: self.frame.len(),
+            });
+        }
+
+        let fcs_valid = match fcs_policy {
+            FcsPolicy::Flag | FcsPolicy::Drop if fcs_length > 0 => Some(verify_fcs(self.frame)),
+            _ => None,
+        };
+        if fcs_policy == FcsPolicy::Drop && fcs_valid == Some(false) {
+            return Err(BfaExtractionError::InvalidFcs);
+        }
+
+        Ok((&self.frame[..self.frame.len() - fcs_length], fcs_valid))
+    }
+}
 
-    // Extract the timestamp from the pcap packet
-    let timestamp = packet.header.ts;
-    let timestamp_secs = timestamp.tv_sec as f64 + timestamp.tv_usec as f64 * 1e-6;
+/// Parse the MIMO Control header and locate the report in a captured frame
+///
+/// # Parameters
+/// * `captured` - The captured frame
+/// * `fcs_policy` - How to treat the FCS of the frame
+pub(crate) fn parse_frame<'a>(
+    captured: CapturedFrame<'a>,
+    fcs_policy: FcsPolicy,
+) -> Result<FeedbackFrame<'a>, BfaExtractionError> {
+    // NOTE: Category and action code follow the MAC header
+    const MIMO_CTRL_HEADER_OFFSET: usize = MAC_HEADER_LENGTH + 2;
 
-    let header_length = u16::from_le_bytes([packet.data[2], packet.data[3]]) as usize;
-    let mimo_ctrl_start = header_length + MIMO_CTRL_HEADER_OFFSET;
+    // NOTE: Only action frames may carry beamforming feedback
+    check_action_frame(captured.frame_control())?;
 
-    let mimo_control = HeMimoControl::from_buf(&packet[mimo_ctrl_start..]);
-    let extraction_config = ExtractionConfig::from_he_mimo_ctrl(&mimo_control)?;
+    let frame = captured.frame;
+    if frame.len() < MIMO_CTRL_HEADER_OFFSET {
+        return Err(BfaExtractionError::TruncatedFrame {
+            required: MIMO_CTRL_HEADER_OFFSET,
+            available: frame.len(),
+        });
+    }
+    let mac_header = MacHeader::from_buf(frame);
 
-    // NOTE: BFA data starts after mimo_control (5 bytes) and SNR (2 bytes)
-    // They last until before the last four bytes (Frame Check Sequence)
-    let bfa_start = mimo_ctrl_start + BFA_HEADER_OFFSET;
-    let bfa_end = packet.len() - FCS_LENGTH;
+    // NOTE: Category and action code directly precede the MIMO control field
+    let category = frame[MIMO_CTRL_HEADER_OFFSET - 2];
+    let action = frame[MIMO_CTRL_HEADER_OFFSET - 1];
+
+    let mimo_control = MimoControl::from_buf(category, action, &frame[MIMO_CTRL_HEADER_OFFSET..])?;
+
+    // NOTE: The report follows the MIMO control field and lasts until the
+    // end of the frame or the Frame Check Sequence, if included.
+    let report_start = MIMO_CTRL_HEADER_OFFSET + mimo_control.header_length();
+    let (frame, fcs_valid) = captured.strip_fcs(report_start, fcs_policy)?;
+
+    Ok(FeedbackFrame {
+        capture: CaptureInfo {
+            timestamp: captured.timestamp,
+            mac_header,
+            fcs_valid,
+            #[cfg(feature = "ndpa")]
+            ndpa: None,
+            #[cfg(feature = "radio_metadata")]
+            radio: captured.link_header.metadata,
+        },
+        mimo_control,
+        report: &frame[report_start..],
+    })
+}
+
+/// Decode a complete (possibly reassembled) feedback report
+///
+/// # Parameters
+/// * `mimo_control` - MIMO Control header describing the report
+/// * `report` - Report bytes following the MIMO Control field
+/// * `capture` - Capture information to attach to the extracted data
+pub(crate) fn decode_report(
+    mimo_control: &MimoControl,
+    report: &[u8],
+    capture: CaptureInfo,
+) -> Result<BfaData, BfaExtractionError> {
+    let mut extraction_config = mimo_control.extraction_config()?;
+    let subcarrier_indices = extraction_config.subcarrier_indices.take();
+    let num_subcarrier = extraction_config.num_subcarrier;
+
+    // NOTE: The report starts with one average SNR byte per stream.
+    let num_streams = mimo_control.num_streams();
+    let snr = extract_snr(report, num_streams)?;
+
+    // NOTE: For MU feedback, the angles are followed by the MU Exclusive
+    // Beamforming Report carrying the delta SNR per subcarrier.
+    let bfa_start = num_streams;
+    let (bfa_end, delta_snr) = if mimo_control.is_mu_feedback() {
+        let bfa_end = bfa_start + extraction_config.payload_length();
+        if report.len() < bfa_end {
+            return Err(BfaExtractionError::TruncatedReport {
+                required: bfa_end,
+                available: report.len(),
+            });
+        }
+        let num_delta_snr = mimo_control.num_delta_snr_subcarrier(num_subcarrier)?;
+        let delta_snr = extract_delta_snr(&report[bfa_end..], num_streams, num_delta_snr)?;
+        (bfa_end, Some(delta_snr))
+    } else {
+        (report.len(), None)
+    };
 
     // Extract the binary data of the BFA angles
-    let bfa_data = &packet[bfa_start..bfa_end];
-    let bfa_angles = extract_bfa(bfa_data, extraction_config).expect("BFA extraction failed");
+    let bfa_data = &report[bfa_start..bfa_end];
+    let bfa_angles = extract_bfa(bfa_data, extraction_config)?;
 
     Ok(BfaData {
         #[cfg(feature = "bfi_metadata")]
-        metadata: crate::BfiMetadata::from_mimo_ctrl_header(&mimo_control),
-        timestamp: timestamp_secs,
-        token_number: u8::from(mimo_control.dialog_token_number()),
+        metadata: mimo_control.metadata(),
+        #[cfg(feature = "radio_metadata")]
+        radio: capture.radio,
+        timestamp: capture.timestamp,
+        mac_header: capture.mac_header,
+        fcs_valid: capture.fcs_valid,
+        #[cfg(feature = "ndpa")]
+        ndpa: capture.ndpa,
+        token_number: mimo_control.dialog_token_number(),
+        snr,
+        delta_snr,
+        subcarrier_indices,
         bfa_angles,
     })
 }
 
+/// Decode a complete (possibly reassembled) CQI report
+///
+/// # Parameters
+/// * `mimo_control` - MIMO Control header describing the report
+/// * `report` - Report bytes following the MIMO Control field
+/// * `capture` - Capture information to attach to the extracted data
+pub(crate) fn decode_cqi_report(
+    mimo_control: &MimoControl,
+    report: &[u8],
+    capture: CaptureInfo,
+) -> Result<CqiData, BfaExtractionError> {
+    // NOTE: The EHT CQI report is not supported (yet)
+    let MimoControl::He(ctrl) = mimo_control else {
+        return Err(BfaExtractionError::InvalidFeedbackType {
+            fb: FEEDBACK_TYPE_CQI,
+        });
+    };
+
+    let (start, end) = (ctrl.ru_start_index().value(), ctrl.ru_end_index().value());
+    if start > end {
+        return Err(BfaExtractionError::InvalidRuRange { start, end });
+    }
+    let ru_indices: Vec<u8> = (start..=end).collect();
+    let snr = extract_cqi(report, mimo_control.num_streams(), ru_indices.len())?;
+
+    Ok(CqiData {
+        #[cfg(feature = "bfi_metadata")]
+        metadata: mimo_control.metadata(),
+        #[cfg(feature = "radio_metadata")]
+        radio: capture.radio,
+        timestamp: capture.timestamp,
+        mac_header: capture.mac_header,
+        fcs_valid: capture.fcs_valid,
+        #[cfg(feature = "ndpa")]
+        ndpa: capture.ndpa,
+        token_number: mimo_control.dialog_token_number(),
+        ru_indices,
+        snr,
+    })
+}
+
+/// Decode a complete (possibly reassembled) report of any feedback type
+///
+/// # Parameters
+/// * `mimo_control` - MIMO Control header describing the report
+/// * `report` - Report bytes following the MIMO Control field
+/// * `capture` - Capture information to attach to the extracted data
+pub(crate) fn decode_feedback(
+    mimo_control: &MimoControl,
+    report: &[u8],
+    capture: CaptureInfo,
+) -> Result<Feedback, BfaExtractionError> {
+    if mimo_control.is_cqi_feedback() {
+        decode_cqi_report(mimo_control, report, capture).map(Feedback::Cqi)
+    } else {
+        decode_report(mimo_control, report, capture).map(Feedback::Angles)
+    }
+}
+
+/// Extract BFI data from a single WiFi packet captured with pcap
+///
+/// Frames carrying only a segment of a feedback report are rejected, use a
+/// [`FeedbackReassembler`](crate::FeedbackReassembler) to handle those.
+///
+/// # Parameters
+/// * `packet` - The captured packet
+/// * `link_type` - Link type of the capture the packet stems from
+/// * `fcs_policy` - How to treat the FCS of the frame
+pub fn extract_from_packet(
+    packet: &Packet,
+    link_type: LinkType,
+    fcs_policy: FcsPolicy,
+) -> Result<BfaData, BfaExtractionError> {
+    let captured = CapturedFrame::from_packet(packet, link_type)?;
+    let frame = parse_frame(captured, fcs_policy)?;
+
+    if frame.mimo_control.is_cqi_feedback() {
+        return Err(NotApplicableReason::CqiFeedback.into());
+    }
+    if frame.mimo_control.is_segmented() {
+        return Err(BfaExtractionError::SegmentedFeedback {
+            remaining: frame.mimo_control.remaining_segments(),
+        });
+    }
+
+    decode_report(&frame.mimo_control, frame.report, frame.capture)
+}
+
 /// Extract all BFI data from a pcap file
 ///
+/// CQI reports are skipped, see [`extract_cqi_from_pcap`] to extract those.
+///
+/// # Parameters
+/// * `file_path` - Path to the pcap file
+/// * `fcs_policy` - How to treat the FCS of the frames
+pub fn extract_from_pcap(pcap_file: PathBuf, fcs_policy: FcsPolicy) -> Vec<BfaData> {
+    extract_feedback_from_pcap(pcap_file, fcs_policy)
+        .into_iter()
+        .filter_map(|feedback| match feedback {
+            Feedback::Angles(data) => Some(data),
+            Feedback::Cqi(_) => None,
+        })
+        .collect()
+}
+
+/// Extract all CQI data from a pcap file
+///
+/// # Parameters
+/// * `file_path` - Path to the pcap file
+/// * `fcs_policy` - How to treat the FCS of the frames
+pub fn extract_cqi_from_pcap(pcap_file: PathBuf, fcs_policy: FcsPolicy) -> Vec<CqiData> {
+    extract_feedback_from_pcap(pcap_file, fcs_policy)
+        .into_iter()
+        .filter_map(|feedback| match feedback {
+            Feedback::Angles(_) => None,
+            Feedback::Cqi(data) => Some(data),
+        })
+        .collect()
+}
+
+/// Extract all feedback from a pcap file
+///
 /// # Parameters
 /// * `file_path` - Path to the pcap file
-pub fn extract_from_pcap(pcap_file: PathBuf) -> Vec<BfaData> {
+/// * `fcs_policy` - How to treat the FCS of the frames
+fn extract_feedback_from_pcap(pcap_file: PathBuf, fcs_policy: FcsPolicy) -> Vec<Feedback> {
     log::trace!(
         "Extracting BFI data from pcap file: {}",
         pcap_file.display(),
     );
 
     let mut capture = Capture::from_file(pcap_file).expect("Couldn't open pcap file");
+    let link_type = match LinkType::try_from(capture.get_datalink()) {
+        Ok(link_type) => link_type,
+        Err(e) => {
+            log::error!("Can't extract BFI data from pcap file: {}", e);
+            return Vec::new();
+        }
+    };
+    let mut reassembler = FeedbackReassembler::default().with_fcs_policy(fcs_policy);
     let mut extracted_data = Vec::new();
 
     loop {
         match capture.next_packet() {
-            Ok(packet) => match extract_from_packet(&packet) {
-                Ok(packet) => extracted_data.push(packet),
+            Ok(packet) => match reassembler.process_packet(&packet, link_type) {
+                Ok(Some(packet)) => extracted_data.push(packet),
+                Ok(None) => {}
+                Err(BfaExtractionError::NotApplicable(reason)) => {
+                    log::trace!("Skipping packet: {}", reason)
+                }
+                Err(BfaExtractionError::InvalidFcs) => {
+                    log::debug!("Dropping packet with invalid FCS")
+                }
                 Err(e) => log::error!("Extraction from packet failed, dropping it. Error: {}", e),
             },
             Err(pcap::Error::TimeoutExpired) => {
@@ -71,8 +609,135 @@ pub fn extract_from_pcap(pcap_file: PathBuf) -> Vec<BfaData> {
     }
 
     log::trace!(
-        "Extracted {} BFI data points from pcap file.",
+        "Extracted {} feedback reports from pcap file.",
         extracted_data.len()
     );
     extracted_data
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mimo_control_dispatch() {
+        let buf = [0u8; 6];
+        assert!(matches!(
+            MimoControl::from_buf(CATEGORY_HE, ACTION_HE_COMPRESSED_BEAMFORMING, &buf),
+            Ok(MimoControl::He(_))
+        ));
+        assert!(matches!(
+            MimoControl::from_buf(CATEGORY_VHT, ACTION_VHT_COMPRESSED_BEAMFORMING, &buf),
+            Ok(MimoControl::Vht(_))
+        ));
+        // VHT Group ID Management is not a beamforming frame
+        assert!(matches!(
+            MimoControl::from_buf(CATEGORY_VHT, 1, &buf),
+            Err(BfaExtractionError::NotApplicable(
+                NotApplicableReason::Action {
+                    category: CATEGORY_VHT,
+                    action: 1
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn mimo_control_truncated() {
+        let buf = [0u8; 4];
+        assert!(matches!(
+            MimoControl::from_buf(CATEGORY_HE, ACTION_HE_COMPRESSED_BEAMFORMING, &buf),
+            Err(BfaExtractionError::TruncatedFrame {
+                required: 5,
+                available: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn truncated_report() {
+        // HE 2x1, 20 MHz, Ng4, SU: 1 SNR byte and 48 bytes of angles
+        let mimo_control = MimoControl::He(HeMimoControl::from_buf(&[
+            0b00001000, 0b10000000, 0, 0b11000100, 0b00000001,
+        ]));
+        let capture = CaptureInfo {
+            timestamp: 0.0,
+            mac_header: MacHeader::default(),
+            fcs_valid: None,
+            #[cfg(feature = "ndpa")]
+            ndpa: None,
+            #[cfg(feature = "radio_metadata")]
+            radio: Default::default(),
+        };
+
+        let report = [0u8; 49];
+        assert!(decode_report(&mimo_control, &report, capture.clone()).is_ok());
+        assert!(matches!(
+            decode_report(&mimo_control, &report[..20], capture.clone()),
+            Err(BfaExtractionError::InsufficientBitsize { .. })
+        ));
+        assert!(matches!(
+            decode_report(&mimo_control, &[], capture),
+            Err(BfaExtractionError::TruncatedReport { .. })
+        ));
+    }
+
+    #[test]
+    fn vht_mu_report() {
+        // VHT 2x1, 20 MHz, Ng4, MU: 1 SNR byte, 24 bytes of angles and
+        // 5 bytes of delta SNR for 10 subcarriers
+        let mimo_control = MimoControl::Vht(VhtMimoControl::from_buf(&[
+            0b00001000, 0b10001010, 0b00000100,
+        ]));
+        let capture = CaptureInfo {
+            timestamp: 0.0,
+            mac_header: MacHeader::default(),
+            fcs_valid: None,
+            #[cfg(feature = "ndpa")]
+            ndpa: None,
+            #[cfg(feature = "radio_metadata")]
+            radio: Default::default(),
+        };
+
+        let report = [0u8; 30];
+        let bfa = decode_report(&mimo_control, &report, capture.clone()).unwrap();
+        assert_eq!(bfa.bfa_angles.dim(), (16, 2));
+        assert_eq!(bfa.delta_snr.map(|d| d.dim()), Some((1, 10)));
+        assert!(matches!(
+            decode_report(&mimo_control, &report[..29], capture),
+            Err(BfaExtractionError::TruncatedReport {
+                required: 5,
+                available: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn cqi_report() {
+        // HE 2x2, 20 MHz, CQI for RU 2-4: 2 SNR bytes per RU
+        let mimo_control = MimoControl::He(HeMimoControl::from_buf(&[
+            0b00001001, 0b10001000, 0b00000010, 0b00000010, 0b00000000,
+        ]));
+        let capture = CaptureInfo {
+            timestamp: 0.0,
+            mac_header: MacHeader::default(),
+            fcs_valid: None,
+            #[cfg(feature = "ndpa")]
+            ndpa: None,
+            #[cfg(feature = "radio_metadata")]
+            radio: Default::default(),
+        };
+
+        let report = [0u8; 6];
+        let Ok(Feedback::Cqi(cqi)) = decode_feedback(&mimo_control, &report, capture.clone())
+        else {
+            panic!("CQI feedback must decode to CQI data");
+        };
+        assert_eq!(cqi.ru_indices, vec![2, 3, 4]);
+        assert_eq!(cqi.snr.dim(), (2, 3));
+        assert!(matches!(
+            decode_feedback(&mimo_control, &report[..5], capture),
+            Err(BfaExtractionError::TruncatedReport { .. })
+        ));
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{bfm_data::BfmData, errors::PersistenceError, BfaData};
+use crate::bfm_data::{BfmData, Precision};
+use crate::{errors::PersistenceError, BfaData, CqiData};
 use std::path::PathBuf;
 
 #[cfg(feature = "parquet")]
@@ -19,8 +20,14 @@ pub enum FileType {
 pub enum FileContentType {
     /// BFA data (angles)
     Bfa,
+    /// BFA data with the angles dequantized to radians
+    BfaRadians,
     /// BFM data (Feedback matrices)
     Bfm,
+    /// BFM data with the matrices stored in single precision
+    BfmSingle,
+    /// CQI data (SNR per RU)
+    Cqi,
 }
 
 /// Struct specifying a file to write BFI data to
@@ -55,9 +62,21 @@ impl Writer {
                 Self::Parquet(parquet::BatchWriter::new_bfa(file.file_path)?)
             }
             #[cfg(feature = "parquet")]
+            (FileType::Parquet, FileContentType::BfaRadians) => {
+                Self::Parquet(parquet::BatchWriter::new_bfa_radians(file.file_path)?)
+            }
+            #[cfg(feature = "parquet")]
             (FileType::Parquet, FileContentType::Bfm) => {
                 Self::Parquet(parquet::BatchWriter::new_bfm(file.file_path)?)
             }
+            #[cfg(feature = "parquet")]
+            (FileType::Parquet, FileContentType::BfmSingle) => {
+                Self::Parquet(parquet::BatchWriter::new_bfm_single(file.file_path)?)
+            }
+            #[cfg(feature = "parquet")]
+            (FileType::Parquet, FileContentType::Cqi) => {
+                Self::Parquet(parquet::BatchWriter::new_cqi(file.file_path)?)
+            }
             (FileType::_Dummy, _) => Self::_Dummy,
         };
 
@@ -69,6 +88,7 @@ impl Writer {
     /// # Arguments
     ///
     /// * `data` A batch (slice) of data to write to the file
+    #[cfg_attr(not(feature = "parquet"), allow(unused_variables))]
     pub fn add_bfa_batch(&mut self, data: &[BfaData]) -> Result<(), PersistenceError> {
         match self {
             #[cfg(feature = "parquet")]
@@ -82,10 +102,17 @@ impl Writer {
 
     /// Add a batch of BFM data to the writer
     ///
+    /// The matrices are written in the precision of the file content type,
+    /// independent of the precision of the data.
+    ///
     /// # Arguments
     ///
     /// * `data` A batch (slice) of data to write to the file
-    pub fn add_bfm_batch(&mut self, data: &[BfmData]) -> Result<(), PersistenceError> {
+    #[cfg_attr(not(feature = "parquet"), allow(unused_variables))]
+    pub fn add_bfm_batch<T: Precision>(
+        &mut self,
+        data: &[BfmData<T>],
+    ) -> Result<(), PersistenceError> {
         match self {
             #[cfg(feature = "parquet")]
             Writer::Parquet(writer) => writer.add_bfm_batch(data),
@@ -96,6 +123,23 @@ impl Writer {
         }
     }
 
+    /// Add a batch of CQI data to the writer
+    ///
+    /// # Arguments
+    ///
+    /// * `data` A batch (slice) of data to write to the file
+    #[cfg_attr(not(feature = "parquet"), allow(unused_variables))]
+    pub fn add_cqi_batch(&mut self, data: &[CqiData]) -> Result<(), PersistenceError> {
+        match self {
+            #[cfg(feature = "parquet")]
+            Writer::Parquet(writer) => writer.add_cqi_batch(data),
+            Writer::_Dummy => {
+                log::warn!("Tried to write to dummy file; Ignoring. Specify a proper file type.");
+                Ok(())
+            }
+        }
+    }
+
     /// Finalize the file writes, i.e. clear all buffers and make sure
     /// the data is actually written to file.
     ///
@@ -1,12 +1,32 @@
 //! Parquet file writer
 use crate::errors::PersistenceError;
-use crate::BfaData;
-use crate::BfmData;
+#[cfg(feature = "bfi_metadata")]
+use crate::BfiMetadata;
+use crate::CqiData;
+use crate::MacHeader;
+#[cfg(feature = "ndpa")]
+use crate::Ndpa;
+#[cfg(feature = "radio_metadata")]
+use crate::RadioMetadata;
+use crate::{dequantize, BfaData};
+use crate::{BfmData, Precision};
+#[cfg(feature = "ndpa")]
+use arrow::array::Float64Array;
+use arrow::array::PrimitiveBuilder;
+#[cfg(feature = "ndpa")]
+use arrow::array::StructBuilder;
 use arrow::array::{
-    ArrayRef, Float64Builder, ListBuilder, UInt16Array, UInt16Builder, UInt8Array, UInt8Builder,
+    ArrayRef, BooleanArray, FixedSizeBinaryBuilder, Float32Builder, Float64Builder, Int16Builder,
+    Int8Builder, ListBuilder, UInt16Array, UInt16Builder, UInt8Array, UInt8Builder,
 };
-use arrow::datatypes::{DataType, Field, Schema};
+#[cfg(feature = "radio_metadata")]
+use arrow::array::{Int8Array, UInt64Array};
+#[cfg(feature = "ndpa")]
+use arrow::datatypes::Fields;
+use arrow::datatypes::{ArrowPrimitiveType, DataType, Field, Float32Type, Float64Type, Schema};
 use arrow::record_batch::RecordBatch;
+use ndarray::Array2;
+use num_complex::Complex64;
 use parquet::arrow::ArrowWriter;
 use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
@@ -15,24 +35,121 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 // ---------- Schema Creation ----------
+const NUM_HEADER_COLUMNS: usize = 9;
+const NUM_BASE_COLUMNS: usize = NUM_HEADER_COLUMNS + 3;
 #[cfg(feature = "bfi_metadata")]
-const NUM_META_COLUMNS: usize = 7; // 2 base + 5 extra metadata
+const NUM_BFI_META_COLUMNS: usize = 9;
 #[cfg(not(feature = "bfi_metadata"))]
-const NUM_META_COLUMNS: usize = 2;
+const NUM_BFI_META_COLUMNS: usize = 0;
+#[cfg(feature = "radio_metadata")]
+const NUM_RADIO_META_COLUMNS: usize = 6;
+#[cfg(not(feature = "radio_metadata"))]
+const NUM_RADIO_META_COLUMNS: usize = 0;
+#[cfg(feature = "ndpa")]
+const NUM_NDPA_COLUMNS: usize = 3;
+#[cfg(not(feature = "ndpa"))]
+const NUM_NDPA_COLUMNS: usize = 0;
+const NUM_OPTIONAL_COLUMNS: usize =
+    NUM_BFI_META_COLUMNS + NUM_RADIO_META_COLUMNS + NUM_NDPA_COLUMNS;
+const NUM_META_COLUMNS: usize = NUM_BASE_COLUMNS + NUM_OPTIONAL_COLUMNS;
 
-/// Create base schema (metadata)
-fn create_base_schema(num_data_fields: usize) -> Schema {
-    let mut fields = Vec::with_capacity(NUM_META_COLUMNS + num_data_fields);
+/// Fields of the NDPA STA Info struct
+#[cfg(feature = "ndpa")]
+fn sta_info_fields() -> Fields {
+    Fields::from(vec![
+        Field::new("aid", DataType::UInt16, false),
+        Field::new("feedback_type", DataType::UInt8, false),
+        Field::new("grouping", DataType::UInt8, true),
+        Field::new("codebook_info", DataType::UInt8, true),
+        Field::new("nc_index", DataType::UInt8, false),
+        Field::new("ru_start_index", DataType::UInt8, true),
+        Field::new("ru_end_index", DataType::UInt8, true),
+    ])
+}
+
+/// Create the fields identifying a record (capture, MAC header and FCS)
+fn create_header_fields(fields: &mut Vec<Field>) {
     fields.push(Field::new("timestamps", DataType::Float64, false));
     fields.push(Field::new("token_nums", DataType::UInt8, false));
+    fields.push(Field::new("receiver", DataType::FixedSizeBinary(6), false));
+    fields.push(Field::new(
+        "transmitter",
+        DataType::FixedSizeBinary(6),
+        false,
+    ));
+    fields.push(Field::new("bssid", DataType::FixedSizeBinary(6), false));
+    fields.push(Field::new("sequence_number", DataType::UInt16, false));
+    fields.push(Field::new("fragment_number", DataType::UInt8, false));
+    fields.push(Field::new("retry", DataType::Boolean, false));
+    fields.push(Field::new("fcs_valid", DataType::Boolean, true));
+}
+
+/// Create the fields of the optional metadata (depending on features)
+#[allow(unused_variables)] // All metadata might be disabled
+fn create_optional_fields(fields: &mut Vec<Field>) {
     #[cfg(feature = "bfi_metadata")]
     {
+        fields.push(Field::new("standard", DataType::UInt8, false));
         fields.push(Field::new("bandwidth", DataType::UInt16, false));
         fields.push(Field::new("nr_index", DataType::UInt8, false));
         fields.push(Field::new("nc_index", DataType::UInt8, false));
         fields.push(Field::new("codebook_info", DataType::UInt8, false));
         fields.push(Field::new("feedback_type", DataType::UInt8, false));
+        fields.push(Field::new("partial_bw_info", DataType::UInt16, true));
+        fields.push(Field::new("ru_start_index", DataType::UInt8, true));
+        fields.push(Field::new("ru_end_index", DataType::UInt8, true));
     }
+    #[cfg(feature = "radio_metadata")]
+    {
+        fields.push(Field::new("tsft", DataType::UInt64, true));
+        fields.push(Field::new("antenna_signal", DataType::Int8, true));
+        fields.push(Field::new("antenna_noise", DataType::Int8, true));
+        fields.push(Field::new("channel_freq", DataType::UInt16, true));
+        fields.push(Field::new("channel_flags", DataType::UInt16, true));
+        fields.push(Field::new(
+            "antenna_signals",
+            DataType::List(Arc::new(Field::new("item", DataType::Int8, true))),
+            false,
+        ));
+    }
+    #[cfg(feature = "ndpa")]
+    {
+        fields.push(Field::new("ndpa_timestamp", DataType::Float64, true));
+        fields.push(Field::new("ndpa_standard", DataType::UInt8, true));
+        fields.push(Field::new(
+            "ndpa_sta_info",
+            DataType::List(Arc::new(Field::new(
+                "item",
+                DataType::Struct(sta_info_fields()),
+                true,
+            ))),
+            true,
+        ));
+    }
+}
+
+/// Create base schema (metadata)
+fn create_base_schema(num_data_fields: usize) -> Schema {
+    let mut fields = Vec::with_capacity(NUM_META_COLUMNS + num_data_fields);
+    create_header_fields(&mut fields);
+    fields.push(Field::new(
+        "snr",
+        DataType::List(Arc::new(Field::new("item", DataType::Float32, true))),
+        false,
+    ));
+    // delta_snr is defined as List(List(Int8)) of shape (streams, subcarriers)
+    let delta_snr_inner = DataType::List(Arc::new(Field::new("item", DataType::Int8, true)));
+    fields.push(Field::new(
+        "delta_snr",
+        DataType::List(Arc::new(Field::new("item", delta_snr_inner, true))),
+        true,
+    ));
+    fields.push(Field::new(
+        "subcarrier_indices",
+        DataType::List(Arc::new(Field::new("item", DataType::Int16, true))),
+        true,
+    ));
+    create_optional_fields(&mut fields);
     Schema::new(fields)
 }
 
@@ -46,13 +163,28 @@ fn create_bfa_schema() -> Schema {
     Schema::new(fields)
 }
 
-/// Create BFM schema
-fn create_bfm_schema() -> Schema {
-    // Start with base schema (timestamps, token_nums, and optional metadata)
+/// Create BFA schema with the angles dequantized to radians
+fn create_bfa_radians_schema() -> Schema {
+    let mut fields = create_base_schema(1).fields().to_vec();
+    // bfa_radians is defined as List(List(Float64))
+    let inner = DataType::List(Arc::new(Field::new("item", DataType::Float64, true)));
+    let outer = DataType::List(Arc::new(Field::new("item", inner, true)));
+    fields.push(Arc::new(Field::new("bfa_radians", outer, true)));
+    Schema::new(fields)
+}
+
+/// Create BFM schema (of single or double precision matrices)
+fn create_bfm_schema(single_precision: bool) -> Schema {
+    // Start with base schema (timestamps, token_nums, snr, delta_snr, subcarrier_indices, and optional metadata)
     let mut fields = create_base_schema(2).fields().to_vec();
 
-    // Create triply nested list: List<List<List(Float64)>>
-    let inner = DataType::List(Arc::new(Field::new("item", DataType::Float64, true)));
+    // Create triply nested list: List<List<List(Float64/Float32)>>
+    let value_type = if single_precision {
+        DataType::Float32
+    } else {
+        DataType::Float64
+    };
+    let inner = DataType::List(Arc::new(Field::new("item", value_type, true)));
     let mid = DataType::List(Arc::new(Field::new("item", inner, true)));
     let outer = DataType::List(Arc::new(Field::new("item", mid, true)));
 
@@ -61,9 +193,342 @@ fn create_bfm_schema() -> Schema {
     Schema::new(fields)
 }
 
+/// Create CQI schema
+fn create_cqi_schema() -> Schema {
+    let mut fields = Vec::with_capacity(NUM_HEADER_COLUMNS + NUM_OPTIONAL_COLUMNS + 2);
+    create_header_fields(&mut fields);
+    fields.push(Field::new(
+        "ru_indices",
+        DataType::List(Arc::new(Field::new("item", DataType::UInt8, true))),
+        false,
+    ));
+    create_optional_fields(&mut fields);
+    // snr is defined as List(List(Float32)) of shape (streams, RUs)
+    let inner = DataType::List(Arc::new(Field::new("item", DataType::Float32, true)));
+    fields.push(Field::new(
+        "snr",
+        DataType::List(Arc::new(Field::new("item", inner, true))),
+        false,
+    ));
+    Schema::new(fields)
+}
+
+/// Create the MAC header columns (in schema order) from a batch of MAC headers
+fn create_mac_header_arrays<'a>(
+    mac_headers: impl ExactSizeIterator<Item = &'a MacHeader>,
+) -> Vec<ArrayRef> {
+    let num_records = mac_headers.len();
+    let mut receiver_builder = FixedSizeBinaryBuilder::with_capacity(num_records, 6);
+    let mut transmitter_builder = FixedSizeBinaryBuilder::with_capacity(num_records, 6);
+    let mut bssid_builder = FixedSizeBinaryBuilder::with_capacity(num_records, 6);
+    let mut sequence_number_vec = Vec::with_capacity(num_records);
+    let mut fragment_number_vec = Vec::with_capacity(num_records);
+    let mut retry_vec = Vec::with_capacity(num_records);
+
+    for header in mac_headers {
+        // NOTE: Appending only fails for values of the wrong size
+        receiver_builder
+            .append_value(header.receiver)
+            .expect("MAC address has 6 bytes");
+        transmitter_builder
+            .append_value(header.transmitter)
+            .expect("MAC address has 6 bytes");
+        bssid_builder
+            .append_value(header.bssid)
+            .expect("MAC address has 6 bytes");
+        sequence_number_vec.push(header.sequence_number);
+        fragment_number_vec.push(header.fragment_number);
+        retry_vec.push(header.retry);
+    }
+
+    vec![
+        Arc::new(receiver_builder.finish()) as ArrayRef,
+        Arc::new(transmitter_builder.finish()) as ArrayRef,
+        Arc::new(bssid_builder.finish()) as ArrayRef,
+        Arc::new(UInt16Array::from(sequence_number_vec)) as ArrayRef,
+        Arc::new(UInt8Array::from(fragment_number_vec)) as ArrayRef,
+        Arc::new(BooleanArray::from(retry_vec)) as ArrayRef,
+    ]
+}
+
+/// Create the FCS verification column (null for records not verified)
+fn create_fcs_valid_array(fcs_valid: impl Iterator<Item = Option<bool>>) -> ArrayRef {
+    Arc::new(fcs_valid.collect::<BooleanArray>()) as ArrayRef
+}
+
+/// Create the SNR column (list of per-stream SNR values per record)
+fn create_snr_array<'a>(snr: impl Iterator<Item = &'a Vec<f32>>) -> ArrayRef {
+    let mut snr_builder = ListBuilder::new(Float32Builder::new());
+    for stream_snr in snr {
+        snr_builder.values().append_slice(stream_snr);
+        snr_builder.append(true);
+    }
+    Arc::new(snr_builder.finish()) as ArrayRef
+}
+
+/// Create the delta SNR column (null for records without MU exclusive report)
+fn create_delta_snr_array<'a>(delta_snr: impl Iterator<Item = &'a Option<Array2<i8>>>) -> ArrayRef {
+    let mut outer_builder = ListBuilder::new(ListBuilder::new(Int8Builder::new()));
+    for record in delta_snr {
+        match record {
+            Some(delta_snr) => {
+                let inner_builder = outer_builder.values();
+                for stream in delta_snr.rows() {
+                    inner_builder
+                        .values()
+                        .extend(stream.iter().map(|&v| Some(v)));
+                    inner_builder.append(true);
+                }
+                outer_builder.append(true);
+            }
+            None => outer_builder.append(false),
+        }
+    }
+    Arc::new(outer_builder.finish()) as ArrayRef
+}
+
+/// Create the subcarrier indices column (null if unknown for a record)
+fn create_subcarrier_indices_array<'a>(
+    subcarrier_indices: impl Iterator<Item = &'a Option<Vec<i16>>>,
+) -> ArrayRef {
+    let mut builder = ListBuilder::new(Int16Builder::new());
+    for indices in subcarrier_indices {
+        match indices {
+            Some(indices) => {
+                builder.values().append_slice(indices);
+                builder.append(true);
+            }
+            None => builder.append(false),
+        }
+    }
+    Arc::new(builder.finish()) as ArrayRef
+}
+
+/// Create the metadata columns (in schema order) from a batch of metadata
+#[cfg(feature = "bfi_metadata")]
+fn create_metadata_arrays<'a>(
+    metadata: impl ExactSizeIterator<Item = &'a BfiMetadata>,
+) -> Vec<ArrayRef> {
+    let num_records = metadata.len();
+    let (
+        mut standard_vec,
+        mut bandwidth_vec,
+        mut nr_index_vec,
+        mut nc_index_vec,
+        mut codebook_vec,
+        mut feedback_type_vec,
+        mut partial_bw_info_vec,
+        mut ru_start_index_vec,
+        mut ru_end_index_vec,
+    ) = (
+        Vec::with_capacity(num_records),
+        Vec::with_capacity(num_records),
+        Vec::with_capacity(num_records),
+        Vec::with_capacity(num_records),
+        Vec::with_capacity(num_records),
+        Vec::with_capacity(num_records),
+        Vec::with_capacity(num_records),
+        Vec::with_capacity(num_records),
+        Vec::with_capacity(num_records),
+    );
+
+    for meta in metadata {
+        standard_vec.push(meta.standard as u8);
+        bandwidth_vec.push(meta.bandwidth);
+        nr_index_vec.push(meta.nr_index);
+        nc_index_vec.push(meta.nc_index);
+        codebook_vec.push(meta.codebook_info);
+        feedback_type_vec.push(meta.feedback_type);
+        partial_bw_info_vec.push(meta.partial_bw_info);
+        ru_start_index_vec.push(meta.ru_start_index);
+        ru_end_index_vec.push(meta.ru_end_index);
+    }
+
+    vec![
+        Arc::new(UInt8Array::from(standard_vec)) as ArrayRef,
+        Arc::new(UInt16Array::from(bandwidth_vec)) as ArrayRef,
+        Arc::new(UInt8Array::from(nr_index_vec)) as ArrayRef,
+        Arc::new(UInt8Array::from(nc_index_vec)) as ArrayRef,
+        Arc::new(UInt8Array::from(codebook_vec)) as ArrayRef,
+        Arc::new(UInt8Array::from(feedback_type_vec)) as ArrayRef,
+        Arc::new(UInt16Array::from(partial_bw_info_vec)) as ArrayRef,
+        Arc::new(UInt8Array::from(ru_start_index_vec)) as ArrayRef,
+        Arc::new(UInt8Array::from(ru_end_index_vec)) as ArrayRef,
+    ]
+}
+
+/// Create the radio metadata columns (in schema order) from a batch of radio metadata
+#[cfg(feature = "radio_metadata")]
+fn create_radio_metadata_arrays<'a>(
+    radio: impl ExactSizeIterator<Item = &'a RadioMetadata>,
+) -> Vec<ArrayRef> {
+    let num_records = radio.len();
+    let mut tsft_vec = Vec::with_capacity(num_records);
+    let mut antenna_signal_vec = Vec::with_capacity(num_records);
+    let mut antenna_noise_vec = Vec::with_capacity(num_records);
+    let mut channel_freq_vec = Vec::with_capacity(num_records);
+    let mut channel_flags_vec = Vec::with_capacity(num_records);
+    let mut antenna_signals_builder = ListBuilder::new(Int8Builder::new());
+
+    for meta in radio {
+        tsft_vec.push(meta.tsft);
+        antenna_signal_vec.push(meta.antenna_signal);
+        antenna_noise_vec.push(meta.antenna_noise);
+        channel_freq_vec.push(meta.channel_freq);
+        channel_flags_vec.push(meta.channel_flags);
+        antenna_signals_builder
+            .values()
+            .append_slice(&meta.antenna_signals);
+        antenna_signals_builder.append(true);
+    }
+
+    vec![
+        Arc::new(UInt64Array::from(tsft_vec)) as ArrayRef,
+        Arc::new(Int8Array::from(antenna_signal_vec)) as ArrayRef,
+        Arc::new(Int8Array::from(antenna_noise_vec)) as ArrayRef,
+        Arc::new(UInt16Array::from(channel_freq_vec)) as ArrayRef,
+        Arc::new(UInt16Array::from(channel_flags_vec)) as ArrayRef,
+        Arc::new(antenna_signals_builder.finish()) as ArrayRef,
+    ]
+}
+
+/// Create the NDPA columns (in schema order; null for records without NDPA)
+#[cfg(feature = "ndpa")]
+fn create_ndpa_arrays<'a>(ndpas: impl ExactSizeIterator<Item = &'a Option<Ndpa>>) -> Vec<ArrayRef> {
+    let num_records = ndpas.len();
+    let mut timestamp_vec = Vec::with_capacity(num_records);
+    let mut standard_vec = Vec::with_capacity(num_records);
+    let mut sta_info_builder =
+        ListBuilder::new(StructBuilder::from_fields(sta_info_fields(), num_records));
+
+    for ndpa in ndpas {
+        timestamp_vec.push(ndpa.as_ref().map(|ndpa| ndpa.timestamp));
+        standard_vec.push(ndpa.as_ref().map(|ndpa| ndpa.standard as u8));
+        let Some(ndpa) = ndpa else {
+            sta_info_builder.append(false);
+            continue;
+        };
+
+        // NOTE: Field builders are accessed by their index in `sta_info_fields`
+        let struct_builder = sta_info_builder.values();
+        for info in &ndpa.sta_info {
+            struct_builder
+                .field_builder::<UInt16Builder>(0)
+                .expect("Field 0 is the AID")
+                .append_value(info.aid);
+            for (i, value) in [
+                (1, Some(info.feedback_type)),
+                (2, info.grouping),
+                (3, info.codebook_info),
+                (4, Some(info.nc_index)),
+                (5, info.ru_start_index),
+                (6, info.ru_end_index),
+            ] {
+                struct_builder
+                    .field_builder::<UInt8Builder>(i)
+                    .expect("Fields 1-6 are UInt8")
+                    .append_option(value);
+            }
+            struct_builder.append(true);
+        }
+        sta_info_builder.append(true);
+    }
+
+    vec![
+        Arc::new(Float64Array::from(timestamp_vec)) as ArrayRef,
+        Arc::new(UInt8Array::from(standard_vec)) as ArrayRef,
+        Arc::new(sta_info_builder.finish()) as ArrayRef,
+    ]
+}
+
+/// Create the BFA angles column (nested list of quantized angles per subcarrier)
+fn create_bfa_angles_array(data: &[BfaData]) -> ArrayRef {
+    let mut outer_builder = ListBuilder::new(ListBuilder::new(UInt16Builder::new()));
+    for d in data {
+        let inner_builder = outer_builder.values();
+        for inner in d.bfa_angles.rows() {
+            inner_builder
+                .values()
+                .extend(inner.iter().map(|&v| Some(v)));
+            inner_builder.append(true);
+        }
+        outer_builder.append(true);
+    }
+    Arc::new(outer_builder.finish()) as ArrayRef
+}
+
+/// Create the BFA radians column (nested list of dequantized angles per subcarrier)
+fn create_bfa_radians_array(data: &[BfaData]) -> Result<ArrayRef, PersistenceError> {
+    let mut outer_builder = ListBuilder::new(ListBuilder::new(Float64Builder::new()));
+    for d in data {
+        let radians = dequantize(d)?;
+        let inner_builder = outer_builder.values();
+        for inner in radians.rows() {
+            inner_builder
+                .values()
+                .extend(inner.iter().map(|&v| Some(v)));
+            inner_builder.append(true);
+        }
+        outer_builder.append(true);
+    }
+    Ok(Arc::new(outer_builder.finish()) as ArrayRef)
+}
+
+/// Create the BFM columns (absolute value and phase) from a batch of BFM data
+///
+/// Both columns are triple nested lists of shape (antennas, streams, subcarriers)
+/// with values of the arrow type `P`.
+fn create_bfm_arrays<T: Precision, P: ArrowPrimitiveType>(
+    data: &[BfmData<T>],
+    convert: impl Fn(f64) -> P::Native,
+) -> (ArrayRef, ArrayRef) {
+    let mut abs_outer = ListBuilder::new(ListBuilder::new(ListBuilder::new(
+        PrimitiveBuilder::<P>::new(),
+    )));
+    let mut phase_outer = ListBuilder::new(ListBuilder::new(ListBuilder::new(
+        PrimitiveBuilder::<P>::new(),
+    )));
+
+    for d in data {
+        let abs_middle = abs_outer.values();
+        let phase_middle = phase_outer.values();
+
+        for antenna in d.feedback_matrix.outer_iter() {
+            let abs_inner = abs_middle.values();
+            let phase_inner = phase_middle.values();
+
+            for core in antenna.outer_iter() {
+                let abs_builder = abs_inner.values();
+                let phase_builder = phase_inner.values();
+
+                for value in core {
+                    let value = Complex64::new(value.re.to_f64(), value.im.to_f64());
+                    abs_builder.append_value(convert(value.norm()));
+                    phase_builder.append_value(convert(value.arg()));
+                }
+                abs_inner.append(true);
+                phase_inner.append(true);
+            }
+            abs_middle.append(true);
+            phase_middle.append(true);
+        }
+        abs_outer.append(true);
+        phase_outer.append(true);
+    }
+
+    (
+        Arc::new(abs_outer.finish()) as ArrayRef,
+        Arc::new(phase_outer.finish()) as ArrayRef,
+    )
+}
+
 /// A batch writer to write batches of BFA/BFM data to a Parquet file.
 pub struct BatchWriter {
     writer: Option<ArrowWriter<File>>,
+    /// Whether BFA angles are written dequantized to radians
+    angle_radians: bool,
+    /// Whether BFM are written in single precision
+    single_precision: bool,
 }
 
 impl BatchWriter {
@@ -76,6 +541,8 @@ impl BatchWriter {
             .map_err(|e| PersistenceError::Parquet(e.to_string()))?;
         Ok(Self {
             writer: Some(writer),
+            angle_radians: false,
+            single_precision: false,
         })
     }
 
@@ -84,9 +551,28 @@ impl BatchWriter {
         Self::new_with_schema(file_path, create_bfa_schema())
     }
 
+    /// Create a writer for BFA data with the angles dequantized to radians
+    pub fn new_bfa_radians(file_path: PathBuf) -> Result<Self, PersistenceError> {
+        let mut writer = Self::new_with_schema(file_path, create_bfa_radians_schema())?;
+        writer.angle_radians = true;
+        Ok(writer)
+    }
+
     /// Create a writer for BFM data
     pub fn new_bfm(file_path: PathBuf) -> Result<Self, PersistenceError> {
-        Self::new_with_schema(file_path, create_bfm_schema())
+        Self::new_with_schema(file_path, create_bfm_schema(false))
+    }
+
+    /// Create a writer for BFM data in single precision
+    pub fn new_bfm_single(file_path: PathBuf) -> Result<Self, PersistenceError> {
+        let mut writer = Self::new_with_schema(file_path, create_bfm_schema(true))?;
+        writer.single_precision = true;
+        Ok(writer)
+    }
+
+    /// Create a writer for CQI data
+    pub fn new_cqi(file_path: PathBuf) -> Result<Self, PersistenceError> {
+        Self::new_with_schema(file_path, create_cqi_schema())
     }
 
     /// Write a record batch
@@ -121,174 +607,137 @@ impl BatchWriter {
     }
 
     /// Add a batch of BFA data.
+    ///
+    /// Writers created with `new_bfa_radians` dequantize the angles first.
     pub fn add_bfa_batch(&mut self, data: &[BfaData]) -> Result<(), PersistenceError> {
         // Build timestamps and token_nums.
         let mut ts_builder = Float64Builder::new();
         let mut token_builder = UInt8Builder::new();
-        #[cfg(feature = "bfi_metadata")]
-        let (
-            mut bandwidth_vec,
-            mut nr_index_vec,
-            mut nc_index_vec,
-            mut codebook_vec,
-            mut feedback_type_vec,
-        ) = (
-            Vec::with_capacity(data.len()),
-            Vec::with_capacity(data.len()),
-            Vec::with_capacity(data.len()),
-            Vec::with_capacity(data.len()),
-            Vec::with_capacity(data.len()),
-        );
-        // Build bfa_angles as nested lists.
-        let mut outer_builder = ListBuilder::new(ListBuilder::new(UInt16Builder::new()));
-
         for d in data {
             ts_builder.append_value(d.timestamp);
             token_builder.append_value(d.token_number);
-            #[cfg(feature = "bfi_metadata")]
-            {
-                bandwidth_vec.push(d.metadata.bandwidth);
-                nr_index_vec.push(d.metadata.nr_index);
-                nc_index_vec.push(d.metadata.nc_index);
-                codebook_vec.push(d.metadata.codebook_info);
-                feedback_type_vec.push(d.metadata.feedback_type);
-            }
-            let inner_builder = outer_builder.values();
-            for inner in &d.bfa_angles {
-                for &angle in inner {
-                    inner_builder.values().append_value(angle);
-                }
-                inner_builder.append(true);
-            }
-            outer_builder.append(true);
         }
         let ts_array = Arc::new(ts_builder.finish()) as ArrayRef;
         let token_array = Arc::new(token_builder.finish()) as ArrayRef;
-        let bfa_angles_array = Arc::new(outer_builder.finish()) as ArrayRef;
+        let bfa_angles_array = if self.angle_radians {
+            create_bfa_radians_array(data)?
+        } else {
+            create_bfa_angles_array(data)
+        };
+        let snr_array = create_snr_array(data.iter().map(|d| &d.snr));
+        let delta_snr_array = create_delta_snr_array(data.iter().map(|d| &d.delta_snr));
+        let indices_array =
+            create_subcarrier_indices_array(data.iter().map(|d| &d.subcarrier_indices));
         let mut arrays = vec![ts_array, token_array];
+        arrays.extend(create_mac_header_arrays(data.iter().map(|d| &d.mac_header)));
+        arrays.push(create_fcs_valid_array(data.iter().map(|d| d.fcs_valid)));
+        arrays.extend([snr_array, delta_snr_array, indices_array]);
 
         #[cfg(feature = "bfi_metadata")]
-        {
-            let bandwidth_array = Arc::new(UInt16Array::from(bandwidth_vec)) as ArrayRef;
-            let nr_index_array = Arc::new(UInt8Array::from(nr_index_vec)) as ArrayRef;
-            let nc_index_array = Arc::new(UInt8Array::from(nc_index_vec)) as ArrayRef;
-            let codebook_array = Arc::new(UInt8Array::from(codebook_vec)) as ArrayRef;
-            let feedback_type_array = Arc::new(UInt8Array::from(feedback_type_vec)) as ArrayRef;
-            arrays.push(bandwidth_array);
-            arrays.push(nr_index_array);
-            arrays.push(nc_index_array);
-            arrays.push(codebook_array);
-            arrays.push(feedback_type_array);
-        }
+        arrays.extend(create_metadata_arrays(data.iter().map(|d| &d.metadata)));
+        #[cfg(feature = "radio_metadata")]
+        arrays.extend(create_radio_metadata_arrays(data.iter().map(|d| &d.radio)));
+        #[cfg(feature = "ndpa")]
+        arrays.extend(create_ndpa_arrays(data.iter().map(|d| &d.ndpa)));
         arrays.push(bfa_angles_array);
 
-        let schema = Arc::new(create_bfa_schema());
+        let schema = Arc::new(if self.angle_radians {
+            create_bfa_radians_schema()
+        } else {
+            create_bfa_schema()
+        });
         let batch = RecordBatch::try_new(schema, arrays)?;
         self.write(batch)
     }
 
     /// Add a batch of BFM data.
-    /// For each BfmData record, the feedback_matrix (ndarray of Complex64 with shape (m,n,k))
+    /// For each BfmData record, the feedback_matrix (ndarray of complex values with shape (m,n,k))
     /// is converted into two columns:
-    /// - "bfm_abs": triple nested lists of Float64 containing the absolute values (flattened row‑major per subcarrier)
-    /// - "bfm_phase": triple nested lists of Float64 containing the phase (argument) values.
-    pub fn add_bfm_batch(&mut self, data: &[BfmData]) -> Result<(), PersistenceError> {
-        let num_records = data.len();
+    /// - "bfm_abs": triple nested lists containing the absolute values (flattened row‑major per subcarrier)
+    /// - "bfm_phase": triple nested lists containing the phase (argument) values.
+    ///
+    /// Values are written in the precision of the writer (see `new_bfm_single`).
+    pub fn add_bfm_batch<T: Precision>(
+        &mut self,
+        data: &[BfmData<T>],
+    ) -> Result<(), PersistenceError> {
         let mut ts_builder = Float64Builder::new();
         let mut token_builder = UInt8Builder::new();
-
-        #[cfg(feature = "bfi_metadata")]
-        let (
-            mut bandwidth_vec,
-            mut nr_index_vec,
-            mut nc_index_vec,
-            mut codebook_vec,
-            mut feedback_type_vec,
-        ) = (
-            Vec::with_capacity(num_records),
-            Vec::with_capacity(num_records),
-            Vec::with_capacity(num_records),
-            Vec::with_capacity(num_records),
-            Vec::with_capacity(num_records),
-        );
-
-        // Create triple-nested ListBuilders for bfm_abs and bfm_phase.
-        // Each will build a List<List<List<Float64>>>
-        let mut abs_outer =
-            ListBuilder::new(ListBuilder::new(ListBuilder::new(Float64Builder::new())));
-        let mut phase_outer =
-            ListBuilder::new(ListBuilder::new(ListBuilder::new(Float64Builder::new())));
-
         for d in data {
             ts_builder.append_value(d.timestamp);
             token_builder.append_value(d.token_number);
-            #[cfg(feature = "bfi_metadata")]
-            {
-                bandwidth_vec.push(d.metadata.bandwidth);
-                nr_index_vec.push(d.metadata.nr_index);
-                nc_index_vec.push(d.metadata.nc_index);
-                codebook_vec.push(d.metadata.codebook_info);
-                feedback_type_vec.push(d.metadata.feedback_type);
-            }
-
-            // Build triple-nested list for absolute values.
-
-            // abs_outer: ListBuilder<ListBuilder<ListBuilder<Float64>>>
-            // Get mutable reference to the middle builder for the current record.
-            let (m, n, k) = d.feedback_matrix.dim();
-            let abs_middle = abs_outer.values();
-            let phase_middle = phase_outer.values();
-
-            for antenna in 0..m {
-                // For each row, get the inner builder.
-                let abs_inner = abs_middle.values();
-                let phase_inner = phase_middle.values();
-
-                for core in 0..n {
-                    // For each column, get the Float64Builder.
-                    let abs_builder = abs_inner.values();
-                    let phase_builder = phase_inner.values();
-
-                    for subcarrier in 0..k {
-                        abs_builder
-                            .append_value(d.feedback_matrix[(antenna, core, subcarrier)].norm());
-                        phase_builder
-                            .append_value(d.feedback_matrix[(antenna, core, subcarrier)].arg());
-                    }
-                    abs_inner.append(true);
-                    phase_inner.append(true);
-                }
-                abs_middle.append(true);
-                phase_middle.append(true);
-            }
-            abs_outer.append(true);
-            phase_outer.append(true);
         }
 
         let ts_array = Arc::new(ts_builder.finish()) as ArrayRef;
         let token_array = Arc::new(token_builder.finish()) as ArrayRef;
-        let bfm_abs_array = Arc::new(abs_outer.finish()) as ArrayRef;
-        let bfm_phase_array = Arc::new(phase_outer.finish()) as ArrayRef;
+        let (bfm_abs_array, bfm_phase_array) = if self.single_precision {
+            create_bfm_arrays::<T, Float32Type>(data, |v| v as f32)
+        } else {
+            create_bfm_arrays::<T, Float64Type>(data, |v| v)
+        };
+        let snr_array = create_snr_array(data.iter().map(|d| &d.snr));
+        let delta_snr_array = create_delta_snr_array(data.iter().map(|d| &d.delta_snr));
+        let indices_array =
+            create_subcarrier_indices_array(data.iter().map(|d| &d.subcarrier_indices));
 
         let mut arrays = vec![ts_array, token_array];
+        arrays.extend(create_mac_header_arrays(data.iter().map(|d| &d.mac_header)));
+        arrays.push(create_fcs_valid_array(data.iter().map(|d| d.fcs_valid)));
+        arrays.extend([snr_array, delta_snr_array, indices_array]);
 
         #[cfg(feature = "bfi_metadata")]
-        {
-            let bandwidth_array = Arc::new(UInt16Array::from(bandwidth_vec)) as ArrayRef;
-            let nr_index_array = Arc::new(UInt8Array::from(nr_index_vec)) as ArrayRef;
-            let nc_index_array = Arc::new(UInt8Array::from(nc_index_vec)) as ArrayRef;
-            let codebook_array = Arc::new(UInt8Array::from(codebook_vec)) as ArrayRef;
-            let feedback_type_array = Arc::new(UInt8Array::from(feedback_type_vec)) as ArrayRef;
-            arrays.push(bandwidth_array);
-            arrays.push(nr_index_array);
-            arrays.push(nc_index_array);
-            arrays.push(codebook_array);
-            arrays.push(feedback_type_array);
-        }
+        arrays.extend(create_metadata_arrays(data.iter().map(|d| &d.metadata)));
+        #[cfg(feature = "radio_metadata")]
+        arrays.extend(create_radio_metadata_arrays(data.iter().map(|d| &d.radio)));
+        #[cfg(feature = "ndpa")]
+        arrays.extend(create_ndpa_arrays(data.iter().map(|d| &d.ndpa)));
         arrays.push(bfm_abs_array);
         arrays.push(bfm_phase_array);
 
-        let schema = Arc::new(create_bfm_schema());
+        let schema = Arc::new(create_bfm_schema(self.single_precision));
+        let batch = RecordBatch::try_new(schema, arrays)?;
+        self.write(batch)
+    }
+
+    /// Add a batch of CQI data.
+    pub fn add_cqi_batch(&mut self, data: &[CqiData]) -> Result<(), PersistenceError> {
+        let mut ts_builder = Float64Builder::new();
+        let mut token_builder = UInt8Builder::new();
+        let mut ru_indices_builder = ListBuilder::new(UInt8Builder::new());
+        let mut snr_builder = ListBuilder::new(ListBuilder::new(Float32Builder::new()));
+
+        for d in data {
+            ts_builder.append_value(d.timestamp);
+            token_builder.append_value(d.token_number);
+            ru_indices_builder.values().append_slice(&d.ru_indices);
+            ru_indices_builder.append(true);
+            let inner_builder = snr_builder.values();
+            for stream in d.snr.rows() {
+                inner_builder
+                    .values()
+                    .extend(stream.iter().map(|&v| Some(v)));
+                inner_builder.append(true);
+            }
+            snr_builder.append(true);
+        }
+
+        let mut arrays = vec![
+            Arc::new(ts_builder.finish()) as ArrayRef,
+            Arc::new(token_builder.finish()) as ArrayRef,
+        ];
+        arrays.extend(create_mac_header_arrays(data.iter().map(|d| &d.mac_header)));
+        arrays.push(create_fcs_valid_array(data.iter().map(|d| d.fcs_valid)));
+        arrays.push(Arc::new(ru_indices_builder.finish()) as ArrayRef);
+
+        #[cfg(feature = "bfi_metadata")]
+        arrays.extend(create_metadata_arrays(data.iter().map(|d| &d.metadata)));
+        #[cfg(feature = "radio_metadata")]
+        arrays.extend(create_radio_metadata_arrays(data.iter().map(|d| &d.radio)));
+        #[cfg(feature = "ndpa")]
+        arrays.extend(create_ndpa_arrays(data.iter().map(|d| &d.ndpa)));
+        arrays.push(Arc::new(snr_builder.finish()) as ArrayRef);
+
+        let schema = Arc::new(create_cqi_schema());
         let batch = RecordBatch::try_new(schema, arrays)?;
         self.write(batch)
     }
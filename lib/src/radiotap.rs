@@ -0,0 +1,388 @@
+//! Radiotap header parsing.
+//!
+//! Captured WiFi frames are prefixed with a radiotap header carrying
+//! information about the reception of the frame. The header consists of a
+//! fixed part (version, length), a chain of `present` bitmasks and the data
+//! of all present fields, each aligned to its natural boundary.
+//!
+//! See <https://www.radiotap.org> for the specification of the fields.
+use crate::errors::BfaExtractionError;
+
+/// Length of the fixed part of the radiotap header (version, pad, length, first present word)
+const RADIOTAP_MIN_LENGTH: usize = 8;
+
+/// Present bit signalling that the next bitmask restarts the radiotap namespace
+const RADIOTAP_NAMESPACE_BIT: u32 = 29;
+/// Present bit signalling that the next bitmask belongs to a vendor namespace
+const VENDOR_NAMESPACE_BIT: u32 = 30;
+/// Present bit signalling that another bitmask follows
+const EXT_BIT: u32 = 31;
+
+/// Radiotap fields of interest
+const TSFT: u32 = 0;
+const FLAGS: u32 = 1;
+const CHANNEL: u32 = 3;
+const ANTENNA_SIGNAL: u32 = 5;
+const ANTENNA_NOISE: u32 = 6;
+const ANTENNA: u32 = 11;
+
+/// Bit of the Flags field signalling that the frame includes the FCS
+const FLAGS_FCS_AT_END: u8 = 0x10;
+
+/// Reception information of a frame extracted from the link layer header.
+///
+/// Fields follow the definitions of radiotap; other link layer headers fill
+/// in what they provide.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RadioMetadata {
+    /// Value of the TSF timer when the first bit of the frame arrived (in µs)
+    pub tsft: Option<u64>,
+    /// Combined RF signal power at the antennas (in dBm)
+    pub antenna_signal: Option<i8>,
+    /// RF noise power at the antennas (in dBm)
+    pub antenna_noise: Option<i8>,
+    /// Channel center frequency (in MHz)
+    pub channel_freq: Option<u16>,
+    /// Channel flags (band, modulation)
+    pub channel_flags: Option<u16>,
+    /// RF signal power at the individual antennas (in dBm), ordered by antenna
+    pub antenna_signals: Vec<i8>,
+}
+
+/// Parsed radiotap header
+#[derive(Debug, Clone)]
+pub(crate) struct Radiotap {
+    /// Total length of the radiotap header in bytes
+    pub length: usize,
+    /// Radiotap flags field (e.g. FCS presence)
+    pub flags: Option<u8>,
+    /// Reception information
+    #[cfg_attr(not(feature = "radio_metadata"), allow(dead_code))]
+    pub metadata: RadioMetadata,
+}
+
+impl Radiotap {
+    /// Whether the frame includes the FCS (`None` if the Flags field is missing)
+    pub fn fcs_present(&self) -> Option<bool> {
+        self.flags.map(|flags| flags & FLAGS_FCS_AT_END != 0)
+    }
+}
+
+/// Alignment and size of a field in the radiotap namespace.
+///
+/// Returns `None` for unknown fields, after which the remaining data can not
+/// be parsed anymore.
+fn field_layout(bit: u32) -> Option<(usize, usize)> {
+    let layout = match bit {
+        0 => (8, 8),                    // TSFT
+        1 | 2 => (1, 1),                // Flags, Rate
+        3 => (2, 4),                    // Channel
+        4 => (1, 2),                    // FHSS
+        5 | 6 => (1, 1),                // Antenna signal/noise
+        7..=9 => (2, 2),                // Lock quality, TX attenuation
+        10..=13 => (1, 1),              // TX power, Antenna, dB signal/noise
+        14 | 15 => (2, 2),              // RX/TX flags
+        16 | 17 => (1, 1),              // RTS/data retries
+        18 => (4, 8),                   // XChannel
+        19 => (1, 3),                   // MCS
+        20 => (4, 8),                   // A-MPDU status
+        21 => (2, 12),                  // VHT
+        22 => (8, 12),                  // Timestamp
+        23 | 24 => (2, 12),             // HE, HE-MU
+        25 => (2, 6),                   // HE-MU-other-user
+        26 => (1, 1),                   // 0-length PSDU
+        27 => (2, 4),                   // L-SIG
+        VENDOR_NAMESPACE_BIT => (2, 6), // Vendor namespace header
+        _ => return None,
+    };
+    Some(layout)
+}
+
+/// Namespace a present bitmask belongs to
+#[derive(Clone, Copy, PartialEq)]
+enum Namespace {
+    /// Radiotap namespace with the bit offset of the bitmask
+    Radiotap { base_bit: u32 },
+    /// Vendor namespace; the data is skipped
+    Vendor,
+}
+
+/// Parse the radiotap header at the start of a captured frame
+///
+/// # Parameters
+/// * `buf` - Bytestream of the captured frame
+pub(crate) fn parse_radiotap(buf: &[u8]) -> Result<Radiotap, BfaExtractionError> {
+    let malformed = |reason: &str| BfaExtractionError::MalformedRadiotap {
+        reason: reason.to_string(),
+    };
+
+    if buf.len() < RADIOTAP_MIN_LENGTH {
+        return Err(malformed("header truncated"));
+    }
+    let length = u16::from_le_bytes([buf[2], buf[3]]) as usize;
+    if length < RADIOTAP_MIN_LENGTH || length > buf.len() {
+        return Err(malformed("invalid header length"));
+    }
+    let header = &buf[..length];
+
+    // Collect the chain of present bitmasks
+    let mut present_words = Vec::new();
+    let mut offset = 4;
+    loop {
+        if offset + 4 > length {
+            return Err(malformed("present bitmasks exceed header"));
+        }
+        let word = u32::from_le_bytes([
+            header[offset],
+            header[offset + 1],
+            header[offset + 2],
+            header[offset + 3],
+        ]);
+        present_words.push(word);
+        offset += 4;
+        if word & (1 << EXT_BIT) == 0 {
+            break;
+        }
+    }
+
+    let mut radiotap = Radiotap {
+        length,
+        flags: None,
+        metadata: RadioMetadata::default(),
+    };
+
+    // Per-antenna (antenna index, signal) of additional radiotap namespaces
+    let mut antenna_signals: Vec<(Option<u8>, i8)> = Vec::new();
+    let mut namespace = Namespace::Radiotap { base_bit: 0 };
+    let mut namespace_index = 0;
+    let mut vendor_skip = 0;
+
+    'words: for word in present_words {
+        // NOTE: The vendor namespace header announced how much data to skip
+        offset += vendor_skip;
+        vendor_skip = 0;
+
+        let mut antenna = None;
+        let mut signal = None;
+
+        for bit in 0..EXT_BIT {
+            if word & (1 << bit) == 0 || bit == RADIOTAP_NAMESPACE_BIT {
+                continue;
+            }
+            // NOTE: Namespace switches are valid in every bitmask, other
+            // fields of vendor namespaces are contained in the skipped data.
+            let field_bit = match namespace {
+                _ if bit == VENDOR_NAMESPACE_BIT => bit,
+                Namespace::Radiotap { base_bit } => base_bit + bit,
+                Namespace::Vendor => continue,
+            };
+            let Some((align, size)) = field_layout(field_bit) else {
+                // Unknown field, the position of the following data is unknown
+                break 'words;
+            };
+
+            offset = offset.next_multiple_of(align);
+            if offset + size > length {
+                return Err(malformed("field exceeds header"));
+            }
+            let field = &header[offset..offset + size];
+
+            match field_bit {
+                TSFT => {
+                    let tsft = u64::from_le_bytes(field.try_into().expect("TSFT has 8 bytes"));
+                    radiotap.metadata.tsft = Some(tsft);
+                }
+                FLAGS => radiotap.flags = Some(field[0]),
+                CHANNEL => {
+                    radiotap.metadata.channel_freq = Some(u16::from_le_bytes([field[0], field[1]]));
+                    radiotap.metadata.channel_flags =
+                        Some(u16::from_le_bytes([field[2], field[3]]));
+                }
+                ANTENNA_SIGNAL => signal = Some(field[0] as i8),
+                ANTENNA_NOISE if namespace_index == 0 => {
+                    radiotap.metadata.antenna_noise = Some(field[0] as i8)
+                }
+                ANTENNA => antenna = Some(field[0]),
+                VENDOR_NAMESPACE_BIT => {
+                    vendor_skip = u16::from_le_bytes([field[4], field[5]]) as usize
+                }
+                _ => {}
+            }
+            offset += size;
+        }
+
+        // NOTE: The first namespace holds the combined signal, additional
+        // radiotap namespaces hold the signal of individual antennas.
+        if let Some(signal) = signal {
+            if namespace_index == 0 {
+                radiotap.metadata.antenna_signal = Some(signal);
+            } else {
+                antenna_signals.push((antenna, signal));
+            }
+        }
+
+        namespace = if word & (1 << RADIOTAP_NAMESPACE_BIT) != 0 {
+            namespace_index += 1;
+            Namespace::Radiotap { base_bit: 0 }
+        } else if word & (1 << VENDOR_NAMESPACE_BIT) != 0 {
+            namespace_index += 1;
+            Namespace::Vendor
+        } else {
+            match namespace {
+                Namespace::Radiotap { base_bit } => Namespace::Radiotap {
+                    base_bit: base_bit + 32,
+                },
+                Namespace::Vendor => Namespace::Vendor,
+            }
+        };
+    }
+
+    // Entries without antenna index keep their order of appearance
+    antenna_signals.sort_by_key(|&(antenna, _)| antenna);
+    radiotap.metadata.antenna_signals = antenna_signals.into_iter().map(|(_, s)| s).collect();
+
+    Ok(radiotap)
+}
+
+/// Build a radiotap header carrying the given reception information
+///
+/// The combined fields are placed in the first namespace, followed by one
+/// radiotap namespace per entry of `antenna_signals` (like most drivers do).
+///
+/// # Parameters
+/// * `metadata` - Reception information to encode
+/// * `fcs_present` - Whether the frame following the header includes the FCS
+#[cfg_attr(not(feature = "bfi_metadata"), allow(dead_code))]
+pub(crate) fn build_radiotap(metadata: &RadioMetadata, fcs_present: bool) -> Vec<u8> {
+    let flags = if fcs_present { FLAGS_FCS_AT_END } else { 0 };
+
+    // Fields (bit and data) of every namespace, in order of their bits
+    let mut combined = Vec::new();
+    if let Some(tsft) = metadata.tsft {
+        combined.push((TSFT, tsft.to_le_bytes().to_vec()));
+    }
+    combined.push((FLAGS, vec![flags]));
+    if let Some(freq) = metadata.channel_freq {
+        let channel_flags = metadata.channel_flags.unwrap_or(0);
+        let mut channel = freq.to_le_bytes().to_vec();
+        channel.extend_from_slice(&channel_flags.to_le_bytes());
+        combined.push((CHANNEL, channel));
+    }
+    if let Some(signal) = metadata.antenna_signal {
+        combined.push((ANTENNA_SIGNAL, vec![signal as u8]));
+    }
+    if let Some(noise) = metadata.antenna_noise {
+        combined.push((ANTENNA_NOISE, vec![noise as u8]));
+    }
+    let mut namespaces = vec![combined];
+    for (antenna, &signal) in metadata.antenna_signals.iter().enumerate() {
+        namespaces.push(vec![
+            (ANTENNA_SIGNAL, vec![signal as u8]),
+            (ANTENNA, vec![antenna as u8]),
+        ]);
+    }
+
+    // Version, pad and length (filled in below), followed by the bitmasks
+    let mut header = vec![0; 4];
+    for (i, fields) in namespaces.iter().enumerate() {
+        let mut word = fields.iter().fold(0u32, |word, (bit, _)| word | (1 << bit));
+        if i + 1 < namespaces.len() {
+            word |= (1 << RADIOTAP_NAMESPACE_BIT) | (1 << EXT_BIT);
+        }
+        header.extend_from_slice(&word.to_le_bytes());
+    }
+    for (bit, data) in namespaces.iter().flatten() {
+        let (align, _) = field_layout(*bit).expect("Only known fields are built");
+        header.resize(header.len().next_multiple_of(align), 0);
+        header.extend_from_slice(data);
+    }
+
+    let length = header.len() as u16;
+    header[2..4].copy_from_slice(&length.to_le_bytes());
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radiotap_parsing() {
+        // Radiotap header of the first packet in data/test_data/bfi.pcap
+        let header: &[u8] = &[
+            0x00, 0x00, 0x38, 0x00, 0x2f, 0x40, 0x40, 0xa0, 0x20, 0x08, 0x00, 0xa0, 0x20, 0x08,
+            0x00, 0x00, 0x6b, 0x25, 0x35, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x0c, 0x99, 0x16,
+            0x40, 0x01, 0xe9, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xbc, 0x7e,
+            0x35, 0x00, 0x00, 0x00, 0x00, 0x00, 0x16, 0x00, 0x11, 0x03, 0xe8, 0x00, 0xe9, 0x01,
+        ];
+
+        let result = parse_radiotap(header).unwrap();
+        assert_eq!(result.length, 56);
+        assert_eq!(result.flags, Some(0x10));
+        assert_eq!(result.fcs_present(), Some(true));
+        assert_eq!(
+            result.metadata,
+            RadioMetadata {
+                tsft: Some(3482987),
+                antenna_signal: Some(-23),
+                antenna_noise: None,
+                channel_freq: Some(5785),
+                channel_flags: Some(0x0140),
+                antenna_signals: vec![-24, -23],
+            }
+        );
+    }
+
+    #[test]
+    fn radiotap_vendor_namespace_is_skipped() {
+        // Flags and a vendor namespace (2 bytes of data) followed by another
+        // radiotap namespace with an antenna signal.
+        let header: &[u8] = &[
+            0x00, 0x00, 0x1b, 0x00, // version, pad, length 27
+            0x02, 0x00, 0x00, 0xc0, // flags, vendor namespace, ext
+            0x00, 0x00, 0x00, 0xa0, // vendor namespace: radiotap namespace, ext
+            0x20, 0x00, 0x00, 0x00, // antenna signal
+            0x10, 0x00, // flags, padding
+            0x00, 0x11, 0x22, 0x00, 0x02, 0x00, // OUI, sub namespace, skip length
+            0xaa, 0xbb, // vendor data
+            0xa0, // antenna signal
+        ];
+
+        let result = parse_radiotap(header).unwrap();
+        assert_eq!(result.flags, Some(0x10));
+        assert_eq!(result.metadata.antenna_signal, None);
+        assert_eq!(result.metadata.antenna_signals, vec![-96]);
+    }
+
+    #[test]
+    fn radiotap_building() {
+        let metadata = RadioMetadata {
+            tsft: Some(3482987),
+            antenna_signal: Some(-23),
+            antenna_noise: Some(-95),
+            channel_freq: Some(5785),
+            channel_flags: Some(0x0140),
+            antenna_signals: vec![-24, -23],
+        };
+
+        let header = build_radiotap(&metadata, true);
+        let result = parse_radiotap(&header).unwrap();
+        assert_eq!(result.length, header.len());
+        assert_eq!(result.fcs_present(), Some(true));
+        assert_eq!(result.metadata, metadata);
+
+        let header = build_radiotap(&RadioMetadata::default(), false);
+        let result = parse_radiotap(&header).unwrap();
+        assert_eq!(result.fcs_present(), Some(false));
+        assert_eq!(result.metadata, RadioMetadata::default());
+    }
+
+    #[test]
+    fn radiotap_truncated() {
+        let result = parse_radiotap(&[0x00, 0x00, 0x38, 0x00, 0x2f, 0x40, 0x40, 0xa0]);
+        assert!(matches!(
+            result,
+            Err(BfaExtractionError::MalformedRadiotap { .. })
+        ));
+    }
+}
@@ -0,0 +1,421 @@
+//! Reassembly of segmented beamforming feedback.
+//!
+//! Large feedback reports (e.g. 160 MHz with many antennas) exceed the
+//! maximum frame size and are split across multiple frames. Every segment
+//! carries a copy of the MIMO Control header, which signals the first
+//! segment and the number of remaining segments. The report is recovered by
+//! concatenating the payload of all segments in order.
+//!
+//! Segments of the same report are identified by the transmitter address and
+//! the sounding dialog token (the sounding timestamp for HT).
+//!
+//! With the `ndpa` feature, NDP Announcements in the stream are tracked as
+//! well and attached to the reports they solicited.
+use std::collections::HashMap;
+use std::time::Duration;
+
+use pcap::Packet;
+
+use crate::errors::BfaExtractionError;
+use crate::fcs::FcsPolicy;
+use crate::link_layer::LinkType;
+#[cfg(feature = "ndpa")]
+use crate::ndpa::{is_ndpa, parse_ndpa, NdpaTracker};
+use crate::pcap::{
+    decode_feedback, parse_frame, CaptureInfo, CapturedFrame, Feedback, FeedbackFrame, MimoControl,
+};
+
+/// Default time after which incomplete reports are dropped.
+const DEFAULT_SEGMENT_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Key identifying segments of the same report: (transmitter, sounding id)
+type SegmentKey = ([u8; 6], u32);
+
+/// A report for which not all segments have been received yet.
+struct PendingReport {
+    /// MIMO Control header of the first segment
+    mimo_control: MimoControl,
+    /// Capture information of the first segment
+    capture: CaptureInfo,
+    /// Number of remaining segments expected in the next frame
+    expected_remaining: u8,
+    /// Concatenated report bytes received so far
+    report: Vec<u8>,
+}
+
+/// Reassembles segmented feedback reports from a stream of packets.
+///
+/// Unsegmented reports are decoded directly. Incomplete reports are dropped
+/// if a segment is missing or if they are not completed within the timeout
+/// (measured in capture time).
+pub struct FeedbackReassembler {
+    timeout: f64,
+    fcs_policy: FcsPolicy,
+    pending: HashMap<SegmentKey, PendingReport>,
+    #[cfg(feature = "ndpa")]
+    ndpas: NdpaTracker,
+}
+
+impl Default for FeedbackReassembler {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEGMENT_TIMEOUT)
+    }
+}
+
+impl FeedbackReassembler {
+    /// Create a new reassembler
+    ///
+    /// # Parameters
+    /// * `timeout` - Time after which incomplete reports are dropped
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout: timeout.as_secs_f64(),
+            fcs_policy: FcsPolicy::default(),
+            pending: HashMap::new(),
+            #[cfg(feature = "ndpa")]
+            ndpas: NdpaTracker::default(),
+        }
+    }
+
+    /// Set the policy on how to treat the FCS of frames
+    ///
+    /// # Parameters
+    /// * `fcs_policy` - How to treat the FCS of frames
+    pub fn with_fcs_policy(mut self, fcs_policy: FcsPolicy) -> Self {
+        self.fcs_policy = fcs_policy;
+        self
+    }
+
+    /// Process a single packet
+    ///
+    /// # Parameters
+    /// * `packet` - The captured packet
+    /// * `link_type` - Link type of the capture the packet stems from
+    ///
+    /// # Returns
+    /// * The decoded feedback if the packet completes a report, `None` if more
+    ///   segments are required, the segment had to be dropped or the packet
+    ///   is an NDP Announcement.
+    pub fn process_packet(
+        &mut self,
+        packet: &Packet,
+        link_type: LinkType,
+    ) -> Result<Option<Feedback>, BfaExtractionError> {
+        let captured = CapturedFrame::from_packet(packet, link_type)?;
+
+        #[cfg(feature = "ndpa")]
+        if is_ndpa(captured.frame_control()) {
+            let ndpa = parse_ndpa(&captured, self.fcs_policy)?;
+            self.ndpas.insert(ndpa);
+            return Ok(None);
+        }
+
+        let frame = parse_frame(captured, self.fcs_policy)?;
+        self.process_frame(frame)
+    }
+
+    fn process_frame(
+        &mut self,
+        frame: FeedbackFrame,
+    ) -> Result<Option<Feedback>, BfaExtractionError> {
+        self.drop_expired(frame.capture.timestamp);
+
+        let key = (
+            frame.capture.mac_header.transmitter,
+            frame.mimo_control.sounding_id(),
+        );
+
+        // NOTE: The last HT segment looks like an unsegmented report. It
+        // continues a report if one is pending.
+        let continues_pending =
+            frame.mimo_control.is_first_segment().is_none() && self.pending.contains_key(&key);
+        if !frame.mimo_control.is_segmented() && !continues_pending {
+            return self.decode(&frame.mimo_control, frame.report, frame.capture);
+        }
+
+        let remaining = frame.mimo_control.remaining_segments();
+
+        // NOTE: HT does not signal the first segment. We consider it the start
+        // of a new report if no other report is pending.
+        let is_first = frame
+            .mimo_control
+            .is_first_segment()
+            .unwrap_or_else(|| !self.pending.contains_key(&key));
+
+        if is_first {
+            if self.pending.remove(&key).is_some() {
+                log::warn!(
+                    "Received new first segment for {:?} before completion; dropping previous report.",
+                    key
+                );
+            }
+            if remaining == 0 {
+                return self.decode(&frame.mimo_control, frame.report, frame.capture);
+            }
+            self.pending.insert(
+                key,
+                PendingReport {
+                    mimo_control: frame.mimo_control,
+                    capture: frame.capture,
+                    expected_remaining: remaining - 1,
+                    report: frame.report.to_vec(),
+                },
+            );
+            return Ok(None);
+        }
+
+        let Some(pending) = self.pending.get_mut(&key) else {
+            log::warn!(
+                "Received feedback segment for {:?} without first segment; dropping it.",
+                key
+            );
+            return Ok(None);
+        };
+
+        if pending.expected_remaining != remaining {
+            log::warn!(
+                "Missing feedback segment for {:?} (expected {} remaining, got {}); dropping report.",
+                key,
+                pending.expected_remaining,
+                remaining
+            );
+            self.pending.remove(&key);
+            return Ok(None);
+        }
+
+        pending.report.extend_from_slice(frame.report);
+        // NOTE: A reassembled report is only valid if all segments are
+        pending.capture.fcs_valid = match (pending.capture.fcs_valid, frame.capture.fcs_valid) {
+            (Some(pending_valid), Some(valid)) => Some(pending_valid && valid),
+            (pending_valid, valid) => pending_valid.or(valid),
+        };
+        if remaining > 0 {
+            pending.expected_remaining -= 1;
+            return Ok(None);
+        }
+
+        let complete = self
+            .pending
+            .remove(&key)
+            .expect("Pending report vanished during reassembly");
+        self.decode(&complete.mimo_control, &complete.report, complete.capture)
+    }
+
+    /// Decode a complete report and join it with the NDPA that solicited it
+    fn decode(
+        &self,
+        mimo_control: &MimoControl,
+        report: &[u8],
+        capture: CaptureInfo,
+    ) -> Result<Option<Feedback>, BfaExtractionError> {
+        #[allow(unused_mut)]
+        let mut capture = capture;
+
+        // NOTE: HT sounding is not announced by an NDPA
+        #[cfg(feature = "ndpa")]
+        if !matches!(mimo_control, MimoControl::Ht(_)) {
+            capture.ndpa = self
+                .ndpas
+                .find(
+                    capture.mac_header.receiver,
+                    mimo_control.dialog_token_number(),
+                    capture.timestamp,
+                )
+                .cloned();
+        }
+
+        decode_feedback(mimo_control, report, capture).map(Some)
+    }
+
+    /// Drop all pending reports whose first segment is older than the timeout.
+    fn drop_expired(&mut self, now: f64) {
+        let timeout = self.timeout;
+        self.pending.retain(|key, pending| {
+            let alive = now - pending.capture.timestamp <= timeout;
+            if !alive {
+                log::warn!("Feedback report for {:?} timed out; dropping it.", key);
+            }
+            alive
+        });
+    }
+
+    /// Number of reports still waiting for segments
+    pub fn num_pending(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::he_mimo_ctrl::HeMimoControl;
+    use crate::ht_mimo_ctrl::HtMimoControl;
+    use crate::MacHeader;
+
+    /// Create a HE 2x1, 20 MHz, Ng4 segment with the given segment fields
+    fn he_segment(remaining: u8, first: bool, report: &[u8], timestamp: f64) -> FeedbackFrame<'_> {
+        // Nc=1, Nr=2, Bw20, Ng4, codebook 0, SU, RU 0-8, token 7
+        let byte_1 = (remaining << 4) | ((first as u8) << 7);
+        let buf = [0b00001000, byte_1, 0b00000000, 0b11000100, 0b00000001];
+        FeedbackFrame {
+            capture: CaptureInfo {
+                timestamp,
+                mac_header: MacHeader {
+                    transmitter: [0, 1, 2, 3, 4, 5],
+                    ..Default::default()
+                },
+                fcs_valid: None,
+                #[cfg(feature = "ndpa")]
+                ndpa: None,
+                #[cfg(feature = "radio_metadata")]
+                radio: crate::RadioMetadata::default(),
+            },
+            mimo_control: MimoControl::He(HeMimoControl::from_buf(&buf)),
+            report,
+        }
+    }
+
+    /// Create a HT 2x1, 20 MHz, Ng4 segment of the given sounding
+    fn ht_segment(
+        remaining: u8,
+        sounding: u32,
+        report: &[u8],
+        timestamp: f64,
+    ) -> FeedbackFrame<'_> {
+        // Nc=1, Nr=2, 20 MHz, Ng4, codebook 0
+        let [t0, t1, t2, t3] = sounding.to_le_bytes();
+        let buf = [0b01000100, remaining << 3, t0, t1, t2, t3];
+        let mut frame = he_segment(0, true, report, timestamp);
+        frame.mimo_control = MimoControl::Ht(HtMimoControl::from_buf(&buf));
+        frame
+    }
+
+    /// Unwrap the angles of a completed report
+    fn angles(result: Result<Option<Feedback>, BfaExtractionError>) -> crate::BfaData {
+        match result.unwrap() {
+            Some(Feedback::Angles(data)) => data,
+            other => panic!("Expected angles, got {:?}", other),
+        }
+    }
+
+    /// 1 SNR byte followed by 64 subcarriers with 6 bit (4 phi + 2 psi) each
+    fn report() -> Vec<u8> {
+        (0..49u8).map(|i| i.wrapping_mul(37)).collect()
+    }
+
+    #[test]
+    fn reassemble_in_order() {
+        let report = report();
+        let mut reassembler = FeedbackReassembler::default();
+        let expected = angles(reassembler.process_frame(he_segment(0, true, &report, 0.0)));
+
+        let first = reassembler.process_frame(he_segment(2, true, &report[..5], 0.0));
+        assert!(first.unwrap().is_none());
+        let second = reassembler.process_frame(he_segment(1, false, &report[5..30], 0.01));
+        assert!(second.unwrap().is_none());
+        let third = reassembler.process_frame(he_segment(0, false, &report[30..], 0.02));
+        let result = angles(third);
+
+        assert_eq!(result.bfa_angles, expected.bfa_angles);
+        assert_eq!(result.snr, expected.snr);
+        assert_eq!(result.timestamp, 0.0);
+        assert_eq!(reassembler.num_pending(), 0);
+    }
+
+    #[test]
+    fn reassemble_ht_in_order() {
+        // 1 SNR byte followed by 16 subcarriers with 4 bit (3 phi + 1 psi) each
+        let report: Vec<u8> = (0..9u8).map(|i| i.wrapping_mul(37)).collect();
+        let mut reassembler = FeedbackReassembler::default();
+        let expected = angles(reassembler.process_frame(ht_segment(0, 0x12345678, &report, 0.0)));
+
+        let first = reassembler.process_frame(ht_segment(2, 0x12345678, &report[..3], 0.0));
+        assert!(first.unwrap().is_none());
+        let second = reassembler.process_frame(ht_segment(1, 0x12345678, &report[3..6], 0.01));
+        assert!(second.unwrap().is_none());
+        // The last HT segment is indistinguishable from an unsegmented report
+        let third = reassembler.process_frame(ht_segment(0, 0x12345678, &report[6..], 0.02));
+        let result = angles(third);
+
+        assert_eq!(result.bfa_angles, expected.bfa_angles);
+        assert_eq!(result.snr, expected.snr);
+        assert_eq!(result.timestamp, 0.0);
+        assert_eq!(reassembler.num_pending(), 0);
+    }
+
+    #[test]
+    fn ht_report_after_lost_segment() {
+        let report: Vec<u8> = (0..9u8).map(|i| i.wrapping_mul(37)).collect();
+        let mut reassembler = FeedbackReassembler::default();
+        let expected = angles(reassembler.process_frame(ht_segment(0, 2, &report, 0.0)));
+
+        let first = reassembler.process_frame(ht_segment(1, 1, &report[..3], 0.0));
+        assert!(first.unwrap().is_none());
+        // The last segment is lost, the next report answers a new sounding
+        let result = angles(reassembler.process_frame(ht_segment(0, 2, &report, 0.01)));
+
+        assert_eq!(result.bfa_angles, expected.bfa_angles);
+        assert_eq!(result.timestamp, 0.01);
+        assert_eq!(reassembler.num_pending(), 1);
+    }
+
+    #[test]
+    fn drop_on_missing_segment() {
+        let report = report();
+        let mut reassembler = FeedbackReassembler::default();
+        let first = reassembler.process_frame(he_segment(2, true, &report[..5], 0.0));
+        assert!(first.unwrap().is_none());
+        // Segment with one remaining is missing
+        let last = reassembler.process_frame(he_segment(0, false, &report[30..], 0.01));
+        assert!(last.unwrap().is_none());
+        assert_eq!(reassembler.num_pending(), 0);
+    }
+
+    #[test]
+    fn drop_on_timeout() {
+        let report = report();
+        let mut reassembler = FeedbackReassembler::new(Duration::from_millis(10));
+        let first = reassembler.process_frame(he_segment(1, true, &report[..5], 0.0));
+        assert!(first.unwrap().is_none());
+        assert_eq!(reassembler.num_pending(), 1);
+
+        let last = reassembler.process_frame(he_segment(0, false, &report[5..], 0.5));
+        assert!(last.unwrap().is_none());
+        assert_eq!(reassembler.num_pending(), 0);
+    }
+
+    #[test]
+    fn invalid_segment_flags_report() {
+        let report = report();
+        let mut reassembler = FeedbackReassembler::default();
+        let mut first = he_segment(1, true, &report[..5], 0.0);
+        first.capture.fcs_valid = Some(true);
+        let mut last = he_segment(0, false, &report[5..], 0.01);
+        last.capture.fcs_valid = Some(false);
+
+        assert!(reassembler.process_frame(first).unwrap().is_none());
+        let result = angles(reassembler.process_frame(last));
+        assert_eq!(result.fcs_valid, Some(false));
+    }
+
+    #[cfg(feature = "ndpa")]
+    #[test]
+    fn join_soliciting_ndpa() {
+        let ndpa = crate::Ndpa {
+            timestamp: 0.0,
+            beamformer: [0; 6],
+            dialog_token: 7,
+            standard: crate::WifiStandard::He,
+            sta_info: vec![],
+        };
+        let report = report();
+        let mut reassembler = FeedbackReassembler::default();
+        reassembler.ndpas.insert(ndpa.clone());
+
+        let result = angles(reassembler.process_frame(he_segment(0, true, &report, 0.005)));
+        assert_eq!(result.ndpa, Some(ndpa));
+        // Reports of later soundings are not joined with a stale NDPA
+        let result = angles(reassembler.process_frame(he_segment(0, true, &report, 1.0)));
+        assert_eq!(result.ndpa, None);
+    }
+}
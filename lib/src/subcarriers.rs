@@ -0,0 +1,396 @@
+//! Subcarrier (tone) indices of compressed beamforming feedback.
+//!
+//! The compressed feedback does not contain a matrix for every tone of the
+//! channel. Instead, only every Ng-th tone (plus some edge tones) within the
+//! requested part of the bandwidth is reported. This module reconstructs the
+//! set of reported tone indices, which also determines the number of
+//! subcarriers to extract from the payload.
+use crate::eht_mimo_ctrl::{EhtBandwidth, PartialBwInfo};
+use crate::errors::BfaExtractionError;
+use crate::he_mimo_ctrl::Bandwidth;
+
+/// Inclusive range of tone indices occupied by a 20 MHz subchannel
+type ToneSpan = (i16, i16);
+
+/// Subcarrier (tone) spacing of HE and EHT PPDUs in Hertz.
+///
+/// The frequency offset of a tone from the channel center is its index
+/// multiplied by this spacing.
+pub const HE_SUBCARRIER_SPACING_HZ: f64 = 78_125.0;
+
+/// First tone index of every RU26 in a 20 MHz channel (IEEE 802.11ax Table 27-7)
+const RU26_STARTS_20MHZ: [i16; 9] = [-121, -95, -68, -42, -16, 17, 43, 70, 96];
+
+/// First tone index of every RU26 in a 40 MHz channel (IEEE 802.11ax Table 27-8)
+#[rustfmt::skip]
+const RU26_STARTS_40MHZ: [i16; 18] = [
+    -243, -217, -189, -163, -136, -109, -83, -55, -29,
+    4, 30, 58, 84, 111, 138, 164, 192, 218,
+];
+
+/// First tone index of every RU26 in an 80 MHz channel (IEEE 802.11ax Table 27-9)
+#[rustfmt::skip]
+const RU26_STARTS_80MHZ: [i16; 37] = [
+    -499, -473, -445, -419, -392, -365, -339, -311, -285,
+    -257, -231, -203, -177, -150, -123, -97, -69, -43,
+    -16,
+    18, 44, 72, 98, 125, 152, 178, 206, 232,
+    260, 286, 314, 340, 367, 394, 420, 448, 474,
+];
+
+/// Get the subcarrier grouping factor Ng from the HE/EHT grouping bit
+fn grouping_factor(grouping: u8) -> i16 {
+    match grouping {
+        0 => 4,
+        _ => 16,
+    }
+}
+
+/// Tone indices reported in the lower half of a (sub)band, i.e. starting
+/// at `-4` and walking towards `-limit` in steps of `ng`. Returned in
+/// ascending order.
+fn half_band_tones(ng: i16, limit: i16) -> Vec<i16> {
+    (4..=limit).step_by(ng as usize).rev().map(|t| -t).collect()
+}
+
+/// Mirror lower half band tones to the upper half band (ascending order).
+fn mirror(tones: &[i16]) -> impl Iterator<Item = i16> + '_ {
+    tones.iter().rev().map(|t| -t)
+}
+
+/// Reported tone indices for an 80 MHz segment centered at `center`.
+fn segment_80mhz_tones(ng: i16, center: i16) -> Vec<i16> {
+    let half = half_band_tones(ng, 500);
+    half.iter()
+        .copied()
+        .chain(mirror(&half))
+        .map(|t| center + t)
+        .collect()
+}
+
+/// Occupied tone spans of the 20 MHz subchannels in an 80 MHz segment.
+fn segment_80mhz_subchannels(center: i16) -> [ToneSpan; 4] {
+    [
+        (center - 500, center - 259),
+        (center - 253, center - 12),
+        (center + 12, center + 253),
+        (center + 259, center + 500),
+    ]
+}
+
+/// Centers of the 80 MHz segments of a wide bandwidth channel
+fn segment_centers(bandwidth: EhtBandwidth) -> &'static [i16] {
+    match bandwidth {
+        EhtBandwidth::Bw160 => &[-512, 512],
+        EhtBandwidth::Bw320 => &[-1536, -512, 512, 1536],
+        _ => &[0],
+    }
+}
+
+/// Get the full bandwidth set of reported tones and the tone spans of all
+/// 20 MHz subchannels in ascending order.
+///
+/// HE and EHT share the same set of reported tones for bandwidths up to
+/// 160 MHz, see IEEE 802.11ax Table 9-91e.
+///
+/// # Parameters
+/// * `bandwidth` - Channel bandwidth
+/// * `ng` - Subcarrier grouping factor
+///
+/// See IEEE 802.11be Table 9-94p (Ng=4) and Table 9-94q (Ng=16)
+fn eht_tone_plan(
+    bandwidth: EhtBandwidth,
+    ng: i16,
+) -> Result<(Vec<i16>, Vec<ToneSpan>), BfaExtractionError> {
+    let plan = match bandwidth {
+        EhtBandwidth::Bw20 => {
+            // NOTE: 20 MHz additionally reports the edge tones next to DC/guards
+            let half = half_band_tones(ng, 120);
+            let mut tones = vec![-122];
+            tones.extend(&half);
+            tones.extend([-2, 2]);
+            tones.extend(mirror(&half));
+            tones.push(122);
+            (tones, vec![(-122, 122)])
+        }
+        EhtBandwidth::Bw40 => {
+            let half = half_band_tones(ng, 244);
+            let tones = half.iter().copied().chain(mirror(&half)).collect();
+            (tones, vec![(-244, -3), (3, 244)])
+        }
+        EhtBandwidth::Bw80 | EhtBandwidth::Bw160 | EhtBandwidth::Bw320 => {
+            let centers = segment_centers(bandwidth);
+            let tones = centers
+                .iter()
+                .flat_map(|&c| segment_80mhz_tones(ng, c))
+                .collect();
+            let spans = centers
+                .iter()
+                .flat_map(|&c| segment_80mhz_subchannels(c))
+                .collect();
+            (tones, spans)
+        }
+        EhtBandwidth::Reserved => {
+            return Err(BfaExtractionError::InvalidBandwidth {
+                bandwidth: bandwidth as u8,
+            })
+        }
+    };
+
+    Ok(plan)
+}
+
+/// Tone spans of all RU26 of a HE channel in ascending order.
+///
+/// The spans of RU26 at the edge of an 80 MHz segment are widened by one
+/// tone, since the outermost reported tone lies just beyond the RU.
+pub(crate) fn he_ru26_spans(bandwidth: Bandwidth) -> Vec<ToneSpan> {
+    let spans = |starts: &[i16], center: i16| -> Vec<ToneSpan> {
+        let last = starts.len() - 1;
+        starts
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| {
+                // NOTE: The center RU26 (if any) is split by the DC tones
+                let end = if start == -16 { 16 } else { start + 25 };
+                let start = if i == 0 { start - 1 } else { start };
+                let end = if i == last { end + 1 } else { end };
+                (center + start, center + end)
+            })
+            .collect()
+    };
+
+    match bandwidth {
+        Bandwidth::Bw20 => spans(&RU26_STARTS_20MHZ, 0),
+        Bandwidth::Bw40 => spans(&RU26_STARTS_40MHZ, 0),
+        Bandwidth::Bw80 => spans(&RU26_STARTS_80MHZ, 0),
+        Bandwidth::Bw160 => {
+            let mut lower = spans(&RU26_STARTS_80MHZ, -512);
+            lower.extend(spans(&RU26_STARTS_80MHZ, 512));
+            lower
+        }
+    }
+}
+
+/// Merge neighbouring requested subchannels into contiguous tone spans.
+///
+/// # Parameters
+/// * `subchannels` - Tone spans of all 20 MHz subchannels in ascending order
+/// * `requested` - Whether feedback was requested for each of the subchannels
+fn requested_spans(subchannels: &[ToneSpan], requested: impl Fn(usize) -> bool) -> Vec<ToneSpan> {
+    let mut spans: Vec<ToneSpan> = Vec::new();
+    let mut previous_requested = false;
+
+    for (i, &(start, end)) in subchannels.iter().enumerate() {
+        let is_requested = requested(i);
+        match spans.last_mut() {
+            Some(last) if is_requested && previous_requested => last.1 = end,
+            _ if is_requested => spans.push((start, end)),
+            _ => {}
+        }
+        previous_requested = is_requested;
+    }
+
+    spans
+}
+
+/// Get the tone indices for which EHT compressed beamforming feedback is reported.
+///
+/// # Parameters
+/// * `bandwidth` - Channel bandwidth
+/// * `grouping` - Grouping bit of the EHT MIMO Control header (Ng=4 or 16)
+/// * `partial_bw_info` - Requested subchannels of the EHT MIMO Control header
+///
+/// # Returns
+/// * Ascending tone indices of the reported subcarriers
+pub fn eht_subcarrier_indices(
+    bandwidth: EhtBandwidth,
+    grouping: u8,
+    partial_bw_info: PartialBwInfo,
+) -> Result<Vec<i16>, BfaExtractionError> {
+    let (tones, subchannels) = eht_tone_plan(bandwidth, grouping_factor(grouping))?;
+
+    // Every bitmap bit covers one or two 20 MHz subchannels (resolution).
+    let subchannels_per_bit = (partial_bw_info.resolution_mhz() / 20) as usize;
+    let bitmap = partial_bw_info.feedback_bitmap();
+    let spans = requested_spans(&subchannels, |i| {
+        let bit = i / subchannels_per_bit;
+        bit < 8 && (bitmap >> bit) & 1 == 1
+    });
+
+    Ok(tones
+        .into_iter()
+        .filter(|&t| spans.iter().any(|&(start, end)| start <= t && t <= end))
+        .collect())
+}
+
+/// Get the tone indices for which HE compressed beamforming feedback is reported.
+///
+/// For partial bandwidth feedback, only the tones within the RU26 range from
+/// `ru_start_index` to `ru_end_index` are reported.
+///
+/// # Parameters
+/// * `bandwidth` - Channel bandwidth
+/// * `grouping` - Grouping bit of the HE MIMO Control header (Ng=4 or 16)
+/// * `ru_start_index` - First RU26 for which feedback is reported
+/// * `ru_end_index` - Last RU26 for which feedback is reported
+///
+/// # Returns
+/// * Ascending tone indices of the reported subcarriers
+pub fn he_subcarrier_indices(
+    bandwidth: Bandwidth,
+    grouping: u8,
+    ru_start_index: u8,
+    ru_end_index: u8,
+) -> Result<Vec<i16>, BfaExtractionError> {
+    let eht_bandwidth = match bandwidth {
+        Bandwidth::Bw20 => EhtBandwidth::Bw20,
+        Bandwidth::Bw40 => EhtBandwidth::Bw40,
+        Bandwidth::Bw80 => EhtBandwidth::Bw80,
+        Bandwidth::Bw160 => EhtBandwidth::Bw160,
+    };
+    let (tones, _) = eht_tone_plan(eht_bandwidth, grouping_factor(grouping))?;
+
+    let rus = he_ru26_spans(bandwidth);
+    let (start, end) = (ru_start_index as usize, ru_end_index as usize);
+    if start > end || end >= rus.len() {
+        return Err(BfaExtractionError::InvalidRuRange {
+            start: ru_start_index,
+            end: ru_end_index,
+        });
+    }
+
+    let (first_tone, last_tone) = (rus[start].0, rus[end].1);
+    Ok(tones
+        .into_iter()
+        .filter(|&t| first_tone <= t && t <= last_tone)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bilge::prelude::*;
+
+    fn full_bw(resolution: u8) -> PartialBwInfo {
+        PartialBwInfo::new(u1::new(resolution), 0xff)
+    }
+
+    #[test]
+    fn eht_full_bandwidth_ng4() {
+        let count = |bw, res| eht_subcarrier_indices(bw, 0, full_bw(res)).unwrap().len();
+        assert_eq!(count(EhtBandwidth::Bw20, 0), 64);
+        assert_eq!(count(EhtBandwidth::Bw40, 0), 122);
+        assert_eq!(count(EhtBandwidth::Bw80, 0), 250);
+        assert_eq!(count(EhtBandwidth::Bw160, 0), 500);
+        assert_eq!(count(EhtBandwidth::Bw320, 1), 1000);
+    }
+
+    #[test]
+    fn eht_full_bandwidth_ng16() {
+        let count = |bw, res| eht_subcarrier_indices(bw, 1, full_bw(res)).unwrap().len();
+        assert_eq!(count(EhtBandwidth::Bw20, 0), 20);
+        assert_eq!(count(EhtBandwidth::Bw40, 0), 32);
+        assert_eq!(count(EhtBandwidth::Bw80, 0), 64);
+        assert_eq!(count(EhtBandwidth::Bw160, 0), 128);
+        assert_eq!(count(EhtBandwidth::Bw320, 1), 256);
+    }
+
+    #[test]
+    fn eht_tones_20mhz() {
+        let tones = eht_subcarrier_indices(EhtBandwidth::Bw20, 1, full_bw(0)).unwrap();
+        let expected = vec![
+            -122, -116, -100, -84, -68, -52, -36, -20, -4, -2, 2, 4, 20, 36, 52, 68, 84, 100, 116,
+            122,
+        ];
+        assert_eq!(tones, expected);
+    }
+
+    #[test]
+    fn eht_tones_160mhz_are_ascending() {
+        let tones = eht_subcarrier_indices(EhtBandwidth::Bw160, 0, full_bw(0)).unwrap();
+        assert_eq!(tones.first(), Some(&-1012));
+        assert_eq!(tones.last(), Some(&1012));
+        assert!(tones.windows(2).all(|w| w[0] < w[1]));
+        assert!(!tones.contains(&-512) && !tones.contains(&0));
+    }
+
+    #[test]
+    fn eht_partial_bandwidth() {
+        // Only lowest 20 MHz subchannel of an 80 MHz channel
+        let lowest = PartialBwInfo::new(u1::new(0), 0b0001);
+        let tones = eht_subcarrier_indices(EhtBandwidth::Bw80, 0, lowest).unwrap();
+        assert_eq!(tones.len(), 61);
+        assert_eq!((tones[0], tones[60]), (-500, -260));
+
+        // Two center subchannels are merged into a single span across DC
+        let center = PartialBwInfo::new(u1::new(0), 0b0110);
+        let tones = eht_subcarrier_indices(EhtBandwidth::Bw80, 0, center).unwrap();
+        assert_eq!(tones.len(), 126);
+        assert_eq!((tones[0], tones[125]), (-252, 252));
+
+        // 40 MHz resolution in 320 MHz: one bit covers two subchannels
+        let first_40 = PartialBwInfo::new(u1::new(1), 0b0000_0001);
+        let tones = eht_subcarrier_indices(EhtBandwidth::Bw320, 0, first_40).unwrap();
+        assert_eq!((tones[0], *tones.last().unwrap()), (-2036, -1548));
+    }
+
+    #[test]
+    fn eht_reserved_bandwidth() {
+        let result = eht_subcarrier_indices(EhtBandwidth::Reserved, 0, full_bw(0));
+        assert!(matches!(
+            result,
+            Err(BfaExtractionError::InvalidBandwidth { .. })
+        ));
+    }
+
+    #[test]
+    fn he_full_bandwidth() {
+        let count = |bw, ng, end| he_subcarrier_indices(bw, ng, 0, end).unwrap().len();
+        assert_eq!(count(Bandwidth::Bw20, 0, 8), 64);
+        assert_eq!(count(Bandwidth::Bw40, 0, 17), 122);
+        assert_eq!(count(Bandwidth::Bw80, 0, 36), 250);
+        assert_eq!(count(Bandwidth::Bw160, 0, 73), 500);
+        assert_eq!(count(Bandwidth::Bw20, 1, 8), 20);
+        assert_eq!(count(Bandwidth::Bw40, 1, 17), 32);
+        assert_eq!(count(Bandwidth::Bw80, 1, 36), 64);
+        assert_eq!(count(Bandwidth::Bw160, 1, 73), 128);
+    }
+
+    #[test]
+    fn he_partial_bandwidth() {
+        // Lowest 106-tone RU (RU26 0-3) of a 20 MHz channel
+        let tones = he_subcarrier_indices(Bandwidth::Bw20, 0, 0, 3).unwrap();
+        assert_eq!((tones[0], *tones.last().unwrap()), (-122, -20));
+        assert_eq!(tones.len(), 27);
+
+        // Center RU26 of an 80 MHz channel spans the DC tones
+        let tones = he_subcarrier_indices(Bandwidth::Bw80, 0, 18, 18).unwrap();
+        assert_eq!(tones, vec![-16, -12, -8, -4, 4, 8, 12, 16]);
+
+        // First RU26 of the upper 80 MHz segment of a 160 MHz channel
+        let tones = he_subcarrier_indices(Bandwidth::Bw160, 0, 37, 37).unwrap();
+        assert_eq!(tones, vec![12, 16, 20, 24, 28, 32, 36]);
+    }
+
+    #[test]
+    fn he_invalid_ru_range() {
+        let result = he_subcarrier_indices(Bandwidth::Bw20, 0, 0, 9);
+        assert!(matches!(
+            result,
+            Err(BfaExtractionError::InvalidRuRange { start: 0, end: 9 })
+        ));
+        let result = he_subcarrier_indices(Bandwidth::Bw40, 0, 5, 4);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn he_tones_skip_dc_and_guards() {
+        let tones = he_subcarrier_indices(Bandwidth::Bw40, 1, 0, 17).unwrap();
+        assert_eq!((tones[0], *tones.last().unwrap()), (-244, 244));
+        assert!(tones.iter().all(|t| t.abs() >= 3));
+
+        let tones = he_subcarrier_indices(Bandwidth::Bw80, 1, 0, 36).unwrap();
+        assert_eq!((tones[31], tones[32]), (-4, 4));
+    }
+}
@@ -0,0 +1,271 @@
+//! HE Trigger frames of type Beamforming Report Poll (BFRP)
+//!
+//! In MU sounding, the beamformer collects the feedback of the stations listed
+//! in the NDP Announcement with BFRP trigger frames. Each User Info field of
+//! the trigger addresses a station, allocates the RU it sends its report on
+//! and lists the feedback segments to (re)transmit.
+//!
+//! See IEEE 802.11ax-2021 9.3.1.22 for the frame format and 9.3.1.22.4 for
+//! the BFRP variant.
+use bilge::prelude::*;
+use pcap::Packet;
+
+use crate::errors::{BfaExtractionError, NotApplicableReason};
+use crate::fcs::FcsPolicy;
+use crate::he_mimo_ctrl::Bandwidth;
+use crate::link_layer::LinkType;
+use crate::mac_header::control_frame_transmitter;
+use crate::pcap::CapturedFrame;
+
+/// Frame control (first byte) of Trigger frames (control frame, subtype 2)
+const FRAME_CONTROL_TRIGGER: u8 = 0x24;
+/// Offset of the Common Info field (after frame control, duration, RA, TA)
+const COMMON_INFO_OFFSET: usize = 16;
+/// Offset of the first User Info field
+const USER_INFO_OFFSET: usize = COMMON_INFO_OFFSET + 8;
+/// Length of a BFRP User Info field (including the Trigger Dependent User Info)
+const BFRP_USER_INFO_LENGTH: usize = 6;
+/// Trigger Type of Beamforming Report Poll triggers
+const TRIGGER_TYPE_BFRP: u8 = 1;
+/// AID12 marking the start of the padding after the User Info List
+const AID12_PADDING: u16 = 4095;
+
+/// Common Info field of HE trigger frames
+#[bitsize(64)]
+#[derive(FromBits, DebugBits)]
+struct CommonInfo {
+    trigger_type: u4,        // Type of the trigger (1=BFRP)
+    ul_length: u12,          // L-SIG length of the solicited TB PPDU
+    more_tf: u1,             // Whether another trigger follows in the TXOP
+    cs_required: u1,         // Whether carrier sensing is required
+    ul_bandwidth: Bandwidth, // Bandwidth of the solicited TB PPDU
+    reserved: u44,           // Remaining subfields (not of interest here)
+}
+
+/// User Info field of HE trigger frames
+#[bitsize(40)]
+#[derive(FromBits, DebugBits)]
+struct UserInfoField {
+    aid12: u12,             // AID12 of the addressed station
+    ru_allocation: u8,      // RU to send the report on
+    ul_fec_coding_type: u1, // BCC or LDPC
+    ul_mcs: u4,             // HE-MCS of the solicited TB PPDU
+    ul_dcm: u1,             // Dual carrier modulation
+    ss_allocation: u6,      // Spatial stream allocation
+    ul_target_rssi: u7,     // Expected receive signal power
+    reserved: u1,           // Reserved
+}
+
+/// Feedback polled from a single station
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerUserInfo {
+    /// AID12 of the polled station
+    pub aid: u16,
+    /// RU Allocation subfield; B0 selects the 80 MHz segment, B1-B7 the RU
+    pub ru_allocation: u8,
+    /// Bitmap of the feedback segments requested (bit n for segment n)
+    pub feedback_segment_retransmission_bitmap: u8,
+}
+
+impl TriggerUserInfo {
+    /// Extract a BFRP User Info field from the bytestream (requires first 6 bytes.)
+    fn from_buf(buf: &[u8]) -> Self {
+        let field = UserInfoField::from(UInt::<u64, 40>::new(
+            (buf[0] as u64)
+                | ((buf[1] as u64) << 8)
+                | ((buf[2] as u64) << 16)
+                | ((buf[3] as u64) << 24)
+                | ((buf[4] as u64) << 32),
+        ));
+        Self {
+            aid: field.aid12().value(),
+            ru_allocation: field.ru_allocation(),
+            feedback_segment_retransmission_bitmap: buf[5],
+        }
+    }
+}
+
+/// Beamforming Report Poll trigger collecting MU feedback
+#[derive(Debug, Clone, PartialEq)]
+pub struct BfrpTrigger {
+    /// Timestamp of the pcap capture
+    pub timestamp: f64,
+    /// Address of the beamformer polling the feedback
+    pub beamformer: [u8; 6],
+    /// Bandwidth of the PPDU carrying the feedback
+    pub ul_bandwidth: Bandwidth,
+    /// Feedback polled from each station
+    pub user_info: Vec<TriggerUserInfo>,
+}
+
+/// Whether a frame is a Trigger frame
+///
+/// # Parameters
+/// * `frame_control` - First byte of the frame control field
+pub(crate) fn is_trigger(frame_control: u8) -> bool {
+    frame_control == FRAME_CONTROL_TRIGGER
+}
+
+/// Parse a captured BFRP Trigger frame
+///
+/// Triggers with an invalid FCS are rejected if the FCS is verified.
+///
+/// # Parameters
+/// * `captured` - The captured frame
+/// * `fcs_policy` - How to treat the FCS of the frame
+pub(crate) fn parse_bfrp_trigger(
+    captured: &CapturedFrame,
+    fcs_policy: FcsPolicy,
+) -> Result<BfrpTrigger, BfaExtractionError> {
+    let (frame, fcs_valid) = captured.strip_fcs(USER_INFO_OFFSET, fcs_policy)?;
+    if fcs_valid == Some(false) {
+        return Err(BfaExtractionError::InvalidFcs);
+    }
+
+    let common_info = CommonInfo::from(u64::from_le_bytes(
+        frame[COMMON_INFO_OFFSET..USER_INFO_OFFSET]
+            .try_into()
+            .expect("Common Info has 8 bytes"),
+    ));
+    let trigger_type = common_info.trigger_type().value();
+    if trigger_type != TRIGGER_TYPE_BFRP {
+        return Err(NotApplicableReason::TriggerType { trigger_type }.into());
+    }
+
+    let mut user_info = Vec::new();
+    let mut offset = USER_INFO_OFFSET;
+    while offset < frame.len() {
+        // NOTE: The User Info List is optionally followed by padding
+        if let Some(&[low, high]) = frame.get(offset..offset + 2) {
+            if u16::from_le_bytes([low, high]) & 0x0fff == AID12_PADDING {
+                break;
+            }
+        }
+        let field = frame.get(offset..offset + BFRP_USER_INFO_LENGTH).ok_or(
+            BfaExtractionError::TruncatedFrame {
+                required: offset + BFRP_USER_INFO_LENGTH,
+                available: frame.len(),
+            },
+        )?;
+        user_info.push(TriggerUserInfo::from_buf(field));
+        offset += BFRP_USER_INFO_LENGTH;
+    }
+
+    Ok(BfrpTrigger {
+        timestamp: captured.timestamp,
+        beamformer: control_frame_transmitter(frame),
+        ul_bandwidth: common_info.ul_bandwidth(),
+        user_info,
+    })
+}
+
+/// Extract a BFRP trigger from a single WiFi packet captured with pcap
+///
+/// Returns `None` if the packet is no Trigger frame at all.
+///
+/// # Parameters
+/// * `packet` - The captured packet
+/// * `link_type` - Link type of the capture the packet stems from
+/// * `fcs_policy` - How to treat the FCS of the frame
+pub fn extract_trigger_from_packet(
+    packet: &Packet,
+    link_type: LinkType,
+    fcs_policy: FcsPolicy,
+) -> Result<Option<BfrpTrigger>, BfaExtractionError> {
+    let captured = CapturedFrame::from_packet(packet, link_type)?;
+    if !is_trigger(captured.frame_control()) {
+        return Ok(None);
+    }
+    parse_bfrp_trigger(&captured, fcs_policy).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::link_layer::LinkHeader;
+    use crate::mac_header::GROUP_BIT;
+
+    const BEAMFORMER: [u8; 6] = [0xc8, 0x7f, 0x54, 0x3c, 0x27, 0x54];
+
+    /// Create a captured trigger (without FCS) with the given common and user infos
+    fn trigger_frame(common_info: u64, user_info: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0x24, 0x00, 0x00, 0x00];
+        frame.extend_from_slice(&[0xff; 6]); // RA (broadcast)
+        frame.extend_from_slice(&BEAMFORMER);
+        frame[10] |= GROUP_BIT; // bandwidth signaling TA
+        frame.extend_from_slice(&common_info.to_le_bytes());
+        frame.extend_from_slice(user_info);
+        frame
+    }
+
+    fn captured(frame: &[u8]) -> CapturedFrame<'_> {
+        CapturedFrame {
+            timestamp: 1.0,
+            link_header: LinkHeader {
+                length: 0,
+                metadata: Default::default(),
+                fcs_present: Some(false),
+            },
+            frame,
+        }
+    }
+
+    /// Create a BFRP User Info field
+    fn user_info(aid: u16, ru_allocation: u8, bitmap: u8) -> [u8; 6] {
+        let field = aid as u64 | (ru_allocation as u64) << 12;
+        let bytes = field.to_le_bytes();
+        [bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bitmap]
+    }
+
+    #[test]
+    fn bfrp_trigger_parsing() {
+        // BFRP, 80 MHz
+        let common_info = TRIGGER_TYPE_BFRP as u64 | (2 << 18);
+        let mut users = user_info(1, 61 << 1, 0xff).to_vec();
+        users.extend_from_slice(&user_info(2, 62 << 1, 0b0000_0010));
+        users.extend_from_slice(&[0xff, 0xff]); // padding
+        let frame = trigger_frame(common_info, &users);
+
+        let trigger = parse_bfrp_trigger(&captured(&frame), FcsPolicy::Ignore).unwrap();
+        assert_eq!(trigger.beamformer, BEAMFORMER);
+        assert_eq!(trigger.ul_bandwidth, Bandwidth::Bw80);
+        assert_eq!(
+            trigger.user_info,
+            vec![
+                TriggerUserInfo {
+                    aid: 1,
+                    ru_allocation: 61 << 1,
+                    feedback_segment_retransmission_bitmap: 0xff,
+                },
+                TriggerUserInfo {
+                    aid: 2,
+                    ru_allocation: 62 << 1,
+                    feedback_segment_retransmission_bitmap: 0b0000_0010,
+                },
+            ]
+        );
+
+        // Truncated User Info (without padding)
+        let frame = trigger_frame(common_info, &users[..10]);
+        assert!(matches!(
+            parse_bfrp_trigger(&captured(&frame), FcsPolicy::Ignore),
+            Err(BfaExtractionError::TruncatedFrame {
+                required: 36,
+                available: 34
+            })
+        ));
+    }
+
+    #[test]
+    fn basic_trigger_is_not_applicable() {
+        let frame = trigger_frame(0, &user_info(1, 0, 0));
+        assert!(matches!(
+            parse_bfrp_trigger(&captured(&frame), FcsPolicy::Ignore),
+            Err(BfaExtractionError::NotApplicable(
+                NotApplicableReason::TriggerType { trigger_type: 0 }
+            ))
+        ));
+        assert!(is_trigger(frame[0]));
+        assert!(!is_trigger(0x54));
+    }
+}
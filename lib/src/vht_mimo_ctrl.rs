@@ -0,0 +1,68 @@
+//! Very High Throughput (VHT) MIMO Control header
+//!
+//! This module defines types and handles extraction of the VHT MIMO Control
+//! header from the bytestream of a captured WiFi packet.
+use crate::he_mimo_ctrl::Bandwidth;
+use bilge::prelude::*;
+
+/// VHT Mimo Control header
+#[bitsize(24)]
+#[derive(FromBits, DebugBits)]
+pub struct VhtMimoControl {
+    pub nc_index: u3,                    // Index for number of "columns" (streams)
+    pub nr_index: u3,                    // Index for number of receive antennas
+    pub bandwidth: Bandwidth,            // channel bandwidth
+    pub grouping: u2,                    // Subcarrier grouping (Ng=1, 2 or 4; 3 is reserved)
+    pub codebook_info: u1,               // Codebook size (depends on feedback type)
+    pub feedback_type: u1,               // Feedback type (0=Single User, 1=Multi User)
+    pub remaining_feedback_segments: u3, // Indicate number of remaining feedback segments
+    pub first_feedback_segments: u1,     // Whether this is the first (or only) feedback segment
+    pub reserved_padding: u2,            // Reserved padding
+    pub dialog_token_number: u6,         // To identify VHT NDP announcement frame
+}
+
+impl VhtMimoControl {
+    /// Extract VhtMimoControl header from the packet bytestream (requires first 3 bytes.)
+    pub fn from_buf(buf: &[u8]) -> Self {
+        let value: UInt<u32, 24> = UInt::<u32, 24>::new(
+            (buf[0] as u32) | ((buf[1] as u32) << 8) | ((buf[2] as u32) << 16),
+        );
+        VhtMimoControl::from(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vht_mimo_ctrl_extraction() {
+        // 1010 1000 1000 0100 1001 1001 = 0xa88499
+        // VHT MIMO Control:
+        // .... .... .... .... .... .001 = Nc Index: 2 Columns (1)
+        // .... .... .... .... ..01 1... = Nr Index: 4 Rows (3)
+        // .... .... .... .... 10.. .... = Channel Width: 80 MHz (2)
+        // .... .... .... ..00 .... .... = Grouping: No Grouping (0)
+        // .... .... .... .1.. .... .... = Codebook Information: 1
+        // .... .... .... 0... .... .... = Feedback Type: SU (0)
+        // .... .... .000 .... .... .... = Remaining Feedback Segments: 0
+        // .... .... 1... .... .... .... = First Feedback Segment: 1
+        // .... ..00 .... .... .... .... = Reserved: 0x0
+        // 1010 10.. .... .... .... .... = Sounding Dialog Token Number: 42
+
+        // bytestream (little endian)
+        let byte_stream: &[u8] = &[0b10011001, 0b10000100, 0b10101000];
+
+        let result = VhtMimoControl::from_buf(byte_stream);
+        assert_eq!(result.nc_index(), UInt::<u8, 3>::new(1));
+        assert_eq!(result.nr_index(), UInt::<u8, 3>::new(3));
+        assert_eq!(result.bandwidth(), Bandwidth::Bw80);
+        assert_eq!(result.grouping(), UInt::<u8, 2>::new(0));
+        assert_eq!(result.codebook_info(), UInt::<u8, 1>::new(1));
+        assert_eq!(result.feedback_type(), UInt::<u8, 1>::new(0));
+        assert_eq!(result.remaining_feedback_segments(), UInt::<u8, 3>::new(0));
+        assert_eq!(result.first_feedback_segments(), UInt::<u8, 1>::new(1));
+        assert_eq!(result.reserved_padding(), UInt::<u8, 2>::new(0));
+        assert_eq!(result.dialog_token_number(), UInt::<u8, 6>::new(42));
+    }
+}
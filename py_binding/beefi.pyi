@@ -7,18 +7,136 @@ class PyBfiMeta:
     BFI metadata structure.
 
     Attributes:
+        standard (int): WiFi standard of the feedback (0=HT, 1=VHT, 2=HE, 3=EHT).
         bandwidth (int): Channel bandwidth.
         nr_index (int): Index of the receive antennas used in the sounding procedure.
         nc_index (int): Index of columns (streams) used in the sounding procedure.
         codebook_info (int): Codebook size.
         feedback_type (int): Feedback type (e.g., SU, MU, CQI).
+        partial_bw_info (Optional[int]): EHT partial bandwidth info (None for non-EHT feedback).
+        ru_start_index (Optional[int]): First RU26 of HE partial bandwidth feedback (None for non-HE feedback).
+        ru_end_index (Optional[int]): Last RU26 of HE partial bandwidth feedback (None for non-HE feedback).
     """
 
+    standard: int
     bandwidth: int
     nr_index: int
     nc_index: int
     codebook_info: int
     feedback_type: int
+    partial_bw_info: Optional[int]
+    ru_start_index: Optional[int]
+    ru_end_index: Optional[int]
+
+class PyMacHeader:
+    """
+    Addressing and sequence information of the feedback frame.
+
+    Attributes:
+        receiver (List[int]): Receiver address (the beamformer).
+        transmitter (List[int]): Transmitter address (the beamformee sending the feedback).
+        bssid (List[int]): BSSID.
+        sequence_number (int): Sequence number of the frame.
+        fragment_number (int): Fragment number of the frame.
+        retry (bool): Whether the frame is a retransmission.
+    """
+
+    receiver: List[int]
+    transmitter: List[int]
+    bssid: List[int]
+    sequence_number: int
+    fragment_number: int
+    retry: bool
+
+class PyRadioMeta:
+    """
+    Reception information from the link layer header.
+
+    Attributes:
+        tsft (Optional[int]): TSF timer value when the first bit of the frame arrived (in µs).
+        antenna_signal (Optional[int]): Combined RF signal power at the antennas (in dBm).
+        antenna_noise (Optional[int]): RF noise power at the antennas (in dBm).
+        channel_freq (Optional[int]): Channel center frequency (in MHz).
+        channel_flags (Optional[int]): Channel flags (band, modulation).
+        antenna_signals (List[int]): RF signal power at the individual antennas (in dBm).
+    """
+
+    tsft: Optional[int]
+    antenna_signal: Optional[int]
+    antenna_noise: Optional[int]
+    channel_freq: Optional[int]
+    channel_flags: Optional[int]
+    antenna_signals: List[int]
+
+class PyStaInfo:
+    """
+    Per-station information of an NDP Announcement.
+
+    Attributes:
+        aid (int): Association ID (AID12 for VHT, AID11 for HE) of the addressed station.
+        feedback_type (int): Requested feedback type (0=SU, 1=MU, 2=CQI).
+        grouping (Optional[int]): Requested subcarrier grouping Ng (None for VHT).
+        codebook_info (Optional[int]): Requested codebook size (None for VHT).
+        nc_index (int): Requested number of columns minus one.
+        ru_start_index (Optional[int]): First RU26 of the requested partial bandwidth (None for VHT).
+        ru_end_index (Optional[int]): Last RU26 of the requested partial bandwidth (None for VHT).
+    """
+
+    aid: int
+    feedback_type: int
+    grouping: Optional[int]
+    codebook_info: Optional[int]
+    nc_index: int
+    ru_start_index: Optional[int]
+    ru_end_index: Optional[int]
+
+class PyNdpa:
+    """
+    NDP Announcement that solicited a feedback report.
+
+    Attributes:
+        timestamp (float): Timestamp of the pcap capture of the announcement.
+        beamformer (List[int]): Address of the beamformer sending the announcement.
+        dialog_token (int): Sounding dialog token number.
+        standard (int): WiFi standard of the announcement (1=VHT, 2=HE).
+        sta_info (List[PyStaInfo]): Information on every addressed station.
+    """
+
+    timestamp: float
+    beamformer: List[int]
+    dialog_token: int
+    standard: int
+    sta_info: List[PyStaInfo]
+
+class PyTriggerUserInfo:
+    """
+    Feedback polled from a single station in a BFRP trigger.
+
+    Attributes:
+        aid (int): AID12 of the polled station.
+        ru_allocation (int): RU Allocation subfield (B0 selects the 80 MHz segment, B1-B7 the RU).
+        feedback_segment_retransmission_bitmap (int): Bitmap of the requested feedback segments (bit n for segment n).
+    """
+
+    aid: int
+    ru_allocation: int
+    feedback_segment_retransmission_bitmap: int
+
+class PyBfrpTrigger:
+    """
+    Beamforming Report Poll trigger collecting MU feedback.
+
+    Attributes:
+        timestamp (float): Timestamp of the pcap capture of the trigger.
+        beamformer (List[int]): Address of the beamformer polling the feedback.
+        ul_bandwidth (int): Bandwidth of the PPDU carrying the feedback (in MHz).
+        user_info (List[PyTriggerUserInfo]): Feedback polled from each station.
+    """
+
+    timestamp: float
+    beamformer: List[int]
+    ul_bandwidth: int
+    user_info: List[PyTriggerUserInfo]
 
 class PyBfmData:
     """
@@ -26,32 +144,86 @@ class PyBfmData:
 
     Attributes:
         metadata (PyBfiMeta): Metadata associated with the BFI data.
+        radio (PyRadioMeta): Reception information of the packet.
         timestamp (float): Timestamp of the associated pcap capture.
+        mac_header (PyMacHeader): MAC header of the feedback frame.
+        fcs_valid (Optional[bool]): Result of the FCS verification (None if not verified).
+        ndpa (Optional[PyNdpa]): NDP Announcement that solicited the report (None if not captured).
         token_number (int): Token number identifying the NDP packet used in the procedure.
+        snr (ndarray): 1D array of the average SNR (in dB) of each space-time stream.
+        delta_snr (Optional[ndarray]): 2D array of delta SNR (in dB) per stream and subcarrier (MU feedback only).
+        subcarrier_indices (Optional[ndarray]): 1D array of the tone index of every subcarrier (HE/EHT only).
         bfa_angles (ndarray): 2D array of extracted BFA angles from the compressed beamforming feedback information.
     """
 
     metadata: PyBfiMeta
+    radio: PyRadioMeta
     timestamp: float
+    mac_header: PyMacHeader
+    fcs_valid: Optional[bool]
+    ndpa: Optional[PyNdpa]
     token_number: int
+    snr: ndarray  # 1D array of shape (num_spatial_streams,)
+    delta_snr: Optional[ndarray]  # 2D array of shape (num_spatial_streams, subcarriers)
+    subcarrier_indices: Optional[ndarray]  # 1D array of shape (subcarriers,)
     bfm: (
-        ndarray  # 3D array of shape (num_rx_antennas, num_spatial_streams, subcarriers)
+        ndarray  # 3D complex128 (complex64 with single_precision) array of shape (num_rx_antennas, num_spatial_streams, subcarriers)
     )
 
+class PyCqiData:
+    """
+    CQI data (SNR per RU) extracted from a single packet.
+
+    Attributes:
+        metadata (PyBfiMeta): Metadata associated with the BFI data.
+        radio (PyRadioMeta): Reception information of the packet.
+        timestamp (float): Timestamp of the associated pcap capture.
+        mac_header (PyMacHeader): MAC header of the feedback frame.
+        fcs_valid (Optional[bool]): Result of the FCS verification (None if not verified).
+        ndpa (Optional[PyNdpa]): NDP Announcement that solicited the report (None if not captured).
+        token_number (int): Token number identifying the NDP packet used in the procedure.
+        ru_indices (ndarray): 1D array of the index of every reported RU26.
+        snr (ndarray): 2D array of the average SNR (in dB) per stream and RU.
+    """
+
+    metadata: PyBfiMeta
+    radio: PyRadioMeta
+    timestamp: float
+    mac_header: PyMacHeader
+    fcs_valid: Optional[bool]
+    ndpa: Optional[PyNdpa]
+    token_number: int
+    ru_indices: ndarray  # 1D array of shape (num_ru,)
+    snr: ndarray  # 2D array of shape (num_spatial_streams, num_ru)
+
 class PyBfmBatch:
     """
     BFM data extracted from a single packet.
 
     Attributes:
         metadata (PyBfiMeta): Metadata associated with the BFI data.
+        radio (List[PyRadioMeta]): Reception information of each packet.
         timestamp (float): Timestamp of the associated pcap capture.
+        mac_header (List[PyMacHeader]): MAC headers of each packet.
+        fcs_valid (List[Optional[bool]]): FCS verification results of each packet.
+        ndpa (List[Optional[PyNdpa]]): Soliciting NDP Announcement of each packet.
         token_number (int): Token number identifying the NDP packet used in the procedure.
+        snr (List[List[float]]): Average SNR (in dB) of each space-time stream per packet.
+        delta_snr (List[Optional[ndarray]]): Delta SNR (in dB) per stream and subcarrier per packet (MU feedback only).
+        subcarrier_indices (List[Optional[ndarray]]): Tone index of every subcarrier per packet (HE/EHT only).
         bfa_angles (ndarray): 2D array of extracted BFA angles from the compressed beamforming feedback information.
     """
 
     metadata: List[PyBfiMeta]
+    radio: List[PyRadioMeta]
     timestamps: ndarray  # 1D array of shape (num_packets,)
+    mac_header: List[PyMacHeader]
+    fcs_valid: List[Optional[bool]]
+    ndpa: List[Optional[PyNdpa]]
     token_numbers: ndarray  # 1D array of shape (num_packets,)
+    snr: List[List[float]]  # per packet list of shape (num_spatial_streams,)
+    delta_snr: List[Optional[ndarray]]  # per packet array of shape (num_spatial_streams, subcarriers)
+    subcarrier_indices: List[Optional[ndarray]]  # per packet array of shape (subcarriers,)
     bfa_angles: ndarray  # 4D array of shape (num_packets, num_rx_antenna, num_spatial_streams, subcarriers)
 
 class PyBfaData:
@@ -60,14 +232,28 @@ class PyBfaData:
 
     Attributes:
         metadata (PyBfiMeta): Metadata associated with the BFI data.
+        radio (PyRadioMeta): Reception information of the packet.
         timestamp (float): Timestamp of the associated pcap capture.
+        mac_header (PyMacHeader): MAC header of the feedback frame.
+        fcs_valid (Optional[bool]): Result of the FCS verification (None if not verified).
+        ndpa (Optional[PyNdpa]): NDP Announcement that solicited the report (None if not captured).
         token_number (int): Token number identifying the NDP packet used in the procedure.
+        snr (ndarray): 1D array of the average SNR (in dB) of each space-time stream.
+        delta_snr (Optional[ndarray]): 2D array of delta SNR (in dB) per stream and subcarrier (MU feedback only).
+        subcarrier_indices (Optional[ndarray]): 1D array of the tone index of every subcarrier (HE/EHT only).
         bfa_angles (ndarray): 2D array of extracted BFA angles from the compressed beamforming feedback information.
     """
 
     metadata: PyBfiMeta
+    radio: PyRadioMeta
     timestamp: float
+    mac_header: PyMacHeader
+    fcs_valid: Optional[bool]
+    ndpa: Optional[PyNdpa]
     token_number: int
+    snr: ndarray  # 1D array of shape (num_spatial_streams,)
+    delta_snr: Optional[ndarray]  # 2D array of shape (num_spatial_streams, subcarriers)
+    subcarrier_indices: Optional[ndarray]  # 1D array of shape (subcarriers,)
     bfa_angles: ndarray  # 2D array of shape (subcarriers, angles)
 
 class PyBfaBatch:
@@ -76,14 +262,28 @@ class PyBfaBatch:
 
     Attributes:
         metadata (List[PyBfiMeta]): List of metadata of shape (num_packets,).
+        radio (List[PyRadioMeta]): List of reception information of shape (num_packets,).
         timestamps (ndarray): 1D array of timestamps of shape (num_packets,).
+        mac_header (List[PyMacHeader]): List of MAC headers of shape (num_packets,).
+        fcs_valid (List[Optional[bool]]): List of FCS verification results of shape (num_packets,).
+        ndpa (List[Optional[PyNdpa]]): Soliciting NDP Announcement of each packet.
         token_numbers (ndarray): 1D array of token numbers of shape (num_packets,).
+        snr (List[List[float]]): Average SNR (in dB) of each space-time stream per packet.
+        delta_snr (List[Optional[ndarray]]): Delta SNR (in dB) per stream and subcarrier per packet (MU feedback only).
+        subcarrier_indices (List[Optional[ndarray]]): Tone index of every subcarrier per packet (HE/EHT only).
         bfa_angles (ndarray): 3D array of BFA angles with shape (num_packets, subcarriers, angles).
     """
 
     metadata: List[PyBfiMeta]
+    radio: List[PyRadioMeta]
     timestamps: ndarray  # 1D array of shape (num_packets,)
+    mac_header: List[PyMacHeader]
+    fcs_valid: List[Optional[bool]]
+    ndpa: List[Optional[PyNdpa]]
     token_numbers: ndarray  # 1D array of shape (num_packets,)
+    snr: List[List[float]]  # per packet list of shape (num_spatial_streams,)
+    delta_snr: List[Optional[ndarray]]  # per packet array of shape (num_spatial_streams, subcarriers)
+    subcarrier_indices: List[Optional[ndarray]]  # per packet array of shape (subcarriers,)
     bfa_angles: ndarray  # 3D array of shape (num_packets, subcarriers, angles)
 
 class DataSource:
@@ -121,6 +321,8 @@ class Bee:
     Methods:
         __init__: Initialize a streaming bee for packet capture.
         poll: Polls for new BFI data, returning it if available.
+        poll_trigger: Polls for new BFRP triggers, returning one if available.
+        poll_cqi: Polls for new CQI reports, returning one if available.
         stop: Stops the capture process.
     """
 
@@ -131,6 +333,9 @@ class Bee:
         pcap_buffer: bool = False,
         pcap_snaplen: int = 4096,
         pcap_bufsize: int = 1_000_000,
+        fcs_policy: str = "ignore",
+        triggers: bool = False,
+        cqi: bool = False,
     ) -> None:
         """
         Initializes a new streaming Bee.
@@ -141,6 +346,9 @@ class Bee:
             pcap_buffer (bool): Whether pcap should buffer packets before processing. Default is off (immediate processing).
             pcap_snaplen (int): Internal pcap snapshot length (defaults to 4k=4096)
             pcap_bufsize (int): Internal pcap buffer size to store snapshots (defaults to 1_000_000)
+            fcs_policy (str): How to treat the FCS of frames: "ignore", "flag" (set `fcs_valid`) or "drop" invalid frames. Defaults to "ignore".
+            triggers (bool): Whether to also collect BFRP triggers of MU sounding sessions. Defaults to False.
+            cqi (bool): Whether to also collect CQI reports. Defaults to False.
         """
         ...
 
@@ -153,43 +361,94 @@ class Bee:
         """
         ...
 
+    def poll_trigger(self) -> Optional[PyBfrpTrigger]:
+        """
+        Polls the queue for new BFRP triggers. Non-blocking; returns None if no trigger is available.
+
+        Raises:
+            RuntimeError: If the bee was created without `triggers=True`.
+
+        Returns:
+            Optional[PyBfrpTrigger]: BFRP trigger if available, or None if the queue is empty.
+        """
+        ...
+
+    def poll_cqi(self) -> Optional[PyCqiData]:
+        """
+        Polls the queue for new CQI reports. Non-blocking; returns None if no report is available.
+
+        Raises:
+            RuntimeError: If the bee was created without `cqi=True`.
+
+        Returns:
+            Optional[PyCqiData]: CQI data if available, or None if the queue is empty.
+        """
+        ...
+
     def stop(self) -> None:
         """
         Stops the capture process, exiting background threads and wrapping up file usage.
         """
         ...
 
-def extract_from_pcap(path: str) -> PyBfaBatch:
+def extract_from_pcap(path: str, fcs_policy: str = "ignore") -> PyBfaBatch:
     """
     Extract all BFA data from a pcap file in a single batch. Pads BFA angles as needed.
 
     Args:
         path (str): Path to the pcap file to extract data from.
+        fcs_policy (str): How to treat the FCS of frames: "ignore", "flag" (set `fcs_valid`) or "drop" invalid frames. Defaults to "ignore".
 
     Returns:
         PyBfaBatch: Batch of BFA data, including metadata, timestamps, token numbers, and padded BFA angles.
     """
     ...
 
-def bfa_to_bfm(bfa: PyBfaData) -> PyBfmData:
+def bfa_to_bfm(bfa: PyBfaData, single_precision: bool = False) -> PyBfmData:
     """
     Convert Beamforming Feedback Angles to Beamforming Feedback Matrices.
 
     Args:
         bfa (PyBfaData): Beamforming feedback angle struct.
+        single_precision (bool): Return complex64 instead of complex128 matrices. Defaults to False.
 
     Returns:
         PyBfmData: Converted BFM struct.
     """
 
-def bfa_to_bfm_batch(bfa_batch: PyBfaBatch) -> PyBfmBatch:
+def bfa_to_bfm_batch(bfa_batch: PyBfaBatch, single_precision: bool = False) -> PyBfmBatch:
     """
     Convert Beamforming Feedback Angles to Beamforming Feedback Matrices
-    for a batch of data.
+    for a batch of data. The conversion runs on all available cores.
 
     Args:
         bfa (PyBfaBatch): Beamforming feedback angle batch
+        single_precision (bool): Return complex64 instead of complex128 matrices. Defaults to False.
 
     Returns:
         PyBfmBatch: Converted BFM batch
     """
+
+def bfa_to_radians(bfa: PyBfaData) -> ndarray:
+    """
+    Dequantize Beamforming Feedback Angles to radians.
+
+    Args:
+        bfa (PyBfaData): Beamforming feedback angle struct.
+
+    Returns:
+        ndarray: Angles in radians (float64) of shape (subcarriers, angles).
+    """
+
+def bfa_to_radians_batch(bfa_batch: PyBfaBatch) -> ndarray:
+    """
+    Dequantize Beamforming Feedback Angles to radians for a batch of data.
+
+    Padded angles beyond the antenna configuration of a packet are NaN.
+
+    Args:
+        bfa_batch (PyBfaBatch): Beamforming feedback angle batch
+
+    Returns:
+        ndarray: Angles in radians (float64) of shape (packets, subcarriers, angles).
+    """
@@ -1,9 +1,12 @@
 use beefi_lib::{
     create_live_capture, create_offline_capture, split_bfi_data, BfaData, BfiMetadata,
-    FeedbackMatrix, NectarSink, StreamBee,
+    BfmConversionError, BfmData, BfrpTrigger, CqiData, FcsPolicy, FeedbackMatrix, MacHeader, Ndpa,
+    NectarSink, Precision, RadioMetadata, StaInfo, StreamBee, TriggerUserInfo, WaggleSink, WaxSink,
+    WifiStandard,
 };
 use crossbeam_channel::{bounded, Receiver};
-use numpy::{Complex64, PyArray1, PyArray2, PyArray3, PyArray4};
+use ndarray::{s, Array2, Array3, Axis};
+use numpy::{Element, PyArray1, PyArray2, PyArray3, PyArray4, PyArrayMethods};
 use pyo3::{prelude::*, types::PyList};
 
 /**************************************************************************
@@ -14,6 +17,8 @@ use pyo3::{prelude::*, types::PyList};
 #[pyclass(get_all)]
 #[derive(Clone, Copy)]
 pub struct PyBfiMeta {
+    /// WiFi standard of the feedback (0=HT, 1=VHT, 2=HE, 3=EHT)
+    pub standard: u8,
     /// Channel bandwidth
     pub bandwidth: u16,
     /// Index of the receive antennas used in the sounding procedure
@@ -24,29 +29,173 @@ pub struct PyBfiMeta {
     pub codebook_info: u8,
     /// Feedback type (SU/MU/CQI)
     pub feedback_type: u8,
+    /// EHT partial bandwidth info (None for non-EHT feedback)
+    pub partial_bw_info: Option<u16>,
+    /// First RU26 of HE partial bandwidth feedback (None for non-HE feedback)
+    pub ru_start_index: Option<u8>,
+    /// Last RU26 of HE partial bandwidth feedback (None for non-HE feedback)
+    pub ru_end_index: Option<u8>,
 }
 
-/// BFA data (angles) extracted from a single packet
+/// Addressing and sequence information of the feedback frame
+#[pyclass(get_all)]
+#[derive(Clone, Copy)]
+pub struct PyMacHeader {
+    /// Receiver address (the beamformer)
+    pub receiver: [u8; 6],
+    /// Transmitter address (the beamformee sending the feedback)
+    pub transmitter: [u8; 6],
+    /// BSSID
+    pub bssid: [u8; 6],
+    /// Sequence number of the frame
+    pub sequence_number: u16,
+    /// Fragment number of the frame
+    pub fragment_number: u8,
+    /// Whether the frame is a retransmission
+    pub retry: bool,
+}
+
+/// Feedback requested from a single station in an NDP Announcement
+#[pyclass(get_all)]
+#[derive(Clone, Copy)]
+pub struct PyStaInfo {
+    /// Association ID of the beamformee (AID12 for VHT, AID11 for HE)
+    pub aid: u16,
+    /// Requested feedback type (0=SU, 1=MU, 2=CQI)
+    pub feedback_type: u8,
+    /// Requested subcarrier grouping Ng (None for VHT)
+    pub grouping: Option<u8>,
+    /// Requested codebook size (None for VHT)
+    pub codebook_info: Option<u8>,
+    /// Index of the number of columns (streams) requested
+    pub nc_index: u8,
+    /// First RU26 of the requested span (None for VHT)
+    pub ru_start_index: Option<u8>,
+    /// Last RU26 of the requested span (None for VHT)
+    pub ru_end_index: Option<u8>,
+}
+
+/// NDP Announcement that solicited a feedback report
+#[pyclass(get_all)]
+#[derive(Clone)]
+pub struct PyNdpa {
+    /// Timestamp of the associated pcap capture
+    pub timestamp: f64,
+    /// Address of the beamformer soliciting the feedback
+    pub beamformer: [u8; 6],
+    /// Sounding dialog token number identifying the sounding sequence
+    pub dialog_token: u8,
+    /// Standard of the announced sounding (1=VHT, 2=HE)
+    pub standard: u8,
+    /// Feedback requested from each station
+    pub sta_info: Vec<PyStaInfo>,
+}
+
+/// Feedback polled from a single station in a BFRP trigger
+#[pyclass(get_all)]
+#[derive(Clone, Copy)]
+pub struct PyTriggerUserInfo {
+    /// AID12 of the polled station
+    pub aid: u16,
+    /// RU Allocation subfield; B0 selects the 80 MHz segment, B1-B7 the RU
+    pub ru_allocation: u8,
+    /// Bitmap of the feedback segments requested (bit n for segment n)
+    pub feedback_segment_retransmission_bitmap: u8,
+}
+
+/// Beamforming Report Poll trigger collecting MU feedback
 #[pyclass(get_all)]
+#[derive(Clone)]
+pub struct PyBfrpTrigger {
+    /// Timestamp of the associated pcap capture
+    pub timestamp: f64,
+    /// Address of the beamformer polling the feedback
+    pub beamformer: [u8; 6],
+    /// Bandwidth of the PPDU carrying the feedback (in MHz)
+    pub ul_bandwidth: u16,
+    /// Feedback polled from each station
+    pub user_info: Vec<PyTriggerUserInfo>,
+}
+
+/// Reception information from the link layer header
+#[pyclass(get_all)]
+#[derive(Clone)]
+pub struct PyRadioMeta {
+    /// Value of the TSF timer when the first bit of the frame arrived (in µs)
+    pub tsft: Option<u64>,
+    /// Combined RF signal power at the antennas (in dBm)
+    pub antenna_signal: Option<i8>,
+    /// RF noise power at the antennas (in dBm)
+    pub antenna_noise: Option<i8>,
+    /// Channel center frequency (in MHz)
+    pub channel_freq: Option<u16>,
+    /// Channel flags (band, modulation)
+    pub channel_flags: Option<u16>,
+    /// RF signal power at the individual antennas (in dBm)
+    pub antenna_signals: Vec<i8>,
+}
+
+/// BFA data (angles) extracted from a single packet
+#[pyclass()]
 pub struct PyBfaData {
     /// Metadata of the extracted BFI data
+    #[pyo3(get)]
     pub metadata: Py<PyBfiMeta>,
+    /// Reception information of the packet
+    #[pyo3(get)]
+    pub radio: Py<PyRadioMeta>,
     /// Timestamp of the associated pcap capture
+    #[pyo3(get)]
     pub timestamp: f64,
+    /// MAC header of the feedback frame
+    #[pyo3(get)]
+    pub mac_header: Py<PyMacHeader>,
+    /// Result of the FCS verification (None if not verified)
+    #[pyo3(get)]
+    pub fcs_valid: Option<bool>,
+    /// NDP Announcement that solicited the report (None if not captured)
+    #[pyo3(get)]
+    pub ndpa: Option<Py<PyNdpa>>,
     /// Token number to identify the NDP packet used in the procedure
+    #[pyo3(get)]
     pub token_number: u8,
+    /// Average SNR (in dB) of each space-time stream
+    pub snr: Vec<f32>,
+    /// Delta SNR (in dB) per stream and subcarrier (only for MU feedback)
+    pub delta_snr: Option<Array2<i8>>,
+    /// Tone index of every reported subcarrier (if known for the standard)
+    pub subcarrier_indices: Option<Vec<i16>>,
     /// Extracted BFA angles from the compressed beamforming feedback information
-    pub bfa_angles: Vec<Vec<u16>>,
+    ///
+    /// The extracted buffer is handed to numpy as is, without copying.
+    #[pyo3(get)]
+    pub bfa_angles: Py<PyArray2<u16>>,
 }
 
 #[pymethods]
 impl PyBfaData {
-    /// When Python accesses `bfa_angles`, convert the native data to a NumPy array.
+    /// When Python accesses `snr`, convert the native data to a NumPy array.
+    #[getter]
+    pub fn snr(&self, py: Python<'_>) -> Py<PyArray1<f32>> {
+        numpy::PyArray1::from_vec(py, self.snr.clone())
+            .to_owned()
+            .into()
+    }
+
+    /// When Python accesses `delta_snr`, convert the native data to a NumPy array.
+    #[getter]
+    pub fn delta_snr(&self, py: Python<'_>) -> Option<Py<PyArray2<i8>>> {
+        self.delta_snr
+            .as_ref()
+            .map(|delta_snr| PyArray2::from_array(py, delta_snr).unbind())
+    }
+
+    /// When Python accesses `subcarrier_indices`, convert the native data to a NumPy array.
     #[getter]
-    pub fn bfa_angles(&self, py: Python) -> PyResult<Py<PyArray2<u16>>> {
-        PyArray2::from_vec2(py, &self.bfa_angles)
-            .map(|bound| bound.unbind())
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    pub fn subcarrier_indices(&self, py: Python<'_>) -> Option<Py<PyArray1<i16>>> {
+        self.subcarrier_indices
+            .as_ref()
+            .map(|indices| numpy::PyArray1::from_slice(py, indices).unbind())
     }
 }
 
@@ -56,23 +205,113 @@ pub struct PyBfmData {
     /// Metadata of the extracted BFI data
     #[pyo3(get)]
     pub metadata: Py<PyBfiMeta>,
+    /// Reception information of the packet
+    #[pyo3(get)]
+    pub radio: Py<PyRadioMeta>,
     /// Timestamp of the associated pcap capture
     #[pyo3(get)]
     pub timestamp: f64,
+    /// MAC header of the feedback frame
+    #[pyo3(get)]
+    pub mac_header: Py<PyMacHeader>,
+    /// Result of the FCS verification (None if not verified)
+    #[pyo3(get)]
+    pub fcs_valid: Option<bool>,
+    /// NDP Announcement that solicited the report (None if not captured)
+    #[pyo3(get)]
+    pub ndpa: Option<Py<PyNdpa>>,
     /// Token number to identify the NDP packet used in the procedure
     #[pyo3(get)]
     pub token_number: u8,
+    /// Average SNR (in dB) of each space-time stream
+    pub snr: Vec<f32>,
+    /// Delta SNR (in dB) per stream and subcarrier (only for MU feedback)
+    pub delta_snr: Option<Array2<i8>>,
+    /// Tone index of every subcarrier of the feedback matrix (if known)
+    pub subcarrier_indices: Option<Vec<i16>>,
     /// Extracted Beamforming Feedback Matrices stored as an ndarray of 3 dimensions
-    pub bfm: FeedbackMatrix,
+    pub bfm: PyFeedbackMatrix,
 }
 
 #[pymethods]
 impl PyBfmData {
+    /// Custom getter for `snr` that converts it into a NumPy array.
+    #[getter]
+    pub fn snr(&self, py: Python<'_>) -> Py<PyArray1<f32>> {
+        numpy::PyArray1::from_vec(py, self.snr.clone())
+            .to_owned()
+            .into()
+    }
+
+    /// Custom getter for `delta_snr` that converts the inner ndarray into a NumPy array.
+    #[getter]
+    pub fn delta_snr(&self, py: Python<'_>) -> Option<Py<PyArray2<i8>>> {
+        self.delta_snr
+            .as_ref()
+            .map(|delta_snr| numpy::PyArray2::from_array(py, delta_snr).unbind())
+    }
+
+    /// Custom getter for `subcarrier_indices` that converts it into a NumPy array.
+    #[getter]
+    pub fn subcarrier_indices(&self, py: Python<'_>) -> Option<Py<PyArray1<i16>>> {
+        self.subcarrier_indices
+            .as_ref()
+            .map(|indices| numpy::PyArray1::from_slice(py, indices).unbind())
+    }
+
     /// Custom getter for `bfm` that converts the inner ndarray into a NumPy array.
+    ///
+    /// The dtype is complex128 or complex64, depending on the requested precision.
     #[getter]
-    pub fn bfm(&self, py: Python<'_>) -> PyResult<Py<PyArray3<Complex64>>> {
-        let array = numpy::PyArray3::from_array(py, &self.bfm);
-        Ok(array.to_owned().into())
+    pub fn bfm(&self, py: Python<'_>) -> Py<PyAny> {
+        match &self.bfm {
+            PyFeedbackMatrix::Double(bfm) => PyArray3::from_array(py, bfm).into_any().unbind(),
+            PyFeedbackMatrix::Single(bfm) => PyArray3::from_array(py, bfm).into_any().unbind(),
+        }
+    }
+}
+
+/// Feedback matrices of a report in the precision requested from Python
+pub enum PyFeedbackMatrix {
+    /// complex128 matrices
+    Double(FeedbackMatrix),
+    /// complex64 matrices
+    Single(FeedbackMatrix<f32>),
+}
+
+/// Feedback matrices of a batch in the precision requested from Python
+pub enum PyFeedbackMatrices {
+    /// complex128 matrices
+    Double(Vec<FeedbackMatrix>),
+    /// complex64 matrices
+    Single(Vec<FeedbackMatrix<f32>>),
+}
+
+/// Precision of feedback matrices that can be handed to Python
+trait PyPrecision: Precision {
+    /// Wrap the matrices of a report
+    fn wrap(bfm: FeedbackMatrix<Self>) -> PyFeedbackMatrix;
+    /// Wrap the matrices of a batch
+    fn wrap_batch(bfm: Vec<FeedbackMatrix<Self>>) -> PyFeedbackMatrices;
+}
+
+impl PyPrecision for f64 {
+    fn wrap(bfm: FeedbackMatrix<Self>) -> PyFeedbackMatrix {
+        PyFeedbackMatrix::Double(bfm)
+    }
+
+    fn wrap_batch(bfm: Vec<FeedbackMatrix<Self>>) -> PyFeedbackMatrices {
+        PyFeedbackMatrices::Double(bfm)
+    }
+}
+
+impl PyPrecision for f32 {
+    fn wrap(bfm: FeedbackMatrix<Self>) -> PyFeedbackMatrix {
+        PyFeedbackMatrix::Single(bfm)
+    }
+
+    fn wrap_batch(bfm: Vec<FeedbackMatrix<Self>>) -> PyFeedbackMatrices {
+        PyFeedbackMatrices::Single(bfm)
     }
 }
 
@@ -81,12 +320,27 @@ impl PyBfmData {
 pub struct PyBfaBatch {
     /// A vector of metadata objects.
     pub metadata: Vec<PyBfiMeta>,
+    /// A vector of reception information objects.
+    pub radio: Vec<PyRadioMeta>,
     /// A vector of timestamps.
     pub timestamps: Vec<f64>,
+    /// A vector of MAC headers.
+    pub mac_header: Vec<PyMacHeader>,
+    /// A vector of FCS verification results.
+    pub fcs_valid: Vec<Option<bool>>,
+    /// A vector of soliciting NDP Announcements.
+    pub ndpa: Vec<Option<PyNdpa>>,
     /// A vector of token numbers.
     pub token_numbers: Vec<u8>,
-    /// 3D vector representing the extracted BFA angles.
-    pub bfa_angles: Vec<Vec<Vec<u16>>>,
+    /// Per-stream average SNR (in dB) of each packet.
+    pub snr: Vec<Vec<f32>>,
+    /// Delta SNR (in dB) per stream and subcarrier of each packet.
+    pub delta_snr: Vec<Option<Array2<i8>>>,
+    /// Tone indices of the subcarriers of each packet.
+    pub subcarrier_indices: Vec<Option<Vec<i16>>>,
+    /// 3D array of the extracted (padded) BFA angles.
+    #[pyo3(get)]
+    pub bfa_angles: Py<PyArray3<u16>>,
 }
 
 #[pymethods]
@@ -101,6 +355,16 @@ impl PyBfaBatch {
         Ok(list.into())
     }
 
+    /// Getter for radio metadata as a Python list.
+    #[getter]
+    pub fn radio(&self, py: Python<'_>) -> PyResult<Py<PyList>> {
+        let list = PyList::empty(py);
+        for radio in &self.radio {
+            list.append(Py::new(py, radio.clone())?)?;
+        }
+        Ok(list.into())
+    }
+
     /// Getter for timestamps as a NumPy array.
     #[getter]
     pub fn timestamps(&self, py: Python<'_>) -> Py<PyArray1<f64>> {
@@ -110,6 +374,28 @@ impl PyBfaBatch {
             .into()
     }
 
+    /// Getter for MAC headers as a Python list.
+    #[getter]
+    pub fn mac_header(&self, py: Python<'_>) -> PyResult<Py<PyList>> {
+        let list = PyList::empty(py);
+        for header in &self.mac_header {
+            list.append(Py::new(py, *header)?)?;
+        }
+        Ok(list.into())
+    }
+
+    /// Getter for FCS verification results as a Python list.
+    #[getter]
+    pub fn fcs_valid(&self) -> Vec<Option<bool>> {
+        self.fcs_valid.clone()
+    }
+
+    /// Getter for soliciting NDP Announcements as a Python list.
+    #[getter]
+    pub fn ndpa(&self) -> Vec<Option<PyNdpa>> {
+        self.ndpa.clone()
+    }
+
     /// Getter for token_numbers as a NumPy array.
     #[getter]
     pub fn token_numbers(&self, py: Python<'_>) -> Py<PyArray1<u8>> {
@@ -118,12 +404,36 @@ impl PyBfaBatch {
             .into()
     }
 
-    /// Getter for bfa_angles as a 3D NumPy array.
+    /// Getter for snr as a list of per-packet SNR lists.
+    ///
+    /// Since the number of streams may vary between packets, this is not an array.
+    #[getter]
+    pub fn snr(&self) -> Vec<Vec<f32>> {
+        self.snr.clone()
+    }
+
+    /// Getter for delta_snr as a list of optional 2D NumPy arrays.
     #[getter]
-    pub fn bfa_angles(&self, py: Python<'_>) -> PyResult<Py<PyArray3<u16>>> {
-        numpy::PyArray3::from_vec3(py, &self.bfa_angles)
-            .map(|arr| arr.to_owned().into())
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    pub fn delta_snr(&self, py: Python<'_>) -> Vec<Option<Py<PyArray2<i8>>>> {
+        self.delta_snr
+            .iter()
+            .map(|d| {
+                d.as_ref()
+                    .map(|d| numpy::PyArray2::from_array(py, d).unbind())
+            })
+            .collect()
+    }
+
+    /// Getter for subcarrier_indices as a list of optional 1D NumPy arrays.
+    #[getter]
+    pub fn subcarrier_indices(&self, py: Python<'_>) -> Vec<Option<Py<PyArray1<i16>>>> {
+        self.subcarrier_indices
+            .iter()
+            .map(|i| {
+                i.as_ref()
+                    .map(|i| numpy::PyArray1::from_slice(py, i).unbind())
+            })
+            .collect()
     }
 }
 
@@ -132,12 +442,26 @@ impl PyBfaBatch {
 pub struct PyBfmBatch {
     /// A vector of metadata objects.
     pub metadata: Vec<PyBfiMeta>,
+    /// A vector of reception information objects.
+    pub radio: Vec<PyRadioMeta>,
     /// A vector of timestamps.
     pub timestamps: Vec<f64>,
+    /// A vector of MAC headers.
+    pub mac_header: Vec<PyMacHeader>,
+    /// A vector of FCS verification results.
+    pub fcs_valid: Vec<Option<bool>>,
+    /// A vector of soliciting NDP Announcements.
+    pub ndpa: Vec<Option<PyNdpa>>,
     /// A vector of token numbers.
     pub token_numbers: Vec<u8>,
+    /// Per-stream average SNR (in dB) of each packet.
+    pub snr: Vec<Vec<f32>>,
+    /// Delta SNR (in dB) per stream and subcarrier of each packet.
+    pub delta_snr: Vec<Option<Array2<i8>>>,
+    /// Tone indices of the subcarriers of each packet.
+    pub subcarrier_indices: Vec<Option<Vec<i16>>>,
     /// Vector of Beamforming Feedback Matrices
-    pub bfm: Vec<FeedbackMatrix>,
+    pub bfm: PyFeedbackMatrices,
 }
 
 /// Batch of BFM data
@@ -153,6 +477,16 @@ impl PyBfmBatch {
         Ok(list.into())
     }
 
+    /// Getter for radio metadata as a Python list.
+    #[getter]
+    pub fn radio(&self, py: Python<'_>) -> PyResult<Py<PyList>> {
+        let list = PyList::empty(py);
+        for radio in &self.radio {
+            list.append(Py::new(py, radio.clone())?)?;
+        }
+        Ok(list.into())
+    }
+
     /// Getter for timestamps as a NumPy array.
     #[getter]
     pub fn timestamps(&self, py: Python<'_>) -> Py<PyArray1<f64>> {
@@ -162,6 +496,28 @@ impl PyBfmBatch {
             .into()
     }
 
+    /// Getter for MAC headers as a Python list.
+    #[getter]
+    pub fn mac_header(&self, py: Python<'_>) -> PyResult<Py<PyList>> {
+        let list = PyList::empty(py);
+        for header in &self.mac_header {
+            list.append(Py::new(py, *header)?)?;
+        }
+        Ok(list.into())
+    }
+
+    /// Getter for FCS verification results as a Python list.
+    #[getter]
+    pub fn fcs_valid(&self) -> Vec<Option<bool>> {
+        self.fcs_valid.clone()
+    }
+
+    /// Getter for soliciting NDP Announcements as a Python list.
+    #[getter]
+    pub fn ndpa(&self) -> Vec<Option<PyNdpa>> {
+        self.ndpa.clone()
+    }
+
     /// Getter for token_numbers as a NumPy array.
     #[getter]
     pub fn token_numbers(&self, py: Python<'_>) -> Py<PyArray1<u8>> {
@@ -170,16 +526,92 @@ impl PyBfmBatch {
             .into()
     }
 
+    /// Getter for snr as a list of per-packet SNR lists.
+    ///
+    /// Since the number of streams may vary between packets, this is not an array.
+    #[getter]
+    pub fn snr(&self) -> Vec<Vec<f32>> {
+        self.snr.clone()
+    }
+
+    /// Getter for delta_snr as a list of optional 2D NumPy arrays.
+    #[getter]
+    pub fn delta_snr(&self, py: Python<'_>) -> Vec<Option<Py<PyArray2<i8>>>> {
+        self.delta_snr
+            .iter()
+            .map(|d| {
+                d.as_ref()
+                    .map(|d| numpy::PyArray2::from_array(py, d).unbind())
+            })
+            .collect()
+    }
+
+    /// Getter for subcarrier_indices as a list of optional 1D NumPy arrays.
+    #[getter]
+    pub fn subcarrier_indices(&self, py: Python<'_>) -> Vec<Option<Py<PyArray1<i16>>>> {
+        self.subcarrier_indices
+            .iter()
+            .map(|i| {
+                i.as_ref()
+                    .map(|i| numpy::PyArray1::from_slice(py, i).unbind())
+            })
+            .collect()
+    }
+
     /// Custom getter that converts the inner bfm Vec into a NumPy array.
+    ///
+    /// The dtype is complex128 or complex64, depending on the requested precision.
+    #[getter]
+    pub fn bfm(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        match &self.bfm {
+            PyFeedbackMatrices::Double(bfm) => stack_bfm(py, bfm),
+            PyFeedbackMatrices::Single(bfm) => stack_bfm(py, bfm),
+        }
+    }
+}
+
+/// CQI data (SNR per RU) extracted from a single packet
+#[pyclass()]
+pub struct PyCqiData {
+    /// Metadata of the extracted BFI data
+    #[pyo3(get)]
+    pub metadata: Py<PyBfiMeta>,
+    /// Reception information of the packet
+    #[pyo3(get)]
+    pub radio: Py<PyRadioMeta>,
+    /// Timestamp of the associated pcap capture
+    #[pyo3(get)]
+    pub timestamp: f64,
+    /// MAC header of the feedback frame
+    #[pyo3(get)]
+    pub mac_header: Py<PyMacHeader>,
+    /// Result of the FCS verification (None if not verified)
+    #[pyo3(get)]
+    pub fcs_valid: Option<bool>,
+    /// NDP Announcement that solicited the report (None if not captured)
+    #[pyo3(get)]
+    pub ndpa: Option<Py<PyNdpa>>,
+    /// Token number to identify the NDP packet used in the procedure
+    #[pyo3(get)]
+    pub token_number: u8,
+    /// Index of every reported RU26
+    pub ru_indices: Vec<u8>,
+    /// Average SNR (in dB) per stream and RU
+    pub snr: Array2<f32>,
+}
+
+#[pymethods]
+impl PyCqiData {
+    /// Custom getter for `ru_indices` that converts it into a NumPy array.
     #[getter]
-    pub fn bfm(&self, py: Python<'_>) -> PyResult<Py<PyArray4<Complex64>>> {
-        use ndarray::Axis;
-        // Each element in self.bfm is an Array3<Complex64>.
-        // We stack them along a new axis (axis 0) to get an Array4.
-        let views: Vec<_> = self.bfm.iter().map(|m| m.view()).collect();
-        let stacked = ndarray::stack(Axis(0), &views)
-            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
-        Ok(numpy::PyArray4::from_array(py, &stacked).to_owned().into())
+    pub fn ru_indices(&self, py: Python<'_>) -> Py<PyArray1<u8>> {
+        numpy::PyArray1::from_slice(py, &self.ru_indices).unbind()
+    }
+
+    /// Custom getter for `snr` that converts it into a NumPy array.
+    #[getter]
+    pub fn snr(&self, py: Python<'_>) -> Py<PyArray2<f32>> {
+        numpy::PyArray2::from_array(py, &self.snr).unbind()
     }
 }
 
@@ -192,8 +624,10 @@ impl PyBfmBatch {
 /// A little worker to read and process packets in a streaming fashion.
 #[pyclass(unsendable)]
 pub struct Bee {
-    bee: StreamBee,              // Internal CaptureBee instance
+    bee: StreamBee,                                  // Internal CaptureBee instance
     receiver: Receiver<BfaData>, // Receiver for BfaData messages from CaptureBee
+    trigger_receiver: Option<Receiver<BfrpTrigger>>, // Receiver for BFRP triggers (if enabled)
+    cqi_receiver: Option<Receiver<CqiData>>, // Receiver for CQI reports (if enabled)
 }
 
 /// Specifies the source of packet data
@@ -226,15 +660,24 @@ impl Bee {
     /// * `pcap_buffer` - Whether to buffer pcap packets internally for batch processing
     /// * `pcap_snaplen` - Snapshot length of pcap packets. Must exceed BFI packet length.
     /// * `pcap_bufsize` - Size of internal pcap packet buffer.
+    /// * `fcs_policy` - How to treat the FCS of frames (ignore, flag, drop)
+    /// * `triggers` - Whether to also collect BFRP triggers (see `poll_trigger`)
+    /// * `cqi` - Whether to also collect CQI reports (see `poll_cqi`)
     #[new]
-    #[pyo3(signature = (source, queue_size=1000, pcap_buffer=false, pcap_snaplen=4096, pcap_bufsize=1_000_000))]
+    #[pyo3(signature = (source, queue_size=1000, pcap_buffer=false, pcap_snaplen=4096, pcap_bufsize=1_000_000, fcs_policy="ignore", triggers=false, cqi=false))]
+    #[allow(clippy::too_many_arguments)] // Mirrors the Python keyword arguments
     pub fn new(
         source: DataSource,
         queue_size: Option<usize>,
         pcap_buffer: Option<bool>,
         pcap_snaplen: Option<i32>,
         pcap_bufsize: Option<i32>,
+        fcs_policy: &str,
+        triggers: bool,
+        cqi: bool,
     ) -> PyResult<Self> {
+        let fcs_policy = parse_fcs_policy(fcs_policy)?;
+
         // Set up the capture bee and queue
         let queue_size = queue_size.unwrap_or(1000);
         let (sender, receiver) = bounded(queue_size);
@@ -253,10 +696,26 @@ impl Bee {
         };
 
         // Attach the queue to CaptureBee to receive processed data and start receiving
+        bee.set_fcs_policy(fcs_policy);
         bee.subscribe_for_nectar(NectarSink::Queue(sender));
+        let trigger_receiver = triggers.then(|| {
+            let (sender, receiver) = bounded(queue_size);
+            bee.subscribe_for_waggles(WaggleSink::Queue(sender));
+            receiver
+        });
+        let cqi_receiver = cqi.then(|| {
+            let (sender, receiver) = bounded(queue_size);
+            bee.subscribe_for_wax(WaxSink::Queue(sender));
+            receiver
+        });
         bee.start_harvesting(false);
 
-        Ok(Bee { bee, receiver })
+        Ok(Bee {
+            bee,
+            receiver,
+            trigger_receiver,
+            cqi_receiver,
+        })
     }
 
     /// Polls the queue for new  and returns it if available, else None.
@@ -275,9 +734,19 @@ impl Bee {
                     py,
                     PyBfaData {
                         metadata: Py::new(py, PyBfiMeta::from(bfi_data.metadata))?,
+                        radio: Py::new(py, PyRadioMeta::from(bfi_data.radio))?,
                         timestamp: bfi_data.timestamp,
+                        mac_header: Py::new(py, PyMacHeader::from(bfi_data.mac_header))?,
+                        fcs_valid: bfi_data.fcs_valid,
+                        ndpa: bfi_data
+                            .ndpa
+                            .map(|ndpa| Py::new(py, PyNdpa::from(ndpa)))
+                            .transpose()?,
                         token_number: bfi_data.token_number,
-                        bfa_angles: bfi_data.bfa_angles,
+                        snr: bfi_data.snr,
+                        delta_snr: bfi_data.delta_snr,
+                        subcarrier_indices: bfi_data.subcarrier_indices,
+                        bfa_angles: PyArray2::from_owned_array(py, bfi_data.bfa_angles).unbind(),
                     },
                 )?;
 
@@ -287,6 +756,53 @@ impl Bee {
         }
     }
 
+    /// Polls the queue for new BFRP triggers and returns one if available, else None.
+    ///
+    /// Like `poll`, this function is nonblocking. It requires the bee to be
+    /// created with `triggers=True`.
+    pub fn poll_trigger(&self) -> PyResult<Option<PyBfrpTrigger>> {
+        let Some(receiver) = &self.trigger_receiver else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "Bee was created without trigger collection (triggers=False)",
+            ));
+        };
+        Ok(receiver.try_recv().ok().map(PyBfrpTrigger::from))
+    }
+
+    /// Polls the queue for new CQI reports and returns one if available, else None.
+    ///
+    /// Like `poll`, this function is nonblocking. It requires the bee to be
+    /// created with `cqi=True`.
+    pub fn poll_cqi(&self, py: Python) -> PyResult<Option<Py<PyCqiData>>> {
+        let Some(receiver) = &self.cqi_receiver else {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "Bee was created without CQI collection (cqi=False)",
+            ));
+        };
+        let Ok(cqi_data) = receiver.try_recv() else {
+            return Ok(None);
+        };
+
+        let py_cqi_data = Py::new(
+            py,
+            PyCqiData {
+                metadata: Py::new(py, PyBfiMeta::from(cqi_data.metadata))?,
+                radio: Py::new(py, PyRadioMeta::from(cqi_data.radio))?,
+                timestamp: cqi_data.timestamp,
+                mac_header: Py::new(py, PyMacHeader::from(cqi_data.mac_header))?,
+                fcs_valid: cqi_data.fcs_valid,
+                ndpa: cqi_data
+                    .ndpa
+                    .map(|ndpa| Py::new(py, PyNdpa::from(ndpa)))
+                    .transpose()?,
+                token_number: cqi_data.token_number,
+                ru_indices: cqi_data.ru_indices,
+                snr: cqi_data.snr,
+            },
+        )?;
+        Ok(Some(py_cqi_data))
+    }
+
     /// Stops the capture process
     ///
     /// This will exit all background threads and wrap up file usage.
@@ -317,12 +833,15 @@ fn beefi<'py>(_py: Python<'py>, m: &Bound<'py, PyModule>) -> PyResult<()> {
      *
      * # Parameters
      * * `path` - Path to pcap file to extract data from
+     * * `fcs_policy` - How to treat the FCS of frames (ignore, flag, drop)
      */
     #[allow(dead_code)]
     #[allow(clippy::type_complexity)] // Don't want to wrap and create owned struct
     #[pyfn(m)]
-    fn extract_from_pcap(_py: Python<'_>, path: &str) -> PyResult<PyBfaBatch> {
-        let data = beefi_lib::extract_from_pcap(path.into());
+    #[pyo3(signature = (path, fcs_policy="ignore"))]
+    fn extract_from_pcap(py: Python<'_>, path: &str, fcs_policy: &str) -> PyResult<PyBfaBatch> {
+        let fcs_policy = parse_fcs_policy(fcs_policy)?;
+        let data = beefi_lib::extract_from_pcap(path.into(), fcs_policy);
         let data_batch = split_bfi_data(data);
 
         // Since we are facing different bandwidth causing number of subcarrier
@@ -336,12 +855,32 @@ fn beefi<'py>(_py: Python<'py>, m: &Bound<'py, PyModule>) -> PyResult<()> {
             .into_iter()
             .map(PyBfiMeta::from)
             .collect::<Vec<PyBfiMeta>>();
+        let radio_list: Vec<PyRadioMeta> = data_batch
+            .radio
+            .into_iter()
+            .map(PyRadioMeta::from)
+            .collect();
 
         Ok(PyBfaBatch {
             timestamps: data_batch.timestamps,
+            mac_header: data_batch
+                .mac_header
+                .into_iter()
+                .map(PyMacHeader::from)
+                .collect(),
+            fcs_valid: data_batch.fcs_valid,
+            ndpa: data_batch
+                .ndpa
+                .into_iter()
+                .map(|ndpa| ndpa.map(PyNdpa::from))
+                .collect(),
             token_numbers: data_batch.token_numbers,
-            bfa_angles: padded_bfa_angles,
+            snr: data_batch.snr,
+            delta_snr: data_batch.delta_snr,
+            subcarrier_indices: data_batch.subcarrier_indices,
+            bfa_angles: PyArray3::from_owned_array(py, padded_bfa_angles).unbind(),
             metadata: meta_list,
+            radio: radio_list,
         })
     }
 
@@ -350,6 +889,7 @@ fn beefi<'py>(_py: Python<'py>, m: &Bound<'py, PyModule>) -> PyResult<()> {
      *
      * # Parameters
      * * `bfa` - Beamforming Feedback Angle Data
+     * * `single_precision` - Whether to return complex64 instead of complex128 matrices
      *
      * # Returns
      * Beamforming feedback matrix data
@@ -357,42 +897,46 @@ fn beefi<'py>(_py: Python<'py>, m: &Bound<'py, PyModule>) -> PyResult<()> {
     #[allow(dead_code)]
     #[allow(clippy::type_complexity)] // Don't want to wrap and create owned struct
     #[pyfn(m)]
-    fn bfa_to_bfm(py: Python<'_>, bfa: &PyBfaData) -> PyResult<PyBfmData> {
+    #[pyo3(signature = (bfa, single_precision=false))]
+    fn bfa_to_bfm(py: Python<'_>, bfa: &PyBfaData, single_precision: bool) -> PyResult<PyBfmData> {
         // --- Step 1. Convert PyBfaData (Python side) to internal BfaData ---
         // Borrow the inner metadata from the Py<> wrapper.
         let meta_py: &PyBfiMeta = &bfa.metadata.borrow(py);
-        let internal_metadata = BfiMetadata {
-            bandwidth: meta_py.bandwidth,
-            nr_index: meta_py.nr_index,
-            nc_index: meta_py.nc_index,
-            codebook_info: meta_py.codebook_info,
-            feedback_type: meta_py.feedback_type,
-        };
+        let internal_metadata = BfiMetadata::try_from(*meta_py)?;
+        let internal_radio = RadioMetadata::from(bfa.radio.borrow(py).clone());
 
         // Construct the internal BfaData
         let bfa_internal = beefi_lib::BfaData {
             metadata: internal_metadata,
+            radio: internal_radio,
             timestamp: bfa.timestamp,
+            mac_header: MacHeader::from(*bfa.mac_header.borrow(py)),
+            fcs_valid: bfa.fcs_valid,
+            ndpa: bfa
+                .ndpa
+                .as_ref()
+                .map(|ndpa| Ndpa::try_from(ndpa.borrow(py).clone()))
+                .transpose()?,
             token_number: bfa.token_number,
-            bfa_angles: bfa.bfa_angles.clone(),
+            snr: bfa.snr.clone(),
+            delta_snr: bfa.delta_snr.clone(),
+            subcarrier_indices: bfa.subcarrier_indices.clone(),
+            bfa_angles: bfa.bfa_angles.bind(py).to_owned_array(),
         };
 
         // --- Step 2. Convert BfaData to BfmData using your conversion function ---
-        let bfm_internal = beefi_lib::to_bfm(&bfa_internal).map_err(|e| {
-            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Conversion failed: {:?}", e))
-        })?;
-
         // --- Step 3. Convert internal BfmData to PyBfmData for Python ---
-        // Convert the metadata back into a PyBfiMeta.
-        let py_meta = Py::new(py, PyBfiMeta::from(bfm_internal.metadata))?;
-
-        // Create and return the PyBfmData instance.
-        Ok(PyBfmData {
-            metadata: py_meta,
-            timestamp: bfm_internal.timestamp,
-            token_number: bfm_internal.token_number,
-            bfm: bfm_internal.feedback_matrix,
-        })
+        let to_value_error = |e: BfmConversionError| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Conversion failed: {:?}", e))
+        };
+        if single_precision {
+            let bfm_internal =
+                beefi_lib::to_bfm_as::<f32>(&bfa_internal).map_err(to_value_error)?;
+            py_bfm_data(py, bfm_internal)
+        } else {
+            let bfm_internal = beefi_lib::to_bfm(&bfa_internal).map_err(to_value_error)?;
+            py_bfm_data(py, bfm_internal)
+        }
     }
 
     /**
@@ -400,130 +944,422 @@ fn beefi<'py>(_py: Python<'py>, m: &Bound<'py, PyModule>) -> PyResult<()> {
      *
      * # Parameters
      * * `bfa_batch` - Batch to process
+     * * `single_precision` - Whether to return complex64 instead of complex128 matrices
      *
      * # Returns
      * Batch of BFM data
      */
     #[pyfn(m)]
-    fn bfa_to_bfm_batch(_py: Python<'_>, bfa_batch: &PyBfaBatch) -> PyResult<PyBfmBatch> {
+    #[pyo3(signature = (bfa_batch, single_precision=false))]
+    fn bfa_to_bfm_batch(
+        py: Python<'_>,
+        bfa_batch: &PyBfaBatch,
+        single_precision: bool,
+    ) -> PyResult<PyBfmBatch> {
+        let bfa_angles = bfa_batch.bfa_angles.bind(py).readonly();
+        let bfa_angles = bfa_angles.as_array();
         let n = bfa_batch.metadata.len();
-        if n != bfa_batch.timestamps.len()
+        if n != bfa_batch.radio.len()
+            || n != bfa_batch.timestamps.len()
+            || n != bfa_batch.mac_header.len()
+            || n != bfa_batch.fcs_valid.len()
+            || n != bfa_batch.ndpa.len()
             || n != bfa_batch.token_numbers.len()
-            || n != bfa_batch.bfa_angles.len()
+            || n != bfa_batch.snr.len()
+            || n != bfa_batch.delta_snr.len()
+            || n != bfa_batch.subcarrier_indices.len()
+            || n != bfa_angles.len_of(Axis(0))
         {
             return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
                 "Input batch fields have mismatched lengths",
             ));
         }
 
-        let mut out_metadata = Vec::with_capacity(n);
-        let mut out_timestamps = Vec::with_capacity(n);
-        let mut out_token_numbers = Vec::with_capacity(n);
-        let mut out_bfm = Vec::with_capacity(n);
-
+        let mut bfa_internal = Vec::with_capacity(n);
         for i in 0..n {
             // Step 1: Convert PyBfiMeta (from PyBfaBatch) into internal BfiMetadata.
-            let meta_py: &PyBfiMeta = &bfa_batch.metadata[i];
-            let internal_metadata = beefi_lib::BfiMetadata {
-                bandwidth: meta_py.bandwidth,
-                nr_index: meta_py.nr_index,
-                nc_index: meta_py.nc_index,
-                codebook_info: meta_py.codebook_info,
-                feedback_type: meta_py.feedback_type,
-            };
+            let internal_metadata = BfiMetadata::try_from(bfa_batch.metadata[i])?;
 
             // Construct internal BfaData from the batch fields.
-            let bfa_internal = beefi_lib::BfaData {
+            bfa_internal.push(beefi_lib::BfaData {
                 metadata: internal_metadata,
+                radio: RadioMetadata::from(bfa_batch.radio[i].clone()),
                 timestamp: bfa_batch.timestamps[i],
+                mac_header: MacHeader::from(bfa_batch.mac_header[i]),
+                fcs_valid: bfa_batch.fcs_valid[i],
+                ndpa: bfa_batch.ndpa[i].clone().map(Ndpa::try_from).transpose()?,
                 token_number: bfa_batch.token_numbers[i],
-                bfa_angles: bfa_batch.bfa_angles[i].clone(),
-            };
-
-            // Step 2: Convert internal BfaData to internal BfmData.
-            let bfm_internal = beefi_lib::to_bfm(&bfa_internal).map_err(|e| {
-                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
-                    "Conversion failed: {:?}",
-                    e
-                ))
-            })?;
-
-            // Step 3: Convert internal BfmData back to PyBfmBatch fields.
-            // Create a new Python object for the metadata.
-            let py_meta: PyBfiMeta = PyBfiMeta::from(bfm_internal.metadata);
-            out_metadata.push(py_meta);
-            out_timestamps.push(bfm_internal.timestamp);
-            out_token_numbers.push(bfm_internal.token_number);
-            out_bfm.push(bfm_internal.feedback_matrix);
+                snr: bfa_batch.snr[i].clone(),
+                delta_snr: bfa_batch.delta_snr[i].clone(),
+                subcarrier_indices: bfa_batch.subcarrier_indices[i].clone(),
+                bfa_angles: bfa_angles.index_axis(Axis(0), i).to_owned(),
+            });
         }
 
-        Ok(PyBfmBatch {
-            metadata: out_metadata,
-            timestamps: out_timestamps,
-            token_numbers: out_token_numbers,
-            bfm: out_bfm,
-        })
+        // Step 2: Convert internal BfaData to internal BfmData on all cores.
+        // Step 3: Convert internal BfmData back to PyBfmBatch fields.
+        if single_precision {
+            py_bfm_batch(py.allow_threads(|| beefi_lib::to_bfm_batch_as::<f32>(&bfa_internal)))
+        } else {
+            py_bfm_batch(py.allow_threads(|| beefi_lib::to_bfm_batch(&bfa_internal)))
+        }
+    }
+
+    /**
+     * Dequantize Beamforming Feedback Angles to radians.
+     *
+     * # Parameters
+     * * `bfa` - Beamforming Feedback Angle Data
+     *
+     * # Returns
+     * Angles in radians of shape (subcarriers, angles)
+     */
+    #[pyfn(m)]
+    fn bfa_to_radians(py: Python<'_>, bfa: &PyBfaData) -> PyResult<Py<PyArray2<f64>>> {
+        let metadata = BfiMetadata::try_from(*bfa.metadata.borrow(py))?;
+        let bfa_internal = BfaData::from_angles(metadata, bfa.bfa_angles.bind(py).to_owned_array());
+        let radians = beefi_lib::dequantize(&bfa_internal).map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Conversion failed: {:?}", e))
+        })?;
+        Ok(PyArray2::from_owned_array(py, radians).unbind())
+    }
+
+    /**
+     * Dequantize a batch of Beamforming Feedback Angles to radians.
+     *
+     * The result has the (padded) shape of the angles of the batch. Padded
+     * angles beyond the antenna configuration of a packet are NaN.
+     *
+     * # Parameters
+     * * `bfa_batch` - Batch to process
+     *
+     * # Returns
+     * Angles in radians of shape (packets, subcarriers, angles)
+     */
+    #[pyfn(m)]
+    fn bfa_to_radians_batch(py: Python<'_>, bfa_batch: &PyBfaBatch) -> PyResult<Py<PyArray3<f64>>> {
+        let bfa_angles = bfa_batch.bfa_angles.bind(py).readonly();
+        let bfa_angles = bfa_angles.as_array();
+        if bfa_batch.metadata.len() != bfa_angles.len_of(Axis(0)) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Input batch fields have mismatched lengths",
+            ));
+        }
+
+        let mut radians = Array3::from_elem(bfa_angles.dim(), f64::NAN);
+        for (i, meta) in bfa_batch.metadata.iter().enumerate() {
+            let metadata = BfiMetadata::try_from(*meta)?;
+            let width = beefi_lib::num_angles(metadata.nr_index, metadata.nc_index)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+            if width > bfa_angles.len_of(Axis(2)) {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Batch carries fewer angles than its metadata requires",
+                ));
+            }
+            let packet_angles = bfa_angles.slice(s![i, .., ..width]).to_owned();
+            let packet_radians =
+                beefi_lib::dequantize(&BfaData::from_angles(metadata, packet_angles)).map_err(
+                    |e| {
+                        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                            "Conversion failed: {:?}",
+                            e
+                        ))
+                    },
+                )?;
+            radians
+                .slice_mut(s![i, .., ..width])
+                .assign(&packet_radians);
+        }
+        Ok(PyArray3::from_owned_array(py, radians).unbind())
     }
 
     m.add_class::<Bee>()?;
     m.add_class::<DataSource>()?;
     m.add_class::<PyBfaData>()?;
     m.add_class::<PyBfiMeta>()?;
+    m.add_class::<PyBfrpTrigger>()?;
+    m.add_class::<PyCqiData>()?;
+    m.add_class::<PyMacHeader>()?;
+    m.add_class::<PyNdpa>()?;
+    m.add_class::<PyRadioMeta>()?;
+    m.add_class::<PyStaInfo>()?;
+    m.add_class::<PyTriggerUserInfo>()?;
 
     Ok(())
 }
 
+/// Helper function to parse the FCS policy passed from Python
+fn parse_fcs_policy(policy: &str) -> PyResult<FcsPolicy> {
+    policy
+        .parse()
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)
+}
+
+/// Helper function to stack the matrices of a batch into a 4D NumPy array
+fn stack_bfm<E: Element + Clone>(py: Python<'_>, bfm: &[Array3<E>]) -> PyResult<Py<PyAny>> {
+    // Each element is an Array3; stack them along a new axis (axis 0) to get an Array4.
+    let views: Vec<_> = bfm.iter().map(|m| m.view()).collect();
+    let stacked = ndarray::stack(Axis(0), &views)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    Ok(PyArray4::from_owned_array(py, stacked).into_any().unbind())
+}
+
+/// Helper function to convert internal BFM data to its Python counterpart
+fn py_bfm_data<T: PyPrecision>(py: Python<'_>, bfm_internal: BfmData<T>) -> PyResult<PyBfmData> {
+    Ok(PyBfmData {
+        metadata: Py::new(py, PyBfiMeta::from(bfm_internal.metadata))?,
+        radio: Py::new(py, PyRadioMeta::from(bfm_internal.radio))?,
+        timestamp: bfm_internal.timestamp,
+        mac_header: Py::new(py, PyMacHeader::from(bfm_internal.mac_header))?,
+        fcs_valid: bfm_internal.fcs_valid,
+        ndpa: bfm_internal
+            .ndpa
+            .map(|ndpa| Py::new(py, PyNdpa::from(ndpa)))
+            .transpose()?,
+        token_number: bfm_internal.token_number,
+        snr: bfm_internal.snr,
+        delta_snr: bfm_internal.delta_snr,
+        subcarrier_indices: bfm_internal.subcarrier_indices,
+        bfm: T::wrap(bfm_internal.feedback_matrix),
+    })
+}
+
+/// Helper function to convert converted internal BFM data to a Python batch
+fn py_bfm_batch<T: PyPrecision>(
+    bfm_batch: Vec<Result<BfmData<T>, BfmConversionError>>,
+) -> PyResult<PyBfmBatch> {
+    let n = bfm_batch.len();
+    let mut out_metadata = Vec::with_capacity(n);
+    let mut out_radio = Vec::with_capacity(n);
+    let mut out_timestamps = Vec::with_capacity(n);
+    let mut out_mac_header = Vec::with_capacity(n);
+    let mut out_fcs_valid = Vec::with_capacity(n);
+    let mut out_ndpa = Vec::with_capacity(n);
+    let mut out_token_numbers = Vec::with_capacity(n);
+    let mut out_snr = Vec::with_capacity(n);
+    let mut out_delta_snr = Vec::with_capacity(n);
+    let mut out_subcarrier_indices = Vec::with_capacity(n);
+    let mut out_bfm = Vec::with_capacity(n);
+
+    for bfm_internal in bfm_batch {
+        let bfm_internal = bfm_internal.map_err(|e| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Conversion failed: {:?}", e))
+        })?;
+
+        out_metadata.push(PyBfiMeta::from(bfm_internal.metadata));
+        out_radio.push(PyRadioMeta::from(bfm_internal.radio));
+        out_timestamps.push(bfm_internal.timestamp);
+        out_mac_header.push(PyMacHeader::from(bfm_internal.mac_header));
+        out_fcs_valid.push(bfm_internal.fcs_valid);
+        out_ndpa.push(bfm_internal.ndpa.map(PyNdpa::from));
+        out_token_numbers.push(bfm_internal.token_number);
+        out_snr.push(bfm_internal.snr);
+        out_delta_snr.push(bfm_internal.delta_snr);
+        out_subcarrier_indices.push(bfm_internal.subcarrier_indices);
+        out_bfm.push(bfm_internal.feedback_matrix);
+    }
+
+    Ok(PyBfmBatch {
+        metadata: out_metadata,
+        radio: out_radio,
+        timestamps: out_timestamps,
+        mac_header: out_mac_header,
+        fcs_valid: out_fcs_valid,
+        ndpa: out_ndpa,
+        token_numbers: out_token_numbers,
+        snr: out_snr,
+        delta_snr: out_delta_snr,
+        subcarrier_indices: out_subcarrier_indices,
+        bfm: T::wrap_batch(out_bfm),
+    })
+}
+
 /// Helper function to pad the bfi data according to the longest number of subcarrier
-fn pad_bfa_angles(bfa_angles: &[Vec<Vec<u16>>]) -> Vec<Vec<Vec<u16>>> {
-    // Get the maximum length in both the second and third dimensions
-    // (determining the number of subcarrier and number of angels respectively)
-    let max_len_subcarrier = bfa_angles
-        .iter()
-        .map(|outer| outer.len())
-        .max()
-        .unwrap_or(0);
-
-    // Find the maximum length in the third dimension (inner vector lengths)
-    let max_len_angles = bfa_angles
-        .iter()
-        .flat_map(|outer| outer.iter().map(|inner| inner.len()))
-        .max()
-        .unwrap_or(0);
-
-    // Create a zero-filled template for inner padding
-    let zero_padded_inner = vec![0; max_len_angles];
-
-    // Pad each outer vector
-    bfa_angles
-        .iter()
-        .map(|outer| {
-            // Create a vector with padded inner vectors
-            let mut padded_outer: Vec<Vec<u16>> = outer
-                .iter()
-                .map(|inner| {
-                    // Create a new inner vector, padded if necessary
-                    let mut padded_inner = inner.clone();
-                    padded_inner.resize(max_len_angles, 0);
-                    padded_inner
-                })
-                .collect();
-
-            // Add zero-filled vectors if needed to reach `max_len_subcarrier`
-            padded_outer.resize_with(max_len_subcarrier, || zero_padded_inner.clone());
-
-            padded_outer
-        })
-        .collect()
+///
+/// The result is a single contiguous array of shape (num_packets, subcarriers, angles).
+fn pad_bfa_angles(bfa_angles: &[Array2<u16>]) -> Array3<u16> {
+    // Get the maximum length in both dimensions (determining the number of
+    // subcarrier and number of angles respectively)
+    let max_len_subcarrier = bfa_angles.iter().map(|a| a.nrows()).max().unwrap_or(0);
+    let max_len_angles = bfa_angles.iter().map(|a| a.ncols()).max().unwrap_or(0);
+
+    // Copy each packet into the zero-filled batch
+    let mut padded = Array3::zeros((bfa_angles.len(), max_len_subcarrier, max_len_angles));
+    for (mut packet, angles) in padded.outer_iter_mut().zip(bfa_angles) {
+        packet
+            .slice_mut(s![..angles.nrows(), ..angles.ncols()])
+            .assign(angles);
+    }
+    padded
 }
 
 impl From<BfiMetadata> for PyBfiMeta {
     fn from(metadata: BfiMetadata) -> Self {
         PyBfiMeta {
+            standard: metadata.standard as u8,
+            bandwidth: metadata.bandwidth,
+            nr_index: metadata.nr_index,
+            nc_index: metadata.nc_index,
+            codebook_info: metadata.codebook_info,
+            feedback_type: metadata.feedback_type,
+            partial_bw_info: metadata.partial_bw_info,
+            ru_start_index: metadata.ru_start_index,
+            ru_end_index: metadata.ru_end_index,
+        }
+    }
+}
+
+impl TryFrom<PyBfiMeta> for BfiMetadata {
+    type Error = PyErr;
+
+    fn try_from(metadata: PyBfiMeta) -> Result<Self, Self::Error> {
+        let standard = WifiStandard::try_from(metadata.standard)
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+
+        Ok(BfiMetadata {
+            standard,
             bandwidth: metadata.bandwidth,
             nr_index: metadata.nr_index,
             nc_index: metadata.nc_index,
             codebook_info: metadata.codebook_info,
             feedback_type: metadata.feedback_type,
+            partial_bw_info: metadata.partial_bw_info,
+            ru_start_index: metadata.ru_start_index,
+            ru_end_index: metadata.ru_end_index,
+        })
+    }
+}
+
+impl From<RadioMetadata> for PyRadioMeta {
+    fn from(radio: RadioMetadata) -> Self {
+        PyRadioMeta {
+            tsft: radio.tsft,
+            antenna_signal: radio.antenna_signal,
+            antenna_noise: radio.antenna_noise,
+            channel_freq: radio.channel_freq,
+            channel_flags: radio.channel_flags,
+            antenna_signals: radio.antenna_signals,
+        }
+    }
+}
+
+impl From<PyRadioMeta> for RadioMetadata {
+    fn from(radio: PyRadioMeta) -> Self {
+        RadioMetadata {
+            tsft: radio.tsft,
+            antenna_signal: radio.antenna_signal,
+            antenna_noise: radio.antenna_noise,
+            channel_freq: radio.channel_freq,
+            channel_flags: radio.channel_flags,
+            antenna_signals: radio.antenna_signals,
+        }
+    }
+}
+
+impl From<MacHeader> for PyMacHeader {
+    fn from(header: MacHeader) -> Self {
+        PyMacHeader {
+            receiver: header.receiver,
+            transmitter: header.transmitter,
+            bssid: header.bssid,
+            sequence_number: header.sequence_number,
+            fragment_number: header.fragment_number,
+            retry: header.retry,
+        }
+    }
+}
+
+impl From<PyMacHeader> for MacHeader {
+    fn from(header: PyMacHeader) -> Self {
+        MacHeader {
+            receiver: header.receiver,
+            transmitter: header.transmitter,
+            bssid: header.bssid,
+            sequence_number: header.sequence_number,
+            fragment_number: header.fragment_number,
+            retry: header.retry,
+        }
+    }
+}
+
+impl From<StaInfo> for PyStaInfo {
+    fn from(info: StaInfo) -> Self {
+        PyStaInfo {
+            aid: info.aid,
+            feedback_type: info.feedback_type,
+            grouping: info.grouping,
+            codebook_info: info.codebook_info,
+            nc_index: info.nc_index,
+            ru_start_index: info.ru_start_index,
+            ru_end_index: info.ru_end_index,
+        }
+    }
+}
+
+impl From<PyStaInfo> for StaInfo {
+    fn from(info: PyStaInfo) -> Self {
+        StaInfo {
+            aid: info.aid,
+            feedback_type: info.feedback_type,
+            grouping: info.grouping,
+            codebook_info: info.codebook_info,
+            nc_index: info.nc_index,
+            ru_start_index: info.ru_start_index,
+            ru_end_index: info.ru_end_index,
+        }
+    }
+}
+
+impl From<Ndpa> for PyNdpa {
+    fn from(ndpa: Ndpa) -> Self {
+        PyNdpa {
+            timestamp: ndpa.timestamp,
+            beamformer: ndpa.beamformer,
+            dialog_token: ndpa.dialog_token,
+            standard: ndpa.standard as u8,
+            sta_info: ndpa.sta_info.into_iter().map(PyStaInfo::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<PyNdpa> for Ndpa {
+    type Error = PyErr;
+
+    fn try_from(ndpa: PyNdpa) -> Result<Self, Self::Error> {
+        let standard = WifiStandard::try_from(ndpa.standard)
+            .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+
+        Ok(Ndpa {
+            timestamp: ndpa.timestamp,
+            beamformer: ndpa.beamformer,
+            dialog_token: ndpa.dialog_token,
+            standard,
+            sta_info: ndpa.sta_info.into_iter().map(StaInfo::from).collect(),
+        })
+    }
+}
+
+impl From<TriggerUserInfo> for PyTriggerUserInfo {
+    fn from(info: TriggerUserInfo) -> Self {
+        PyTriggerUserInfo {
+            aid: info.aid,
+            ru_allocation: info.ru_allocation,
+            feedback_segment_retransmission_bitmap: info.feedback_segment_retransmission_bitmap,
+        }
+    }
+}
+
+impl From<BfrpTrigger> for PyBfrpTrigger {
+    fn from(trigger: BfrpTrigger) -> Self {
+        PyBfrpTrigger {
+            timestamp: trigger.timestamp,
+            beamformer: trigger.beamformer,
+            ul_bandwidth: trigger.ul_bandwidth.to_mhz(),
+            user_info: trigger
+                .user_info
+                .into_iter()
+                .map(PyTriggerUserInfo::from)
+                .collect(),
         }
     }
 }